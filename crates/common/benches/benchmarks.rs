@@ -0,0 +1,46 @@
+use common::geometry::Point3;
+use common::spatial::KdTree;
+use common::{graph, interval};
+use divan::{Bencher, black_box};
+
+fn main() {
+    divan::main();
+}
+
+#[divan::bench]
+fn interval_set_collect_bench(bencher: Bencher) {
+    bencher
+        .with_inputs(|| {
+            (0..10_000_u64)
+                .map(|i| interval::Interval::new(i * 7 % 5_000, i * 7 % 5_000 + 10))
+                .collect::<Vec<_>>()
+        })
+        .bench_values(|intervals| intervals.into_iter().collect::<interval::IntervalSet<_>>());
+}
+
+#[divan::bench]
+fn bfs_distance_bench() -> Option<usize> {
+    graph::bfs_distance(
+        0_u32,
+        |&n| [n ^ 0b1011, n ^ 0b0110, n ^ 0b1101_0001, n ^ 0b10_0000_0000],
+        |&n| n == black_box(0b10_1101_1100),
+    )
+}
+
+#[divan::bench]
+fn closest_pairs_bench(bencher: Bencher) {
+    bencher
+        .with_inputs(|| {
+            let points: Vec<Point3> = (0..1_000_i64)
+                .map(|i| {
+                    Point3::new(
+                        i * 7_919 % 10_007,
+                        i * 104_729 % 10_007,
+                        i * 1_299_709 % 10_007,
+                    )
+                })
+                .collect();
+            KdTree::new(&points)
+        })
+        .bench_refs(|tree| tree.closest_pairs().take(1_000).count());
+}
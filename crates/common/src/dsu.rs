@@ -0,0 +1,475 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Disjoint-set union over the indices `0..n`, with path compression and
+/// union by size.
+#[derive(Debug, Clone)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl Dsu {
+    /// Creates `n` singleton sets.
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Adds a new singleton set and returns its index.
+    pub fn push(&mut self) -> usize {
+        let index = self.parent.len();
+        self.parent.push(index);
+        self.size.push(1);
+        self.components += 1;
+        index
+    }
+
+    /// Returns the representative of the set containing `i`.
+    ///
+    /// Runs iteratively in two passes (locate the root, then point every node
+    /// on the path straight at it), so long chains cannot overflow the stack.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut current = i;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// Merges the sets containing `i` and `j`. Returns `false` when they were
+    /// already the same set.
+    pub fn union(&mut self, i: usize, j: usize) -> bool {
+        let root_i = self.find(i);
+        let root_j = self.find(j);
+
+        if root_i == root_j {
+            return false;
+        }
+
+        let (big, small) = if self.size[root_i] > self.size[root_j] {
+            (root_i, root_j)
+        } else {
+            (root_j, root_i)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.components -= 1;
+        true
+    }
+
+    /// Whether `i` and `j` belong to the same set.
+    pub fn same(&mut self, i: usize, j: usize) -> bool {
+        self.find(i) == self.find(j)
+    }
+
+    /// Number of elements in the set containing `i`.
+    pub fn size_of(&mut self, i: usize) -> usize {
+        let root = self.find(i);
+        self.size[root]
+    }
+
+    /// Number of disjoint sets, tracked incrementally.
+    #[must_use]
+    pub const fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Sizes of every set, in order of their representative's index.
+    #[must_use]
+    pub fn component_sizes(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&i| self.parent[i] == i)
+            .map(|i| self.size[i])
+            .collect()
+    }
+
+    /// Members of every set. Sets are ordered by their smallest element and
+    /// members are listed in ascending order.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut slot_of_root = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for i in 0..self.len() {
+            let root = self.find(i);
+            let slot = *slot_of_root.entry(root).or_insert_with(|| {
+                groups.push(Vec::with_capacity(self.size[root]));
+                groups.len() - 1
+            });
+            groups[slot].push(i);
+        }
+        groups
+    }
+}
+
+/// A disjoint-set union whose merges can be undone in LIFO order.
+///
+/// Path compression is omitted so every union touches exactly two slots;
+/// union by size alone keeps `find` at `O(log n)`.
+#[derive(Debug, Clone)]
+pub struct RollbackDsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+    /// Absorbed roots, one per successful union.
+    history: Vec<usize>,
+}
+
+impl RollbackDsu {
+    /// Creates `n` singleton sets.
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+            history: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Returns the representative of the set containing `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    #[must_use]
+    pub fn find(&self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Merges the sets containing `i` and `j`. Returns `false` (and records
+    /// nothing) when they were already the same set.
+    pub fn union(&mut self, i: usize, j: usize) -> bool {
+        let root_i = self.find(i);
+        let root_j = self.find(j);
+
+        if root_i == root_j {
+            return false;
+        }
+
+        let (big, small) = if self.size[root_i] > self.size[root_j] {
+            (root_i, root_j)
+        } else {
+            (root_j, root_i)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.components -= 1;
+        self.history.push(small);
+        true
+    }
+
+    #[must_use]
+    pub fn same(&self, i: usize, j: usize) -> bool {
+        self.find(i) == self.find(j)
+    }
+
+    #[must_use]
+    pub fn size_of(&self, i: usize) -> usize {
+        self.size[self.find(i)]
+    }
+
+    #[must_use]
+    pub const fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// A marker for the current state, to be passed to [`Self::rollback`].
+    #[must_use]
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Reverts the most recent successful union. Returns `false` when there
+    /// is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(small) = self.history.pop() else {
+            return false;
+        };
+        let big = self.parent[small];
+        self.size[big] -= self.size[small];
+        self.parent[small] = small;
+        self.components += 1;
+        true
+    }
+
+    /// Reverts every union made since `snapshot` was taken.
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+}
+
+/// A disjoint-set union over arbitrary hashable keys.
+///
+/// Keys are assigned dense indices on first sight and backed by a [`Dsu`].
+#[derive(Debug, Clone)]
+pub struct KeyedDsu<K> {
+    index: HashMap<K, usize>,
+    keys: Vec<K>,
+    dsu: Dsu,
+}
+
+impl<K> Default for KeyedDsu<K> {
+    fn default() -> Self {
+        Self {
+            index: HashMap::new(),
+            keys: Vec::new(),
+            dsu: Dsu::new(0),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> KeyedDsu<K> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the index of `key`, adding it as a singleton set if unseen.
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(&i) = self.index.get(&key) {
+            return i;
+        }
+        let i = self.dsu.push();
+        self.keys.push(key.clone());
+        self.index.insert(key, i);
+        i
+    }
+
+    /// The dense index assigned to `key`, if it has been seen.
+    #[must_use]
+    pub fn index_of(&self, key: &K) -> Option<usize> {
+        self.index.get(key).copied()
+    }
+
+    /// Returns the representative key of the set containing `key`.
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let i = self.index_of(key)?;
+        let root = self.dsu.find(i);
+        Some(&self.keys[root])
+    }
+
+    /// Merges the sets containing `a` and `b`, inserting either key if
+    /// unseen. Returns `false` when they were already the same set.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let i = self.insert(a);
+        let j = self.insert(b);
+        self.dsu.union(i, j)
+    }
+
+    /// Whether `a` and `b` are both known and belong to the same set.
+    pub fn same(&mut self, a: &K, b: &K) -> bool {
+        match (self.index_of(a), self.index_of(b)) {
+            (Some(i), Some(j)) => self.dsu.same(i, j),
+            _ => false,
+        }
+    }
+
+    /// Number of keys in the set containing `key`, or `0` if unseen.
+    pub fn size_of(&mut self, key: &K) -> usize {
+        self.index_of(key).map_or(0, |i| self.dsu.size_of(i))
+    }
+
+    #[must_use]
+    pub const fn component_count(&self) -> usize {
+        self.dsu.component_count()
+    }
+
+    /// Members of every set, in insertion order.
+    pub fn components(&mut self) -> Vec<Vec<&K>> {
+        self.dsu
+            .components()
+            .into_iter()
+            .map(|group| group.into_iter().map(|i| &self.keys[i]).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dsu_initialization() {
+        let dsu = Dsu::new(3);
+
+        assert_eq!(dsu.parent, vec![0, 1, 2]);
+        assert_eq!(dsu.size, vec![1, 1, 1]);
+        assert_eq!(dsu.component_count(), 3);
+    }
+
+    #[test]
+    fn test_dsu_simple_union() {
+        let mut dsu = Dsu::new(2);
+
+        assert!(dsu.union(0, 1), "Should be success for the first time");
+        assert!(!dsu.union(0, 1), "Should fail when duplicate");
+        assert!(!dsu.union(1, 0), "Should fail when duplicate");
+        assert_eq!(dsu.component_count(), 1);
+    }
+
+    #[test]
+    fn test_dsu_union_by_size_logic() {
+        let mut dsu = Dsu::new(10);
+
+        dsu.union(0, 1);
+        dsu.union(0, 2);
+        let root_a = dsu.find(0);
+        assert_eq!(dsu.size[root_a], 3);
+
+        let root_b = dsu.find(3);
+        assert_eq!(dsu.size[root_b], 1);
+
+        dsu.union(3, 0);
+        assert_eq!(
+            dsu.parent[root_b], root_a,
+            "Small root should point to big root"
+        );
+        assert_eq!(dsu.parent[root_a], root_a, "Big root should remain root");
+        assert_eq!(dsu.size_of(3), 4);
+    }
+
+    #[test]
+    fn test_dsu_path_compression() {
+        let mut dsu = Dsu::new(5);
+        // 0 -> 1 -> 2 -> 3 -> 4 (Root)
+        dsu.parent = vec![1, 2, 3, 4, 4];
+        assert_eq!(dsu.find(0), 4);
+        assert_eq!(dsu.parent, vec![4, 4, 4, 4, 4]);
+    }
+
+    #[test]
+    fn test_dsu_find_deep_chain() {
+        // A recursive find would overflow the stack here.
+        let n = 1_000_000;
+        let mut dsu = Dsu::new(n);
+        dsu.parent = (1..=n).collect();
+        dsu.parent[n - 1] = n - 1;
+        assert_eq!(dsu.find(0), n - 1);
+        assert_eq!(dsu.parent[n / 2], n - 1);
+    }
+
+    #[test]
+    fn test_dsu_components() {
+        let mut dsu = Dsu::new(6);
+        dsu.union(4, 1);
+        dsu.union(2, 5);
+        dsu.union(5, 1);
+
+        assert_eq!(dsu.component_count(), 3);
+        assert_eq!(dsu.components(), vec![vec![0], vec![1, 2, 4, 5], vec![3]]);
+
+        let mut sizes = dsu.component_sizes();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 1, 4]);
+    }
+
+    #[test]
+    fn test_dsu_push() {
+        let mut dsu = Dsu::new(1);
+        let i = dsu.push();
+        assert_eq!(i, 1);
+        assert_eq!(dsu.component_count(), 2);
+        assert!(dsu.union(0, i));
+        assert!(dsu.same(1, 0));
+    }
+
+    #[test]
+    fn test_rollback_dsu_undo() {
+        let mut dsu = RollbackDsu::new(4);
+        dsu.union(0, 1);
+        let snapshot = dsu.snapshot();
+
+        dsu.union(2, 3);
+        assert!(!dsu.union(1, 0), "Failed unions are not recorded");
+        dsu.union(1, 3);
+        assert_eq!(dsu.component_count(), 1);
+        assert_eq!(dsu.size_of(2), 4);
+
+        dsu.rollback(snapshot);
+        assert_eq!(dsu.component_count(), 3);
+        assert!(dsu.same(0, 1));
+        assert!(!dsu.same(2, 3));
+        assert_eq!(dsu.size_of(0), 2);
+        assert_eq!(dsu.size_of(3), 1);
+
+        assert!(dsu.undo());
+        assert!(!dsu.same(0, 1));
+        assert!(!dsu.undo());
+    }
+
+    #[test]
+    fn test_keyed_dsu() {
+        let mut dsu = KeyedDsu::new();
+        assert!(dsu.union("a", "b"));
+        assert!(dsu.union("c", "d"));
+        assert!(!dsu.union("b", "a"));
+        dsu.insert("e");
+
+        assert_eq!(dsu.len(), 5);
+        assert_eq!(dsu.component_count(), 3);
+        assert!(dsu.same(&"a", &"b"));
+        assert!(!dsu.same(&"a", &"c"));
+        assert!(!dsu.same(&"a", &"z"), "Unknown keys are never joined");
+        assert_eq!(dsu.size_of(&"d"), 2);
+        assert_eq!(dsu.find(&"z"), None);
+
+        assert!(dsu.union("b", "d"));
+        let root = dsu.find(&"c").copied();
+        assert_eq!(dsu.find(&"a").copied(), root);
+        assert_eq!(
+            dsu.components(),
+            vec![vec![&"a", &"b", &"c", &"d"], vec![&"e"]]
+        );
+    }
+}
@@ -0,0 +1,568 @@
+//! Exact cover by Knuth's Algorithm X on dancing links.
+//!
+//! Rows are subsets of columns. Primary columns must be covered exactly
+//! once, or exactly as often as their multiplicity says; secondary columns
+//! at most once, which is what packing problems need for cells that may stay
+//! empty. The links live in one `Vec` and refer to each other by index.
+
+use thiserror::Error;
+
+/// The search stopped after trying its allotted number of rows.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("gave up after trying {0} rows")]
+pub struct BudgetExceeded(pub u64);
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    left: usize,
+    right: usize,
+    up: usize,
+    down: usize,
+    /// Header node of the node's column.
+    column: usize,
+    /// Row the node belongs to; unused for headers.
+    row: usize,
+}
+
+/// An exact cover problem over `primary + secondary` columns, with columns
+/// `0..primary` primary and the rest secondary.
+#[derive(Debug, Clone)]
+pub struct ExactCover {
+    /// Node 0 is the root, nodes `1..=columns` the column headers, and every
+    /// row's nodes follow.
+    nodes: Vec<Node>,
+    sizes: Vec<usize>,
+    /// Times each column still has to be covered, by header.
+    needs: Vec<usize>,
+    primary: usize,
+    rows: usize,
+    budget: Option<u64>,
+}
+
+const ROOT: usize = 0;
+
+impl ExactCover {
+    #[must_use]
+    pub fn new(primary: usize, secondary: usize) -> Self {
+        let columns = primary + secondary;
+        // Only primary headers join the root's list, so the search never
+        // has to cover a secondary column.
+        let nodes = (0..=columns)
+            .map(|i| {
+                let (left, right) = match i {
+                    _ if i > primary => (i, i),
+                    0 => (primary, usize::from(primary > 0)),
+                    _ if i == primary => (i - 1, ROOT),
+                    _ => (i - 1, i + 1),
+                };
+                Node {
+                    left,
+                    right,
+                    up: i,
+                    down: i,
+                    column: i,
+                    row: usize::MAX,
+                }
+            })
+            .collect();
+        Self {
+            nodes,
+            sizes: vec![0; columns + 1],
+            needs: vec![1; columns + 1],
+            primary,
+            rows: 0,
+            budget: None,
+        }
+    }
+
+    /// Limits [`solve`](Self::solve) to trying at most `budget` rows.
+    #[must_use]
+    pub fn with_node_budget(mut self, budget: u64) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Requires primary column `column` to be covered exactly `count` times.
+    ///
+    /// When the search branches on the column, its rows are picked in the
+    /// order they were added, so `count` interchangeable items are placed
+    /// once rather than once per permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `column` is not primary, `count` is zero, or rows have
+    /// already been added.
+    pub fn set_multiplicity(&mut self, column: usize, count: usize) {
+        assert!(column < self.primary, "column {column} is not primary");
+        assert!(count > 0, "a primary column is covered at least once");
+        assert!(self.is_empty(), "multiplicities are set before any row");
+        self.needs[column + 1] = count;
+    }
+
+    #[must_use]
+    pub fn columns(&self) -> usize {
+        self.sizes.len() - 1
+    }
+
+    #[must_use]
+    pub fn primary(&self) -> usize {
+        self.primary
+    }
+
+    /// Number of rows added so far.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Adds a row covering `columns` and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a column is out of range.
+    pub fn add_row(&mut self, columns: &[usize]) -> usize {
+        let row = self.rows;
+        self.rows += 1;
+        let first = self.nodes.len();
+        for (k, &c) in columns.iter().enumerate() {
+            assert!(c < self.columns(), "column {c} out of range");
+            let header = c + 1;
+            let index = self.nodes.len();
+            let up = self.nodes[header].up;
+            self.nodes.push(Node {
+                left: if k == 0 { index } else { index - 1 },
+                right: first,
+                up,
+                down: header,
+                column: header,
+                row,
+            });
+            self.nodes[up].down = index;
+            self.nodes[header].up = index;
+            self.nodes[first].left = index;
+            if k > 0 {
+                self.nodes[index - 1].right = index;
+            }
+            self.sizes[header] += 1;
+        }
+        row
+    }
+
+    /// Finds a set of rows covering every primary column exactly once and
+    /// every secondary column at most once, as row indices in the order
+    /// they were chosen.
+    ///
+    /// The structure is restored afterwards, so it can be solved again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BudgetExceeded`] if the node budget runs out before the
+    /// search is decided.
+    pub fn solve(&mut self) -> Result<Option<Vec<usize>>, BudgetExceeded> {
+        let mut search = Search {
+            chosen: Vec::new(),
+            tried: 0,
+            budget: self.budget,
+        };
+        Ok(self.search(&mut search)?.then_some(search.chosen))
+    }
+
+    fn search(&mut self, state: &mut Search) -> Result<bool, BudgetExceeded> {
+        // The primary column with the fewest choices left fails fastest.
+        let mut best = None;
+        let mut c = self.nodes[ROOT].right;
+        while c != ROOT {
+            let (size, need) = (self.sizes[c], self.needs[c]);
+            if size < need {
+                return Ok(false);
+            }
+            let choices = size + 1 - need;
+            if best.is_none_or(|(fewest, _)| choices < fewest) {
+                best = Some((choices, c));
+            }
+            c = self.nodes[c].right;
+        }
+        let Some((_, column)) = best else {
+            return Ok(true);
+        };
+
+        if self.needs[column] == 1 {
+            self.branch_last(column, state)
+        } else {
+            self.branch_next(column, state)
+        }
+    }
+
+    /// Tries each row of a column that needs one more cover.
+    fn branch_last(&mut self, column: usize, state: &mut Search) -> Result<bool, BudgetExceeded> {
+        self.select(column);
+        let mut r = self.nodes[column].down;
+        let mut result = Ok(false);
+        while r != column {
+            if let Err(err) = state.try_row() {
+                result = Err(err);
+                break;
+            }
+            state.chosen.push(self.nodes[r].row);
+            self.for_each_right(r, Self::select);
+            let found = self.search(state);
+            self.for_each_left(r, Self::deselect);
+            match found {
+                Ok(false) => {
+                    state.chosen.pop();
+                    r = self.nodes[r].down;
+                }
+                other => {
+                    result = other;
+                    break;
+                }
+            }
+        }
+        self.deselect(column);
+        result
+    }
+
+    /// Tries each row of a column that needs several more covers. A tried
+    /// row stays hidden while its successors are tried, so the rows of the
+    /// column are only ever picked top to bottom.
+    fn branch_next(&mut self, column: usize, state: &mut Search) -> Result<bool, BudgetExceeded> {
+        self.needs[column] -= 1;
+        let mut hidden = Vec::new();
+        let mut r = self.nodes[column].down;
+        let mut result = Ok(false);
+        while r != column {
+            if let Err(err) = state.try_row() {
+                result = Err(err);
+                break;
+            }
+            self.hide(r);
+            hidden.push(r);
+            state.chosen.push(self.nodes[r].row);
+            self.for_each_right(r, Self::select);
+            let found = self.search(state);
+            self.for_each_left(r, Self::deselect);
+            match found {
+                Ok(false) => {
+                    state.chosen.pop();
+                    r = self.nodes[r].down;
+                }
+                other => {
+                    result = other;
+                    break;
+                }
+            }
+        }
+        for &r in hidden.iter().rev() {
+            self.unhide(r);
+        }
+        self.needs[column] += 1;
+        result
+    }
+
+    /// Counts one cover of `column`, removing the column with its rows once
+    /// it needs no more.
+    fn select(&mut self, column: usize) {
+        self.needs[column] -= 1;
+        if self.needs[column] == 0 {
+            self.cover(column);
+        }
+    }
+
+    /// Exactly undoes [`select`](Self::select).
+    fn deselect(&mut self, column: usize) {
+        if self.needs[column] == 0 {
+            self.uncover(column);
+        }
+        self.needs[column] += 1;
+    }
+
+    /// Unlinks every node of `node`'s row from its column.
+    fn hide(&mut self, node: usize) {
+        let mut j = node;
+        loop {
+            let Node {
+                up, down, column, ..
+            } = self.nodes[j];
+            self.nodes[up].down = down;
+            self.nodes[down].up = up;
+            self.sizes[column] -= 1;
+            j = self.nodes[j].right;
+            if j == node {
+                break;
+            }
+        }
+    }
+
+    /// Exactly undoes [`hide`](Self::hide).
+    fn unhide(&mut self, node: usize) {
+        let mut j = node;
+        loop {
+            j = self.nodes[j].left;
+            let Node {
+                up, down, column, ..
+            } = self.nodes[j];
+            self.nodes[up].down = j;
+            self.nodes[down].up = j;
+            self.sizes[column] += 1;
+            if j == node {
+                break;
+            }
+        }
+    }
+
+    /// Applies `f` to the columns of the other nodes in `node`'s row, left
+    /// to right.
+    fn for_each_right(&mut self, node: usize, f: fn(&mut Self, usize)) {
+        let mut j = self.nodes[node].right;
+        while j != node {
+            f(self, self.nodes[j].column);
+            j = self.nodes[j].right;
+        }
+    }
+
+    /// Like [`for_each_right`](Self::for_each_right), right to left.
+    fn for_each_left(&mut self, node: usize, f: fn(&mut Self, usize)) {
+        let mut j = self.nodes[node].left;
+        while j != node {
+            f(self, self.nodes[j].column);
+            j = self.nodes[j].left;
+        }
+    }
+
+    /// Removes a column header and every row through the column.
+    fn cover(&mut self, column: usize) {
+        let Node { left, right, .. } = self.nodes[column];
+        self.nodes[left].right = right;
+        self.nodes[right].left = left;
+
+        let mut i = self.nodes[column].down;
+        while i != column {
+            let mut j = self.nodes[i].right;
+            while j != i {
+                let Node { up, down, .. } = self.nodes[j];
+                self.nodes[up].down = down;
+                self.nodes[down].up = up;
+                self.sizes[self.nodes[j].column] -= 1;
+                j = self.nodes[j].right;
+            }
+            i = self.nodes[i].down;
+        }
+    }
+
+    /// Exactly undoes [`cover`](Self::cover).
+    fn uncover(&mut self, column: usize) {
+        let mut i = self.nodes[column].up;
+        while i != column {
+            let mut j = self.nodes[i].left;
+            while j != i {
+                let Node { up, down, .. } = self.nodes[j];
+                self.nodes[up].down = j;
+                self.nodes[down].up = j;
+                self.sizes[self.nodes[j].column] += 1;
+                j = self.nodes[j].left;
+            }
+            i = self.nodes[i].up;
+        }
+
+        let Node { left, right, .. } = self.nodes[column];
+        self.nodes[left].right = column;
+        self.nodes[right].left = column;
+    }
+}
+
+struct Search {
+    chosen: Vec<usize>,
+    tried: u64,
+    budget: Option<u64>,
+}
+
+impl Search {
+    /// Counts one more row tried against the budget.
+    fn try_row(&mut self) -> Result<(), BudgetExceeded> {
+        self.tried += 1;
+        match self.budget {
+            Some(budget) if self.tried > budget => Err(BudgetExceeded(budget)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn build(primary: usize, secondary: usize, rows: &[Vec<usize>]) -> ExactCover {
+        let mut problem = ExactCover::new(primary, secondary);
+        for row in rows {
+            problem.add_row(row);
+        }
+        problem
+    }
+
+    /// Whether `chosen` covers each primary column as often as `needs` says
+    /// and each secondary column at most once.
+    fn is_cover(needs: &[usize], secondary: usize, rows: &[Vec<usize>], chosen: &[usize]) -> bool {
+        let primary = needs.len();
+        let mut counts = vec![0; primary + secondary];
+        for &r in chosen {
+            for &c in &rows[r] {
+                counts[c] += 1;
+            }
+        }
+        counts[..primary] == *needs && counts[primary..].iter().all(|&n| n <= 1)
+    }
+
+    #[test]
+    fn test_knuth_example() {
+        // The matrix from "Dancing Links", with columns renumbered from 0.
+        let rows = vec![
+            vec![0, 3, 6],
+            vec![0, 3],
+            vec![3, 4, 6],
+            vec![2, 4, 5],
+            vec![1, 2, 5, 6],
+            vec![1, 6],
+        ];
+        let mut problem = build(7, 0, &rows);
+        let mut chosen = problem.solve().expect("no budget").expect("solvable");
+        chosen.sort_unstable();
+        assert_eq!(chosen, [1, 3, 5]);
+
+        // Solving leaves the links as they were.
+        let again = problem.solve().expect("no budget").expect("solvable");
+        assert!(is_cover(&[1; 7], 0, &rows, &again));
+    }
+
+    #[test]
+    fn test_secondary_columns_are_optional() {
+        // Both rows through secondary column 2 would collide.
+        let rows = vec![vec![0, 2], vec![1, 2], vec![1]];
+        let mut problem = build(2, 1, &rows);
+        let mut chosen = problem.solve().expect("no budget").expect("solvable");
+        chosen.sort_unstable();
+        assert_eq!(chosen, [0, 2]);
+
+        let mut blocked = build(2, 1, &rows[..2]);
+        assert_eq!(blocked.solve(), Ok(None));
+    }
+
+    #[test]
+    fn test_empty_problem_is_covered_by_nothing() {
+        let mut problem = ExactCover::new(0, 3);
+        assert_eq!(problem.solve(), Ok(Some(vec![])));
+    }
+
+    #[test]
+    fn test_node_budget() {
+        let rows = vec![vec![0], vec![1]];
+        let mut tight = build(2, 0, &rows).with_node_budget(1);
+        assert_eq!(tight.solve(), Err(BudgetExceeded(1)));
+
+        let mut enough = build(2, 0, &rows).with_node_budget(2);
+        assert_eq!(enough.solve(), Ok(Some(vec![0, 1])));
+
+        // A column no row covers fails before any row is tried.
+        let mut hopeless = build(3, 0, &rows).with_node_budget(0);
+        assert_eq!(hopeless.solve(), Ok(None));
+    }
+
+    #[test]
+    fn test_multiplicity() {
+        // Three dominoes and a monomino on a 1x7 strip, as column 0 (three
+        // dominoes) and column 1 (one monomino) over seven cells.
+        let mut problem = ExactCover::new(2, 7);
+        problem.set_multiplicity(0, 3);
+        let mut rows = Vec::new();
+        for cell in 0..6 {
+            rows.push(vec![0, 2 + cell, 3 + cell]);
+        }
+        for cell in 0..7 {
+            rows.push(vec![1, 2 + cell]);
+        }
+        for row in &rows {
+            problem.add_row(row);
+        }
+        let chosen = problem.solve().expect("no budget").expect("solvable");
+        assert!(is_cover(&[3, 1], 7, &rows, &chosen));
+
+        // Four dominoes don't fit next to the monomino.
+        let mut crowded = ExactCover::new(2, 7);
+        crowded.set_multiplicity(0, 4);
+        for row in &rows {
+            crowded.add_row(row);
+        }
+        assert_eq!(crowded.solve(), Ok(None));
+    }
+
+    fn brute_force(needs: &[usize], secondary: usize, rows: &[Vec<usize>]) -> bool {
+        (0..1u32 << rows.len()).any(|mask| {
+            let chosen: Vec<usize> = (0..rows.len()).filter(|&r| mask >> r & 1 == 1).collect();
+            is_cover(needs, secondary, rows, &chosen)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_solve_matches_brute_force(
+            primary in 0..5usize,
+            secondary in 0..3usize,
+            seeds in prop::collection::vec(prop::collection::vec(any::<prop::sample::Index>(), 1..4), 0..10),
+        ) {
+            let columns = primary + secondary;
+            prop_assume!(columns > 0);
+            let rows: Vec<Vec<usize>> = seeds
+                .iter()
+                .map(|row| {
+                    let mut row: Vec<usize> = row.iter().map(|i| i.index(columns)).collect();
+                    row.sort_unstable();
+                    row.dedup();
+                    row
+                })
+                .collect();
+            let mut problem = build(primary, secondary, &rows);
+            let found = problem.solve().expect("no budget");
+            let needs = vec![1; primary];
+            prop_assert_eq!(found.is_some(), brute_force(&needs, secondary, &rows));
+            if let Some(chosen) = found {
+                prop_assert!(is_cover(&needs, secondary, &rows, &chosen));
+            }
+        }
+
+        #[test]
+        fn prop_multiplicities_match_brute_force(
+            needs in prop::collection::vec(1..=3usize, 1..4),
+            secondary in 0..3usize,
+            seeds in prop::collection::vec(prop::collection::vec(any::<prop::sample::Index>(), 1..4), 0..12),
+        ) {
+            let primary = needs.len();
+            let rows: Vec<Vec<usize>> = seeds
+                .iter()
+                .map(|row| {
+                    let mut row: Vec<usize> =
+                        row.iter().map(|i| i.index(primary + secondary)).collect();
+                    row.sort_unstable();
+                    row.dedup();
+                    row
+                })
+                .collect();
+            let mut problem = ExactCover::new(primary, secondary);
+            for (column, &count) in needs.iter().enumerate() {
+                problem.set_multiplicity(column, count);
+            }
+            for row in &rows {
+                problem.add_row(row);
+            }
+            let found = problem.solve().expect("no budget");
+            prop_assert_eq!(found.is_some(), brute_force(&needs, secondary, &rows));
+            if let Some(chosen) = found {
+                prop_assert!(is_cover(&needs, secondary, &rows, &chosen));
+            }
+        }
+    }
+}
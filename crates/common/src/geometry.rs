@@ -0,0 +1,757 @@
+//! Integer points, segments and polygons.
+//!
+//! Everything is exact: orientation comes from cross products, areas are
+//! kept doubled so they stay integers, and point-in-polygon tells the
+//! boundary apart from the inside. Coordinates can be any signed integer
+//! type through [`Coord`], defaulting to `i64`.
+
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use thiserror::Error;
+
+/// A signed integer usable as a coordinate.
+pub trait Coord:
+    Copy
+    + Ord
+    + Hash
+    + Default
+    + Debug
+    + From<i8>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// The unsigned type of the same width, which holds any distance
+    /// between two coordinates.
+    type Unsigned: Copy
+        + Ord
+        + Hash
+        + Default
+        + Debug
+        + From<u8>
+        + Add<Output = Self::Unsigned>
+        + Sub<Output = Self::Unsigned>
+        + Div<Output = Self::Unsigned>
+        + Rem<Output = Self::Unsigned>;
+
+    fn abs_diff(self, other: Self) -> Self::Unsigned;
+}
+
+macro_rules! impl_coord {
+    ($($t:ty => $u:ty),*) => {$(
+        impl Coord for $t {
+            type Unsigned = $u;
+
+            fn abs_diff(self, other: Self) -> $u {
+                <$t>::abs_diff(self, other)
+            }
+        }
+    )*};
+}
+
+impl_coord!(i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+fn gcd<U: Copy + Default + PartialEq + Rem<Output = U>>(mut a: U, mut b: U) -> U {
+    while b != U::default() {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// A point on the integer plane, or the vector to it from the origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T = i64> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coord> Point2<T> {
+    #[must_use]
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// Taxicab distance `|dx| + |dy|`.
+    #[must_use]
+    pub fn manhattan(&self, other: &Self) -> T::Unsigned {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Chessboard distance `max(|dx|, |dy|)`.
+    #[must_use]
+    pub fn chebyshev(&self, other: &Self) -> T::Unsigned {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Squared Euclidean distance. Kept squared so comparisons stay exact.
+    #[must_use]
+    pub fn dist_sq(&self, other: &Self) -> T {
+        let d = *self - *other;
+        d.x * d.x + d.y * d.y
+    }
+
+    /// The z component of the cross product of two vectors: positive if
+    /// `other` lies counter-clockwise of `self`.
+    #[must_use]
+    pub fn cross(&self, other: &Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    #[must_use]
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+}
+
+impl<T: Coord> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Coord> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+/// A point in integer 3D space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T = i64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coord> Point3<T> {
+    #[must_use]
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Squared Euclidean distance. Kept squared so comparisons stay exact.
+    #[must_use]
+    pub fn dist_sq(&self, other: &Self) -> T {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        let dz = self.z - other.z;
+        dx * dx + dy * dy + dz * dz
+    }
+
+    /// Taxicab distance `|dx| + |dy| + |dz|`.
+    #[must_use]
+    pub fn manhattan(&self, other: &Self) -> T::Unsigned {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// Chessboard distance `max(|dx|, |dy|, |dz|)`.
+    #[must_use]
+    pub fn chebyshev(&self, other: &Self) -> T::Unsigned {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+}
+
+/// Which way a path turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    CounterClockwise,
+    Clockwise,
+    Collinear,
+}
+
+/// Which way the path `a -> b -> c` turns at `b`.
+#[must_use]
+pub fn orientation<T: Coord>(a: Point2<T>, b: Point2<T>, c: Point2<T>) -> Orientation {
+    match (b - a).cross(&(c - a)).cmp(&T::from(0)) {
+        std::cmp::Ordering::Greater => Orientation::CounterClockwise,
+        std::cmp::Ordering::Less => Orientation::Clockwise,
+        std::cmp::Ordering::Equal => Orientation::Collinear,
+    }
+}
+
+/// A closed line segment between two lattice points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment<T = i64> {
+    pub a: Point2<T>,
+    pub b: Point2<T>,
+}
+
+impl<T: Coord> Segment<T> {
+    #[must_use]
+    pub const fn new(a: Point2<T>, b: Point2<T>) -> Self {
+        Self { a, b }
+    }
+
+    /// Whether `p` lies on the segment, endpoints included.
+    #[must_use]
+    pub fn contains(&self, p: Point2<T>) -> bool {
+        orientation(self.a, self.b, p) == Orientation::Collinear
+            && (self.a.x.min(self.b.x)..=self.a.x.max(self.b.x)).contains(&p.x)
+            && (self.a.y.min(self.b.y)..=self.a.y.max(self.b.y)).contains(&p.y)
+    }
+
+    /// Whether the two segments share at least one point, counting touching
+    /// endpoints and collinear overlaps.
+    #[must_use]
+    pub fn intersects(&self, other: &Self) -> bool {
+        let d1 = orientation(other.a, other.b, self.a);
+        let d2 = orientation(other.a, other.b, self.b);
+        let d3 = orientation(self.a, self.b, other.a);
+        let d4 = orientation(self.a, self.b, other.b);
+        let straddles = |p, q| p != q && p != Orientation::Collinear && q != Orientation::Collinear;
+        (straddles(d1, d2) && straddles(d3, d4))
+            || other.contains(self.a)
+            || other.contains(self.b)
+            || self.contains(other.a)
+            || self.contains(other.b)
+    }
+
+    /// Lattice points on the segment, endpoints included.
+    #[must_use]
+    pub fn lattice_points(&self) -> T::Unsigned {
+        gcd(self.a.x.abs_diff(self.b.x), self.a.y.abs_diff(self.b.y)) + 1.into()
+    }
+}
+
+/// Where a point lies relative to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A simple polygon on the integer lattice, given by its vertices in order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polygon<T = i64> {
+    vertices: Vec<Point2<T>>,
+}
+
+impl<T: Coord> Polygon<T> {
+    /// The polygon through `vertices`, closing the last edge back to the
+    /// first vertex.
+    #[must_use]
+    pub fn new(vertices: Vec<Point2<T>>) -> Self {
+        Self { vertices }
+    }
+
+    #[must_use]
+    pub fn vertices(&self) -> &[Point2<T>] {
+        &self.vertices
+    }
+
+    /// Each edge, from every vertex to the next.
+    pub fn edges(&self) -> impl Iterator<Item = Segment<T>> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(&a, &b)| Segment::new(a, b))
+    }
+
+    /// Twice the signed area by the shoelace formula: positive when the
+    /// vertices run counter-clockwise.
+    #[must_use]
+    pub fn doubled_signed_area(&self) -> T {
+        self.edges()
+            .fold(T::from(0), |sum, edge| sum + edge.a.cross(&edge.b))
+    }
+
+    /// Twice the area, which is always an integer.
+    #[must_use]
+    pub fn doubled_area(&self) -> T::Unsigned {
+        self.doubled_signed_area().abs_diff(T::from(0))
+    }
+
+    /// Lattice points on the boundary.
+    #[must_use]
+    pub fn boundary_points(&self) -> T::Unsigned {
+        self.edges().fold(T::Unsigned::from(0), |sum, edge| {
+            sum + edge.lattice_points() - 1.into()
+        })
+    }
+
+    /// Lattice points strictly inside, by Pick's theorem
+    /// `A = I + B / 2 - 1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics on overflow if the polygon has no area, as such a polygon
+    /// has no inside.
+    #[must_use]
+    pub fn interior_points(&self) -> T::Unsigned {
+        (self.doubled_area() + 2.into() - self.boundary_points()) / 2.into()
+    }
+
+    /// Where `p` lies, decided exactly by crossing number.
+    ///
+    /// A horizontal ray to the right of `p` crosses each edge that has one
+    /// endpoint strictly above `p` and the other not; counting edges this
+    /// way means a ray through a vertex is only counted where the boundary
+    /// really passes from one side to the other.
+    #[must_use]
+    pub fn locate(&self, p: Point2<T>) -> Location {
+        let mut inside = false;
+        for edge in self.edges() {
+            if edge.contains(p) {
+                return Location::Boundary;
+            }
+            let (a, b) = (edge.a, edge.b);
+            if (a.y > p.y) != (b.y > p.y) {
+                let side = orientation(a, b, p);
+                let upward = b.y > a.y;
+                if upward == (side == Orientation::CounterClockwise) {
+                    inside = !inside;
+                }
+            }
+        }
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+}
+
+/// The convex hull of `points`, counter-clockwise from the leftmost (then
+/// lowest) point, by Andrew's monotone chain. Points in the middle of a hull
+/// edge are left out, so collinear input gives just its two ends.
+#[must_use]
+pub fn convex_hull<T: Coord>(points: &[Point2<T>]) -> Vec<Point2<T>> {
+    let mut sorted = points.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let mut hull: Vec<Point2<T>> = Vec::with_capacity(sorted.len() + 1);
+    let push = |hull: &mut Vec<Point2<T>>, p: Point2<T>, floor: usize| {
+        while hull.len() > floor
+            && orientation(hull[hull.len() - 2], hull[hull.len() - 1], p)
+                != Orientation::CounterClockwise
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    };
+    // The lower chain left to right, then the upper chain back again.
+    for &p in &sorted {
+        push(&mut hull, p, 1);
+    }
+    let floor = hull.len();
+    for &p in sorted.iter().rev().skip(1) {
+        push(&mut hull, p, floor);
+    }
+    hull.pop(); // Back at the start.
+    hull
+}
+
+/// A polygon edge that is neither horizontal nor vertical, by the index of
+/// its first vertex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("edge {0} of the polygon is neither horizontal nor vertical")]
+pub struct DiagonalEdge(pub usize);
+
+/// A rectilinear polygon rasterised on its own compressed coordinates, for
+/// constant-time "is this rectangle inside?" queries.
+///
+/// Every distinct vertex coordinate gets a grid line, and each gap between
+/// neighbouring lines collapses to a single cell, so the grid has
+/// `2n - 1` cells along an axis with `n` distinct coordinates. A cell is
+/// filled if its points lie in the closed polygon (boundary included), and
+/// 2D prefix sums over the fills answer each query with four lookups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolygonRaster {
+    xs: Vec<i64>,
+    ys: Vec<i64>,
+    cols: usize,
+    /// Filled cells above and left of each grid corner, `(rows + 1) x (cols + 1)`.
+    sums: Vec<u32>,
+}
+
+/// A point's place on a [`PolygonRaster`]: even indices are grid lines,
+/// odd ones the open gaps between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridPoint {
+    row: usize,
+    col: usize,
+}
+
+impl PolygonRaster {
+    /// Rasterises the polygon through `vertices`, in order, closing the last
+    /// edge back to the first vertex.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DiagonalEdge`] if two consecutive vertices differ in both
+    /// coordinates.
+    pub fn new(vertices: &[Point2]) -> Result<Self, DiagonalEdge> {
+        let edges: Vec<(Point2, Point2)> = vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .map(|(&a, &b)| (a, b))
+            .collect();
+        if let Some(i) = edges.iter().position(|(a, b)| a.x != b.x && a.y != b.y) {
+            return Err(DiagonalEdge(i));
+        }
+
+        let axis = |coord: fn(&Point2) -> i64| {
+            let mut values: Vec<i64> = vertices.iter().map(coord).collect();
+            values.sort_unstable();
+            values.dedup();
+            values
+        };
+        let xs = axis(|p| p.x);
+        let ys = axis(|p| p.y);
+        let cols = (2 * xs.len()).saturating_sub(1);
+        let rows = (2 * ys.len()).saturating_sub(1);
+        let line = |values: &[i64], v: i64| 2 * values.partition_point(|&w| w < v);
+
+        // Vertical edges as (column, first row, last row).
+        let verticals: Vec<(usize, usize, usize)> = edges
+            .iter()
+            .filter(|(a, b)| a.x == b.x && a.y != b.y)
+            .map(|(a, b)| {
+                let (lo, hi) = (line(&ys, a.y.min(b.y)), line(&ys, a.y.max(b.y)));
+                (line(&xs, a.x), lo, hi)
+            })
+            .collect();
+
+        // Away from the boundary, a cell is inside if an odd number of
+        // vertical edges lie to its left. Counting each edge over the rows
+        // `lo..hi` only means a ray through a vertex crosses exactly the
+        // edges that really change sides.
+        let mut filled = vec![false; rows * cols];
+        let mut crossings = vec![false; cols];
+        for (r, row) in filled.chunks_mut(cols.max(1)).enumerate() {
+            crossings.fill(false);
+            for &(c, lo, hi) in &verticals {
+                if (lo..hi).contains(&r) {
+                    crossings[c] ^= true;
+                }
+            }
+            let mut inside = false;
+            for (cell, &crossing) in row.iter_mut().zip(&crossings) {
+                *cell = inside;
+                inside ^= crossing;
+            }
+        }
+        for (a, b) in &edges {
+            let (r1, r2) = (line(&ys, a.y.min(b.y)), line(&ys, a.y.max(b.y)));
+            let (c1, c2) = (line(&xs, a.x.min(b.x)), line(&xs, a.x.max(b.x)));
+            for r in r1..=r2 {
+                filled[r * cols + c1..=r * cols + c2].fill(true);
+            }
+        }
+
+        let mut sums = vec![0; (rows + 1) * (cols + 1)];
+        for r in 0..rows {
+            for c in 0..cols {
+                let i = (r + 1) * (cols + 1) + c + 1;
+                sums[i] = u32::from(filled[r * cols + c]) + sums[i - 1] + sums[i - cols - 1]
+                    - sums[i - cols - 2];
+            }
+        }
+
+        Ok(Self { xs, ys, cols, sums })
+    }
+
+    /// Where `p` falls on the grid, or `None` if it lies outside the
+    /// polygon's bounding box.
+    #[must_use]
+    pub fn locate(&self, p: Point2) -> Option<GridPoint> {
+        let index = |values: &[i64], v: i64| match values.binary_search(&v) {
+            Ok(i) => Some(2 * i),
+            Err(i) if i > 0 && i < values.len() => Some(2 * i - 1),
+            Err(_) => None,
+        };
+        Some(GridPoint {
+            row: index(&self.ys, p.y)?,
+            col: index(&self.xs, p.x)?,
+        })
+    }
+
+    /// Whether the closed rectangle spanned by two located corners lies
+    /// entirely within the closed polygon, in constant time.
+    #[must_use]
+    pub fn contains_between(&self, a: GridPoint, b: GridPoint) -> bool {
+        let (r1, r2) = (a.row.min(b.row), a.row.max(b.row) + 1);
+        let (c1, c2) = (a.col.min(b.col), a.col.max(b.col) + 1);
+        let at = |r: usize, c: usize| self.sums[r * (self.cols + 1) + c];
+        let filled = at(r2, c2) + at(r1, c1) - at(r1, c2) - at(r2, c1);
+        filled as usize == (r2 - r1) * (c2 - c1)
+    }
+
+    /// Whether the closed rectangle with opposite corners `a` and `b` lies
+    /// entirely within the closed polygon.
+    #[must_use]
+    pub fn contains_rect(&self, a: Point2, b: Point2) -> bool {
+        match (self.locate(a), self.locate(b)) {
+            (Some(a), Some(b)) => self.contains_between(a, b),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_point2_manhattan() {
+        assert_eq!(Point2::new(-2_i64, 3).manhattan(&Point2::new(4, -1)), 10);
+        assert_eq!(Point2::new(1_i64, 1).manhattan(&Point2::new(1, 1)), 0);
+    }
+
+    #[test]
+    fn test_point3_distances() {
+        let a = Point3::new(0_i64, 0, 0);
+        let b = Point3::new(1, -2, 2);
+        assert_eq!(a.dist_sq(&b), 9);
+        assert_eq!(a.manhattan(&b), 5);
+        assert_eq!(a.chebyshev(&b), 2);
+    }
+
+    #[test]
+    fn test_point2_metrics_on_other_widths() {
+        let a = Point2::<i32>::new(-2, 3);
+        let b = Point2::new(4, -1);
+        assert_eq!(a.manhattan(&b), 10_u32);
+        assert_eq!(a.chebyshev(&b), 6_u32);
+        assert_eq!(a.dist_sq(&b), 52);
+        let far = Point2::<i128>::new(i128::from(i64::MAX), 0);
+        assert_eq!(far.dist_sq(&Point2::default()), i128::from(i64::MAX).pow(2));
+    }
+
+    #[test]
+    fn test_orientation_and_cross() {
+        let o = Point2::new(0, 0);
+        assert_eq!(Point2::new(1, 0).cross(&Point2::new(0, 1)), 1);
+        assert_eq!(Point2::new(3, 4).dot(&Point2::new(4, -3)), 0);
+        assert_eq!(
+            orientation(o, Point2::new(2, 0), Point2::new(2, 1)),
+            Orientation::CounterClockwise
+        );
+        assert_eq!(
+            orientation(o, Point2::new(2, 0), Point2::new(2, -1)),
+            Orientation::Clockwise
+        );
+        assert_eq!(
+            orientation(o, Point2::new(2, 2), Point2::new(5, 5)),
+            Orientation::Collinear
+        );
+    }
+
+    #[test]
+    fn test_segment_intersection() {
+        let seg = |ax: i64, ay, bx, by| Segment::new(Point2::new(ax, ay), Point2::new(bx, by));
+        let diagonal = seg(0, 0, 4, 4);
+
+        assert!(diagonal.intersects(&seg(0, 4, 4, 0)));
+        assert!(diagonal.intersects(&seg(4, 4, 6, 0)), "shared endpoint");
+        assert!(
+            diagonal.intersects(&seg(2, 2, 3, 0)),
+            "endpoint on the other"
+        );
+        assert!(diagonal.intersects(&seg(3, 3, 8, 8)), "collinear overlap");
+        assert!(!diagonal.intersects(&seg(5, 5, 8, 8)), "collinear, apart");
+        assert!(!diagonal.intersects(&seg(1, 0, 4, 3)), "parallel");
+        assert!(
+            !diagonal.intersects(&seg(3, 0, 5, 2)),
+            "would cross if longer"
+        );
+
+        assert!(diagonal.contains(Point2::new(1, 1)));
+        assert!(!diagonal.contains(Point2::new(5, 5)));
+        assert_eq!(diagonal.lattice_points(), 5);
+        assert_eq!(seg(0, 0, 6, 4).lattice_points(), 3);
+    }
+
+    fn polygon(vertices: &[(i64, i64)]) -> Polygon {
+        Polygon::new(vertices.iter().map(|&(x, y)| Point2::new(x, y)).collect())
+    }
+
+    #[test]
+    fn test_shoelace_and_pick() {
+        // The C shape, counter-clockwise: a 4x4 square less a 3x2 bite.
+        let c = polygon(&[
+            (0, 0),
+            (4, 0),
+            (4, 1),
+            (1, 1),
+            (1, 3),
+            (4, 3),
+            (4, 4),
+            (0, 4),
+        ]);
+        assert_eq!(c.doubled_signed_area(), 20);
+        assert_eq!(c.doubled_area(), 20);
+        // Both arms and the spine are one unit thick, so nothing is inside.
+        assert_eq!(c.boundary_points(), 22);
+        assert_eq!(c.interior_points(), 0);
+
+        let clockwise = polygon(&[(0, 0), (0, 3), (6, 0)]);
+        assert_eq!(clockwise.doubled_signed_area(), -18);
+        assert_eq!(clockwise.boundary_points(), 12);
+        assert_eq!(clockwise.interior_points(), 4);
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        let c = polygon(&[
+            (0, 0),
+            (4, 0),
+            (4, 1),
+            (1, 1),
+            (1, 3),
+            (4, 3),
+            (4, 4),
+            (0, 4),
+        ]);
+        let at = |x, y| c.locate(Point2::new(x, y));
+
+        assert_eq!(at(0, 2), Location::Boundary);
+        assert_eq!(at(3, 1), Location::Boundary);
+        assert_eq!(at(4, 4), Location::Boundary);
+        assert_eq!(at(2, 2), Location::Outside);
+        assert_eq!(at(5, 1), Location::Outside);
+        // Rays through the vertices at y = 1 and y = 3.
+        assert_eq!(at(-1, 1), Location::Outside);
+        assert_eq!(at(-1, 3), Location::Outside);
+
+        let doubled = Polygon::new(c.vertices().iter().map(|&v| v + v).collect());
+        assert_eq!(doubled.locate(Point2::new(1, 1)), Location::Inside);
+        assert_eq!(doubled.locate(Point2::new(7, 1)), Location::Inside);
+        assert_eq!(doubled.locate(Point2::new(7, 3)), Location::Outside);
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let points = [
+            (0, 0),
+            (2, 0),
+            (4, 0),
+            (4, 4),
+            (0, 4),
+            (2, 2),
+            (1, 3),
+            (0, 2),
+        ]
+        .map(|(x, y)| Point2::new(x, y));
+        assert_eq!(
+            convex_hull(&points),
+            [(0, 0), (4, 0), (4, 4), (0, 4)].map(|(x, y)| Point2::new(x, y))
+        );
+
+        let line = [(2, 2), (0, 0), (1, 1), (0, 0)].map(|(x, y)| Point2::new(x, y));
+        assert_eq!(convex_hull(&line), [Point2::new(0, 0), Point2::new(2, 2)]);
+        assert!(convex_hull::<i64>(&[]).is_empty());
+    }
+
+    fn small_points() -> impl Strategy<Value = Vec<Point2>> {
+        prop::collection::vec((-6..=6i64, -6..=6i64), 1..12)
+            .prop_map(|v| v.into_iter().map(|(x, y)| Point2::new(x, y)).collect())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_hull_and_pick_agree_with_counting(points in small_points()) {
+            let hull = Polygon::new(convex_hull(&points));
+            for &p in &points {
+                prop_assert_ne!(hull.locate(p), Location::Outside);
+            }
+            if hull.vertices().len() < 3 {
+                return Ok(());
+            }
+            prop_assert!(hull.doubled_signed_area() > 0);
+
+            let (mut inside, mut boundary) = (0, 0);
+            for x in -6..=6 {
+                for y in -6..=6 {
+                    match hull.locate(Point2::new(x, y)) {
+                        Location::Inside => inside += 1,
+                        Location::Boundary => boundary += 1,
+                        Location::Outside => {}
+                    }
+                }
+            }
+            prop_assert_eq!(hull.boundary_points(), boundary);
+            prop_assert_eq!(hull.interior_points(), inside);
+        }
+    }
+
+    /// `(0,0) (4,0) (4,1) (1,1) (1,3) (4,3) (4,4) (0,4)`: a C opening right.
+    fn c_shape() -> PolygonRaster {
+        let vertices = [
+            (0, 0),
+            (4, 0),
+            (4, 1),
+            (1, 1),
+            (1, 3),
+            (4, 3),
+            (4, 4),
+            (0, 4),
+        ]
+        .map(|(x, y)| Point2::new(x, y));
+        PolygonRaster::new(&vertices).expect("rectilinear")
+    }
+
+    #[test]
+    fn test_raster_contains_rect() {
+        let raster = c_shape();
+        let contains = |a: (i64, i64), b: (i64, i64)| {
+            raster.contains_rect(Point2::new(a.0, a.1), Point2::new(b.0, b.1))
+        };
+
+        // The bounding box takes in the mouth of the C.
+        assert!(!contains((0, 0), (4, 4)));
+        // Each arm, the spine, and a point inside the spine.
+        assert!(contains((0, 0), (4, 1)));
+        assert!(contains((4, 4), (0, 3)));
+        assert!(contains((0, 0), (1, 4)));
+        assert!(contains((0, 2), (0, 2)));
+        // Between grid lines, inside an arm and straying into the mouth.
+        assert!(contains((2, 0), (3, 1)));
+        assert!(!contains((2, 1), (3, 2)));
+        // The mouth's edges are boundary, but its middle is not.
+        assert!(contains((1, 1), (4, 1)));
+        assert!(!contains((2, 2), (2, 2)));
+        // Outside the bounding box.
+        assert!(!contains((-1, 0), (1, 1)));
+        assert!(!contains((0, 0), (5, 1)));
+    }
+
+    #[test]
+    fn test_raster_rays_through_vertices() {
+        // A staircase puts vertices on the rows a ray runs along.
+        let vertices =
+            [(0, 0), (2, 0), (2, 2), (4, 2), (4, 4), (0, 4)].map(|(x, y)| Point2::new(x, y));
+        let raster = PolygonRaster::new(&vertices).expect("rectilinear");
+        assert!(raster.contains_rect(Point2::new(0, 2), Point2::new(4, 4)));
+        assert!(raster.contains_rect(Point2::new(0, 0), Point2::new(2, 4)));
+        assert!(!raster.contains_rect(Point2::new(0, 0), Point2::new(3, 2)));
+        assert!(!raster.contains_rect(Point2::new(3, 1), Point2::new(3, 1)));
+    }
+
+    #[test]
+    fn test_raster_rejects_diagonal_edges() {
+        let vertices = [(0, 0), (2, 0), (2, 2), (1, 3)].map(|(x, y)| Point2::new(x, y));
+        assert_eq!(PolygonRaster::new(&vertices), Err(DiagonalEdge(2)));
+        assert_eq!(
+            PolygonRaster::new(&[]).map(|r| r.locate(Point2::new(0, 0))),
+            Ok(None)
+        );
+    }
+}
@@ -0,0 +1,484 @@
+//! Linear algebra over GF(2), the field of bits, where adding is XOR.
+//!
+//! [`BitVec`] and [`BitMatrix`] pack bits into `u64` words so a row
+//! operation touches 64 columns at once. [`Rref`] reduces a system
+//! `A x = b` and describes every solution as one particular solution plus
+//! any combination of a nullspace basis, which [`min_weight_solution`]
+//! walks to find the solution with the fewest ones.
+
+use std::fmt;
+
+use thiserror::Error;
+
+/// A fixed-length vector of bits.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitVec {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl BitVec {
+    /// All zeros.
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    /// The low `len` bits of `mask`, bit `i` going to index `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` exceeds 64.
+    #[must_use]
+    pub fn from_mask(mask: u64, len: usize) -> Self {
+        assert!(len <= 64, "a mask holds at most 64 bits");
+        let mut bits = Self::new(len);
+        if len > 0 {
+            bits.words[0] = mask & (u64::MAX >> (64 - len));
+        }
+        bits
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    #[must_use]
+    pub fn get(&self, i: usize) -> bool {
+        assert!(
+            i < self.len,
+            "bit {i} out of bounds for length {}",
+            self.len
+        );
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(
+            i < self.len,
+            "bit {i} out of bounds for length {}",
+            self.len
+        );
+        let mask = 1 << (i % 64);
+        if value {
+            self.words[i / 64] |= mask;
+        } else {
+            self.words[i / 64] &= !mask;
+        }
+    }
+
+    /// Adds `other` in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    pub fn xor_assign(&mut self, other: &Self) {
+        assert_eq!(self.len, other.len, "vectors have the same length");
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a ^= b;
+        }
+    }
+
+    /// Number of set bits, the Hamming weight.
+    #[must_use]
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Indices of the set bits, in increasing order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                (rest != 0).then(|| {
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    w * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+impl fmt::Debug for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits: String = (0..self.len)
+            .map(|i| if self.get(i) { '1' } else { '0' })
+            .collect();
+        write!(f, "BitVec({bits})")
+    }
+}
+
+/// A matrix of bits, stored as one [`BitVec`] per row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    cols: usize,
+    rows: Vec<BitVec>,
+}
+
+impl BitMatrix {
+    /// All zeros.
+    #[must_use]
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            cols,
+            rows: vec![BitVec::new(cols); rows],
+        }
+    }
+
+    /// The matrix whose column `j` is the low `rows` bits of `columns[j]`,
+    /// as when each column is a mask of the rows it touches.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` exceeds 64.
+    #[must_use]
+    pub fn from_column_masks(columns: &[u64], rows: usize) -> Self {
+        assert!(rows <= 64, "a mask holds at most 64 bits");
+        let mut matrix = Self::new(rows, columns.len());
+        for (j, &mask) in columns.iter().enumerate() {
+            for i in 0..rows {
+                matrix.set(i, j, mask >> i & 1 == 1);
+            }
+        }
+        matrix
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    #[must_use]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[must_use]
+    pub fn row(&self, i: usize) -> &BitVec {
+        &self.rows[i]
+    }
+
+    #[must_use]
+    pub fn get(&self, i: usize, j: usize) -> bool {
+        self.rows[i].get(j)
+    }
+
+    pub fn set(&mut self, i: usize, j: usize, value: bool) {
+        self.rows[i].set(j, value);
+    }
+
+    /// The product `A x`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` does not have one bit per column.
+    #[must_use]
+    pub fn mul_vec(&self, x: &BitVec) -> BitVec {
+        assert_eq!(x.len(), self.cols, "one bit per column");
+        let mut out = BitVec::new(self.rows.len());
+        for (i, row) in self.rows.iter().enumerate() {
+            let dot = row
+                .words
+                .iter()
+                .zip(&x.words)
+                .map(|(a, b)| (a & b).count_ones());
+            out.set(i, dot.sum::<u32>() % 2 == 1);
+        }
+        out
+    }
+}
+
+/// The reduced row echelon form of a system `A x = b` over GF(2).
+///
+/// Each pivot row reads `x[pivot] + sum(x[f]) = rhs` over some of the free
+/// variables `f`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rref {
+    /// Pivot rows, with the right-hand side as one extra bit at the end.
+    rows: Vec<BitVec>,
+    /// Pivot column of each row in `rows`.
+    pivots: Vec<usize>,
+    free: Vec<usize>,
+    vars: usize,
+}
+
+impl Rref {
+    /// Reduces `a x = b`, or returns `None` if the system has no solution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `b` does not have one bit per row of `a`.
+    #[must_use]
+    pub fn new(a: &BitMatrix, b: &BitVec) -> Option<Self> {
+        assert_eq!(b.len(), a.rows(), "one right-hand side per equation");
+        let vars = a.cols();
+        let mut rows: Vec<BitVec> = a
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mut augmented = BitVec::new(vars + 1);
+                for j in row.ones() {
+                    augmented.set(j, true);
+                }
+                augmented.set(vars, b.get(i));
+                augmented
+            })
+            .collect();
+
+        let mut pivots = Vec::new();
+        let mut free = Vec::new();
+        for col in 0..vars {
+            let rank = pivots.len();
+            let Some(found) = (rank..rows.len()).find(|&r| rows[r].get(col)) else {
+                free.push(col);
+                continue;
+            };
+            rows.swap(rank, found);
+            let pivot_row = rows[rank].clone();
+            for (i, row) in rows.iter_mut().enumerate() {
+                if i != rank && row.get(col) {
+                    row.xor_assign(&pivot_row);
+                }
+            }
+            pivots.push(col);
+        }
+
+        // Leftover rows read `0 = rhs`.
+        if rows[pivots.len()..].iter().any(|row| row.get(vars)) {
+            return None;
+        }
+        rows.truncate(pivots.len());
+        Some(Self {
+            rows,
+            pivots,
+            free,
+            vars,
+        })
+    }
+
+    /// Number of variables in the system.
+    #[must_use]
+    pub fn vars(&self) -> usize {
+        self.vars
+    }
+
+    #[must_use]
+    pub fn rank(&self) -> usize {
+        self.pivots.len()
+    }
+
+    /// Variables that can be chosen freely, in increasing order.
+    #[must_use]
+    pub fn free_vars(&self) -> &[usize] {
+        &self.free
+    }
+
+    /// The solution with every free variable zero.
+    #[must_use]
+    pub fn particular(&self) -> BitVec {
+        let mut x = BitVec::new(self.vars);
+        for (row, &col) in self.rows.iter().zip(&self.pivots) {
+            x.set(col, row.get(self.vars));
+        }
+        x
+    }
+
+    /// A basis of the solutions of `A x = 0`: one vector per free variable,
+    /// setting it alone and whichever pivots it forces.
+    #[must_use]
+    pub fn nullspace(&self) -> Vec<BitVec> {
+        self.free
+            .iter()
+            .map(|&f| {
+                let mut v = BitVec::new(self.vars);
+                v.set(f, true);
+                for (row, &col) in self.rows.iter().zip(&self.pivots) {
+                    if row.get(f) {
+                        v.set(col, true);
+                    }
+                }
+                v
+            })
+            .collect()
+    }
+}
+
+/// Nullities above this would take too long to enumerate.
+pub const MAX_NULLITY: usize = 24;
+
+/// The solutions span more dimensions than [`min_weight_solution`] will
+/// enumerate.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("nullity {0} is too large to enumerate, at most {MAX_NULLITY} is supported")]
+pub struct NullityTooLarge(pub usize);
+
+/// The solution of `a x = b` with the fewest ones, or `None` if there is
+/// none.
+///
+/// Every solution is the particular one plus some subset of the nullspace
+/// basis, so all `2^nullity` of them are visited in Gray-code order, each
+/// one a single XOR away from the last.
+///
+/// # Errors
+///
+/// Returns [`NullityTooLarge`] if the nullity exceeds [`MAX_NULLITY`].
+///
+/// # Panics
+///
+/// Panics on mismatched dimensions, as [`Rref::new`] does.
+pub fn min_weight_solution(a: &BitMatrix, b: &BitVec) -> Result<Option<BitVec>, NullityTooLarge> {
+    let Some(rref) = Rref::new(a, b) else {
+        return Ok(None);
+    };
+    let basis = rref.nullspace();
+    if basis.len() > MAX_NULLITY {
+        return Err(NullityTooLarge(basis.len()));
+    }
+
+    let mut x = rref.particular();
+    let mut best = x.clone();
+    let mut best_weight = x.count_ones();
+    for step in 1_u64..1 << basis.len() {
+        x.xor_assign(&basis[step.trailing_zeros() as usize]);
+        let weight = x.count_ones();
+        if weight < best_weight {
+            best_weight = weight;
+            best.clone_from(&x);
+        }
+    }
+    Ok(Some(best))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn matrix(rows: &[&str]) -> BitMatrix {
+        let mut a = BitMatrix::new(rows.len(), rows.first().map_or(0, |r| r.len()));
+        for (i, row) in rows.iter().enumerate() {
+            for (j, c) in row.chars().enumerate() {
+                a.set(i, j, c == '1');
+            }
+        }
+        a
+    }
+
+    fn bits(s: &str) -> BitVec {
+        let mut v = BitVec::new(s.len());
+        for (i, c) in s.chars().enumerate() {
+            v.set(i, c == '1');
+        }
+        v
+    }
+
+    #[test]
+    fn test_bitvec_basics() {
+        let mut v = BitVec::new(130);
+        v.set(0, true);
+        v.set(64, true);
+        v.set(129, true);
+        assert_eq!(v.ones().collect::<Vec<_>>(), [0, 64, 129]);
+        assert_eq!(v.count_ones(), 3);
+        v.xor_assign(&v.clone());
+        assert_eq!(v.count_ones(), 0);
+
+        assert_eq!(BitVec::from_mask(0b1011, 3), bits("110"));
+        assert_eq!(format!("{:?}", bits("0101")), "BitVec(0101)");
+    }
+
+    #[test]
+    fn test_from_column_masks() {
+        let a = BitMatrix::from_column_masks(&[0b01, 0b11, 0b10], 2);
+        assert_eq!(a, matrix(&["110", "011"]));
+        assert_eq!(a.mul_vec(&bits("101")), bits("11"));
+    }
+
+    #[test]
+    fn test_rref_solution_and_nullspace() {
+        // x0 + x1 = 1, x1 + x2 = 0.
+        let a = matrix(&["110", "011"]);
+        let rref = Rref::new(&a, &bits("10")).expect("consistent");
+        assert_eq!(rref.rank(), 2);
+        assert_eq!(rref.free_vars(), [2]);
+        assert_eq!(rref.particular(), bits("100"));
+        assert_eq!(rref.nullspace(), [bits("111")]);
+        assert_eq!(a.mul_vec(&rref.nullspace()[0]), bits("00"));
+    }
+
+    #[test]
+    fn test_rref_rejects_inconsistent_systems() {
+        let a = matrix(&["11", "11"]);
+        assert_eq!(Rref::new(&a, &bits("10")), None);
+        assert!(Rref::new(&a, &bits("11")).is_some());
+    }
+
+    #[test]
+    fn test_min_weight_example_machine() {
+        // [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) takes two presses.
+        let a = BitMatrix::from_column_masks(&[8, 10, 4, 12, 5, 3], 4);
+        let x = min_weight_solution(&a, &bits("0110"))
+            .expect("small nullity")
+            .expect("solvable");
+        assert_eq!(x.count_ones(), 2);
+        assert_eq!(a.mul_vec(&x), bits("0110"));
+    }
+
+    #[test]
+    fn test_min_weight_rejects_large_nullity() {
+        let a = BitMatrix::new(1, MAX_NULLITY + 2);
+        assert_eq!(
+            min_weight_solution(&a, &BitVec::new(1)),
+            Err(NullityTooLarge(MAX_NULLITY + 2))
+        );
+    }
+
+    fn system() -> impl Strategy<Value = (Vec<u64>, u64, usize)> {
+        (1..=6usize, 1..=8usize).prop_flat_map(|(rows, cols)| {
+            let columns = prop::collection::vec(0..1_u64 << rows, cols);
+            (columns, 0..1_u64 << rows, Just(rows))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_min_weight_matches_brute_force((columns, target, rows) in system()) {
+            let a = BitMatrix::from_column_masks(&columns, rows);
+            let b = BitVec::from_mask(target, rows);
+            let brute = (0_u32..1 << columns.len())
+                .filter(|&pick| {
+                    let reached = columns
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, _)| pick >> j & 1 == 1)
+                        .fold(0, |acc, (_, &c)| acc ^ c);
+                    reached == target
+                })
+                .map(|pick| pick.count_ones() as usize)
+                .min();
+
+            let found = min_weight_solution(&a, &b).expect("small nullity");
+            prop_assert_eq!(found.as_ref().map(BitVec::count_ones), brute);
+            if let Some(x) = found {
+                prop_assert_eq!(a.mul_vec(&x), b);
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Graphs: an interned, compressed adjacency structure for labelled
+//! directed graphs here, and searches over implicit graphs in [`search`].
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+pub mod search;
+
+pub use search::bfs_distance;
+
+/// Maps labels to dense ids `0..len`, in order of first appearance.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Interner {
+    ids: HashMap<String, u32>,
+    labels: Vec<String>,
+}
+
+impl Interner {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of `label`, assigning the next free one if it is new.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` labels are interned.
+    pub fn intern(&mut self, label: &str) -> u32 {
+        if let Some(&id) = self.ids.get(label) {
+            return id;
+        }
+        let id = u32::try_from(self.labels.len()).expect("fewer than 2^32 labels");
+        self.ids.insert(label.to_owned(), id);
+        self.labels.push(label.to_owned());
+        id
+    }
+
+    #[must_use]
+    pub fn get(&self, label: &str) -> Option<u32> {
+        self.ids.get(label).copied()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `id` was not handed out by this interner.
+    #[must_use]
+    pub fn label(&self, id: u32) -> &str {
+        &self.labels[id as usize]
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+/// More waypoints than this would make the visited-set table too large.
+pub const MAX_WAYPOINTS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GraphError {
+    #[error("the graph has a cycle through node {0}")]
+    Cycle(u32),
+    #[error("at most {MAX_WAYPOINTS} waypoints are supported, got {0}")]
+    TooManyWaypoints(usize),
+    #[error("node {0} is listed as a waypoint more than once")]
+    DuplicateWaypoint(u32),
+    #[error("there are more than 2^128 paths")]
+    Overflow,
+}
+
+/// A directed graph over labelled nodes, stored as compressed sparse rows:
+/// one flat array of edge targets, sliced per node by an offset table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Digraph {
+    names: Interner,
+    /// The successors of node `i` are `targets[offsets[i]..offsets[i + 1]]`.
+    offsets: Vec<usize>,
+    targets: Vec<u32>,
+}
+
+impl Digraph {
+    /// Builds the graph from each node's label and the labels it points to.
+    /// Nodes that only appear as targets get no successors, and each node's
+    /// successors keep their given order.
+    pub fn from_adjacency<'a, I, J>(adjacency: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, J)>,
+        J: IntoIterator<Item = &'a str>,
+    {
+        let mut names = Interner::new();
+        let mut edges = Vec::new();
+        for (source, targets) in adjacency {
+            let from = names.intern(source);
+            edges.extend(targets.into_iter().map(|t| (from, names.intern(t))));
+        }
+
+        let mut offsets = vec![0; names.len() + 1];
+        for &(from, _) in &edges {
+            offsets[from as usize + 1] += 1;
+        }
+        let mut total = 0;
+        for offset in &mut offsets {
+            total += *offset;
+            *offset = total;
+        }
+        let mut next = offsets.clone();
+        let mut targets = vec![0; edges.len()];
+        for (from, to) in edges {
+            targets[next[from as usize]] = to;
+            next[from as usize] += 1;
+        }
+
+        Self {
+            names,
+            offsets,
+            targets,
+        }
+    }
+
+    /// Number of nodes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    #[must_use]
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    #[must_use]
+    pub fn names(&self) -> &Interner {
+        &self.names
+    }
+
+    /// The id of the node labelled `label`, if there is one.
+    #[must_use]
+    pub fn id(&self, label: &str) -> Option<u32> {
+        self.names.get(label)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `node` is not a node of this graph.
+    #[must_use]
+    pub fn successors(&self, node: u32) -> &[u32] {
+        let node = node as usize;
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    /// Every node, each before all of its successors.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GraphError::Cycle`] naming a node on a cycle.
+    pub fn topological_order(&self) -> Result<Vec<u32>, GraphError> {
+        self.order_from(0..self.node_count())
+    }
+
+    fn node_count(&self) -> u32 {
+        u32::try_from(self.len()).expect("interner ids fit in u32")
+    }
+
+    /// The nodes reachable from `roots` in topological order, by an
+    /// iterative depth-first search so deep graphs can't overflow the stack.
+    fn order_from(&self, roots: impl IntoIterator<Item = u32>) -> Result<Vec<u32>, GraphError> {
+        const NEW: u8 = 0;
+        const OPEN: u8 = 1;
+        const DONE: u8 = 2;
+
+        let mut state = vec![NEW; self.len()];
+        let mut post_order = Vec::with_capacity(self.len());
+        let mut stack = Vec::new();
+        for root in roots {
+            if state[root as usize] != NEW {
+                continue;
+            }
+            state[root as usize] = OPEN;
+            stack.push((root, 0));
+            while let Some((node, next)) = stack.last_mut() {
+                let Some(&child) = self.successors(*node).get(*next) else {
+                    state[*node as usize] = DONE;
+                    post_order.push(*node);
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+                match state[child as usize] {
+                    NEW => {
+                        state[child as usize] = OPEN;
+                        stack.push((child, 0));
+                    }
+                    OPEN => return Err(GraphError::Cycle(child)),
+                    _ => {}
+                }
+            }
+        }
+        post_order.reverse();
+        Ok(post_order)
+    }
+
+    /// Counts the paths from `start` to `end`.
+    ///
+    /// # Errors
+    ///
+    /// See [`count_paths_via`](Self::count_paths_via).
+    pub fn count_paths(&self, start: u32, end: u32) -> Result<u128, GraphError> {
+        self.count_paths_via(start, end, &[], false)
+    }
+
+    /// Counts the paths from `start` to `end` that pass through every one
+    /// of `waypoints`, in the given order if `ordered` is set.
+    ///
+    /// Paths are counted forward over a topological order of the nodes
+    /// reachable from `start`, split by which waypoints they have visited
+    /// so far: a bitmask of which an ordered count only ever fills a prefix.
+    /// Nodes that cannot reach `end` are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GraphError::Cycle`] if a cycle is reachable from `start`,
+    /// [`GraphError::TooManyWaypoints`] beyond [`MAX_WAYPOINTS`],
+    /// [`GraphError::DuplicateWaypoint`] if a waypoint is listed twice, and
+    /// [`GraphError::Overflow`] if a partial count does not fit in a `u128`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start`, `end` or a waypoint is not a node of this graph.
+    pub fn count_paths_via(
+        &self,
+        start: u32,
+        end: u32,
+        waypoints: &[u32],
+        ordered: bool,
+    ) -> Result<u128, GraphError> {
+        if waypoints.len() > MAX_WAYPOINTS {
+            return Err(GraphError::TooManyWaypoints(waypoints.len()));
+        }
+        let mut waypoint = vec![None; self.len()];
+        for (i, &w) in waypoints.iter().enumerate() {
+            if waypoint[w as usize].replace(i).is_some() {
+                return Err(GraphError::DuplicateWaypoint(w));
+            }
+        }
+        // The visited set after arriving at `node`, or `None` if an ordered
+        // path reached it too early.
+        let arrive = |visited: usize, node: u32| match waypoint[node as usize] {
+            None => Some(visited),
+            Some(i) if ordered && visited != (1 << i) - 1 => None,
+            Some(i) => Some(visited | 1 << i),
+        };
+        assert!((end as usize) < self.len(), "end is a node");
+
+        let order = self.order_from([start])?;
+        let mut position = vec![usize::MAX; self.len()];
+        for (i, &node) in order.iter().enumerate() {
+            position[node as usize] = i;
+        }
+        // Counts that can never reach `end` are skipped, so they can't
+        // overflow.
+        let mut reaches_end = vec![false; self.len()];
+        for &node in order.iter().rev() {
+            reaches_end[node as usize] = node == end
+                || self
+                    .successors(node)
+                    .iter()
+                    .any(|&c| reaches_end[c as usize]);
+        }
+        let width = 1 << waypoints.len();
+        let mut ways = vec![0_u128; order.len() * width];
+        if let Some(visited) = arrive(0, start) {
+            ways[visited] = 1;
+        }
+        for (i, &node) in order.iter().enumerate() {
+            for &child in self.successors(node) {
+                if !reaches_end[child as usize] {
+                    continue;
+                }
+                let j = position[child as usize];
+                for visited in 0..width {
+                    let count = ways[i * width + visited];
+                    if count == 0 {
+                        continue;
+                    }
+                    if let Some(next) = arrive(visited, child) {
+                        let slot = &mut ways[j * width + next];
+                        *slot = slot.checked_add(count).ok_or(GraphError::Overflow)?;
+                    }
+                }
+            }
+        }
+
+        Ok(match position[end as usize] {
+            usize::MAX => 0,
+            j => ways[j * width + width - 1],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digraph(adjacency: &[(&'static str, &'static str)]) -> Digraph {
+        Digraph::from_adjacency(adjacency.iter().map(|&(s, t)| (s, t.split_whitespace())))
+    }
+
+    #[test]
+    fn test_interner_assigns_dense_ids() {
+        let mut names = Interner::new();
+        assert_eq!(names.intern("b"), 0);
+        assert_eq!(names.intern("a"), 1);
+        assert_eq!(names.intern("b"), 0);
+        assert_eq!((names.get("a"), names.get("c")), (Some(1), None));
+        assert_eq!(names.label(1), "a");
+        assert_eq!(names.len(), 2);
+    }
+
+    #[test]
+    fn test_digraph_csr_layout() {
+        let graph = digraph(&[("a", "b c"), ("c", "b"), ("b", "")]);
+        let id = |label| graph.id(label).expect("known label");
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.successors(id("a")), [id("b"), id("c")]);
+        assert_eq!(graph.successors(id("c")), [id("b")]);
+        assert!(graph.successors(id("b")).is_empty());
+
+        let order = graph.topological_order().expect("acyclic");
+        assert_eq!(order, [id("a"), id("c"), id("b")]);
+    }
+
+    #[test]
+    fn test_digraph_reports_cycles() {
+        let graph = digraph(&[("a", "b"), ("b", "c"), ("c", "b d")]);
+        let err = graph.topological_order().expect_err("b and c form a cycle");
+        assert!(
+            matches!(err, GraphError::Cycle(n) if ["b", "c"].contains(&graph.names().label(n)))
+        );
+        // Only cycles reachable from the start matter when counting.
+        let d = graph.id("d").expect("known label");
+        assert_eq!(graph.count_paths(d, d), Ok(1));
+    }
+
+    #[test]
+    fn test_count_paths_via_waypoints() {
+        // s-a-b-t, s-a-t, s-b-t and s-c-t.
+        let graph = digraph(&[("s", "a b c"), ("a", "b t"), ("b", "t"), ("c", "t")]);
+        let id = |label| graph.id(label).expect("known label");
+        let (s, t, a, b) = (id("s"), id("t"), id("a"), id("b"));
+
+        assert_eq!(graph.count_paths(s, t), Ok(4));
+        assert_eq!(graph.count_paths(t, s), Ok(0));
+        assert_eq!(graph.count_paths_via(s, t, &[b], false), Ok(2));
+        assert_eq!(graph.count_paths_via(s, t, &[b, a], false), Ok(1));
+        assert_eq!(graph.count_paths_via(s, t, &[b, a], true), Ok(0));
+        assert_eq!(graph.count_paths_via(s, t, &[s, t], true), Ok(4));
+        assert_eq!(
+            graph.count_paths_via(s, t, &[a; MAX_WAYPOINTS + 1], false),
+            Err(GraphError::TooManyWaypoints(MAX_WAYPOINTS + 1))
+        );
+        assert_eq!(
+            graph.count_paths_via(s, t, &[a, b, a], true),
+            Err(GraphError::DuplicateWaypoint(a))
+        );
+    }
+
+    #[test]
+    fn test_count_paths_is_deep_and_checked() {
+        // A ladder of diamonds doubles the count at every rung.
+        let rungs: Vec<String> = (0..=200).map(|i| format!("n{i}")).collect();
+        let sides: Vec<(String, String)> = (0..200)
+            .map(|i| (format!("l{i}"), format!("r{i}")))
+            .collect();
+        let mut adjacency = Vec::new();
+        for i in 0..200 {
+            let (l, r) = &sides[i];
+            adjacency.push((rungs[i].as_str(), vec![l.as_str(), r.as_str()]));
+            adjacency.push((l.as_str(), vec![rungs[i + 1].as_str()]));
+            adjacency.push((r.as_str(), vec![rungs[i + 1].as_str()]));
+        }
+        let graph = Digraph::from_adjacency(adjacency);
+        let node = |i: usize| graph.id(&rungs[i]).expect("known label");
+        assert_eq!(graph.count_paths(node(0), node(127)), Ok(1 << 127));
+        assert_eq!(
+            graph.count_paths(node(0), node(200)),
+            Err(GraphError::Overflow)
+        );
+
+        // A long chain is fine without recursion.
+        let chain: Vec<String> = (0..100_000).map(|i| i.to_string()).collect();
+        let graph =
+            Digraph::from_adjacency(chain.windows(2).map(|w| (w[0].as_str(), [w[1].as_str()])));
+        let (first, last) = (graph.id("0"), graph.id("99999"));
+        assert_eq!(
+            graph.count_paths(first.expect("known"), last.expect("known")),
+            Ok(1)
+        );
+    }
+}
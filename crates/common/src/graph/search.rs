@@ -0,0 +1,564 @@
+//! Searches over implicit graphs, each given as a start node and a closure
+//! listing a node's successors, plus strongly connected components and
+//! topological sorting for graphs given as a node list and such a closure.
+//!
+//! Nodes are numbered on first sight, so bookkeeping lives in flat vectors
+//! and a node only needs `Clone + Eq + Hash`.
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use thiserror::Error;
+
+/// A path found by a search, from the start to the goal inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+/// An edge weight for [`dijkstra`] and [`astar`]; `C::default()` is zero.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<C: Copy + Ord + Default + Add<Output = C>> Cost for C {}
+
+/// Marks a node without a parent, or not yet visited.
+const NONE: usize = usize::MAX;
+
+/// Dense ids for nodes in order of first sight.
+struct Ids<N> {
+    ids: HashMap<N, usize>,
+    nodes: Vec<N>,
+}
+
+impl<N: Clone + Eq + Hash> Ids<N> {
+    fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// The id of `node` and whether it was new.
+    fn id(&mut self, node: N) -> (usize, bool) {
+        match self.ids.entry(node) {
+            Entry::Occupied(entry) => (*entry.get(), false),
+            Entry::Vacant(entry) => {
+                let id = self.nodes.len();
+                self.nodes.push(entry.key().clone());
+                entry.insert(id);
+                (id, true)
+            }
+        }
+    }
+
+    /// The nodes from the root to `id`, following `parent` links.
+    fn path(&self, parent: &[usize], mut id: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[id].clone()];
+        while parent[id] != NONE {
+            id = parent[id];
+            path.push(self.nodes[id].clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// The number of edges on a shortest path from `start` to the first node
+/// satisfying `is_goal`, or `None` when no such node is reachable. See
+/// [`bfs`].
+pub fn bfs_distance<N, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    bfs(start, successors, is_goal).map(|path| path.cost)
+}
+
+/// Breadth-first search for the nearest node satisfying `is_goal`,
+/// returning the path to it with its length in edges as the cost.
+pub fn bfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut ids = Ids::new();
+    ids.id(start);
+    let mut parent = vec![NONE];
+    let found = |ids: &Ids<N>, parent: &[usize], id| {
+        let nodes = ids.path(parent, id);
+        Some(Path {
+            cost: nodes.len() - 1,
+            nodes,
+        })
+    };
+    if is_goal(&ids.nodes[0]) {
+        return found(&ids, &parent, 0);
+    }
+
+    // Goals are caught as they are discovered, a whole level early.
+    let mut queue = VecDeque::from([0]);
+    while let Some(id) = queue.pop_front() {
+        for next in successors(&ids.nodes[id]) {
+            let (next, new) = ids.id(next);
+            if new {
+                parent.push(id);
+                if is_goal(&ids.nodes[next]) {
+                    return found(&ids, &parent, next);
+                }
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// The distance in edges from `start` to every node reachable from it.
+pub fn bfs_distances<N, I>(start: N, mut successors: impl FnMut(&N) -> I) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut dist = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((node, d)) = queue.pop_front() {
+        for next in successors(&node) {
+            if !dist.contains_key(&next) {
+                dist.insert(next.clone(), d + 1);
+                queue.push_back((next, d + 1));
+            }
+        }
+    }
+    dist
+}
+
+/// Dijkstra's algorithm: the cheapest path to a node satisfying `is_goal`,
+/// where `successors` yields each neighbour with the non-negative cost of
+/// the edge to it.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// The cost of the cheapest path from `start` to every node reachable from
+/// it, with edges as for [`dijkstra`].
+pub fn dijkstra_distances<N, C, I>(start: N, mut successors: impl FnMut(&N) -> I) -> HashMap<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut ids = Ids::new();
+    ids.id(start);
+    let mut best = vec![C::default()];
+    let mut done = vec![false];
+    let mut heap = BinaryHeap::from([Reverse((C::default(), 0))]);
+
+    while let Some(Reverse((cost, id))) = heap.pop() {
+        if std::mem::replace(&mut done[id], true) {
+            continue;
+        }
+        for (next, step) in successors(&ids.nodes[id]) {
+            let (next, new) = ids.id(next);
+            let total = cost + step;
+            if new {
+                best.push(total);
+                done.push(false);
+            } else if total >= best[next] {
+                continue;
+            }
+            best[next] = total;
+            heap.push(Reverse((total, next)));
+        }
+    }
+    ids.nodes.into_iter().zip(best).collect()
+}
+
+/// A* search: [`dijkstra`] guided by `heuristic`, a lower bound on the cost
+/// from a node to the nearest goal. Any such bound finds a cheapest path;
+/// nodes are reopened if a cheaper way to them turns up.
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut ids = Ids::new();
+    let estimate = heuristic(&start);
+    ids.id(start);
+    let mut best = vec![C::default()];
+    let mut parent = vec![NONE];
+    let mut done = vec![false];
+    let mut heap = BinaryHeap::from([Reverse((estimate, 0))]);
+
+    while let Some(Reverse((_, id))) = heap.pop() {
+        if std::mem::replace(&mut done[id], true) {
+            continue;
+        }
+        if is_goal(&ids.nodes[id]) {
+            return Some(Path {
+                nodes: ids.path(&parent, id),
+                cost: best[id],
+            });
+        }
+        for (next, step) in successors(&ids.nodes[id]) {
+            let estimate = heuristic(&next);
+            let (next, new) = ids.id(next);
+            let total = best[id] + step;
+            if new {
+                best.push(total);
+                parent.push(id);
+                done.push(false);
+            } else if total >= best[next] {
+                continue;
+            }
+            best[next] = total;
+            parent[next] = id;
+            done[next] = false;
+            heap.push(Reverse((total + estimate, next)));
+        }
+    }
+    None
+}
+
+/// Tarjan's strongly connected components of everything reachable from
+/// `nodes`. Each component lists its nodes once, and components come out
+/// in reverse topological order: no component has an edge into a later one.
+pub fn strongly_connected_components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    successors: impl FnMut(&N) -> I,
+) -> Vec<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut tarjan = Tarjan {
+        ids: Ids::new(),
+        successors,
+        index: Vec::new(),
+        low: Vec::new(),
+        on_stack: Vec::new(),
+        visited: 0,
+        stack: Vec::new(),
+        frames: Vec::new(),
+        components: Vec::new(),
+    };
+    for root in nodes {
+        let root = tarjan.id(root);
+        tarjan.run(root);
+    }
+    tarjan.components
+}
+
+/// Tarjan's algorithm with an explicit stack of depth-first frames, so deep
+/// graphs can't overflow the call stack.
+struct Tarjan<N, F> {
+    ids: Ids<N>,
+    successors: F,
+    /// Visit order of each node, or [`NONE`] if unvisited.
+    index: Vec<usize>,
+    /// Lowest visit order reachable through the node's subtree.
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    visited: usize,
+    stack: Vec<usize>,
+    /// A node, its successors and the next successor to try.
+    frames: Vec<(usize, Vec<usize>, usize)>,
+    components: Vec<Vec<N>>,
+}
+
+impl<N, I, F> Tarjan<N, F>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    fn id(&mut self, node: N) -> usize {
+        let (id, new) = self.ids.id(node);
+        if new {
+            self.index.push(NONE);
+            self.low.push(NONE);
+            self.on_stack.push(false);
+        }
+        id
+    }
+
+    fn open(&mut self, v: usize) {
+        let next: Vec<N> = (self.successors)(&self.ids.nodes[v]).into_iter().collect();
+        let next = next.into_iter().map(|n| self.id(n)).collect();
+        (self.index[v], self.low[v], self.on_stack[v]) = (self.visited, self.visited, true);
+        self.visited += 1;
+        self.stack.push(v);
+        self.frames.push((v, next, 0));
+    }
+
+    fn run(&mut self, root: usize) {
+        if self.index[root] != NONE {
+            return;
+        }
+        self.open(root);
+        while let Some((v, next, i)) = self.frames.last_mut() {
+            let v = *v;
+            if let Some(&w) = next.get(*i) {
+                *i += 1;
+                if self.index[w] == NONE {
+                    self.open(w);
+                } else if self.on_stack[w] {
+                    self.low[v] = self.low[v].min(self.index[w]);
+                }
+                continue;
+            }
+
+            self.frames.pop();
+            if let Some(&(parent, _, _)) = self.frames.last() {
+                self.low[parent] = self.low[parent].min(self.low[v]);
+            }
+            if self.low[v] == self.index[v] {
+                let mut component = Vec::new();
+                loop {
+                    let w = self.stack.pop().expect("v is still on the stack");
+                    self.on_stack[w] = false;
+                    component.push(self.ids.nodes[w].clone());
+                    if w == v {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+}
+
+/// A cycle that stopped a topological sort, each node pointing to the next
+/// and the last back to the first.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("the graph has a cycle through {} nodes", .0.len())]
+pub struct Cycle<N>(pub Vec<N>);
+
+/// Kahn's topological sort of everything reachable from `nodes`: each node
+/// comes before all of its successors, and nodes that are free at the same
+/// time keep the order they were first seen in.
+///
+/// # Errors
+///
+/// Returns a [`Cycle`] found among the nodes left over once no node is
+/// free.
+pub fn toposort<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> Result<Vec<N>, Cycle<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut ids = Ids::new();
+    for node in nodes {
+        ids.id(node);
+    }
+    let mut edges: Vec<Vec<usize>> = Vec::new();
+    while edges.len() < ids.nodes.len() {
+        let next: Vec<N> = successors(&ids.nodes[edges.len()]).into_iter().collect();
+        edges.push(next.into_iter().map(|n| ids.id(n).0).collect());
+    }
+
+    let mut in_degree = vec![0; edges.len()];
+    for &w in edges.iter().flatten() {
+        in_degree[w] += 1;
+    }
+    let mut free: VecDeque<usize> = (0..edges.len()).filter(|&v| in_degree[v] == 0).collect();
+    let mut order = Vec::with_capacity(edges.len());
+    while let Some(v) = free.pop_front() {
+        order.push(ids.nodes[v].clone());
+        for &w in &edges[v] {
+            in_degree[w] -= 1;
+            if in_degree[w] == 0 {
+                free.push_back(w);
+            }
+        }
+    }
+    if order.len() == edges.len() {
+        return Ok(order);
+    }
+
+    // Every leftover node still has a leftover predecessor, so walking
+    // backwards from any of them must come round in a loop.
+    let mut predecessor = vec![NONE; edges.len()];
+    for (v, next) in edges.iter().enumerate() {
+        for &w in next {
+            if in_degree[v] > 0 && in_degree[w] > 0 {
+                predecessor[w] = v;
+            }
+        }
+    }
+    let mut v = in_degree.iter().position(|&d| d > 0).unwrap_or_default();
+    let mut seen = HashSet::new();
+    while seen.insert(v) {
+        v = predecessor[v];
+    }
+    let mut cycle = vec![ids.nodes[v].clone()];
+    let mut w = predecessor[v];
+    while w != v {
+        cycle.push(ids.nodes[w].clone());
+        w = predecessor[w];
+    }
+    cycle.reverse();
+    Err(Cycle(cycle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bfs_distance_on_line() {
+        let dist = bfs_distance(0_i32, |&n| [n - 1, n + 1], |&n| n == 5);
+        assert_eq!(dist, Some(5));
+    }
+
+    #[test]
+    fn test_bfs_distance_start_is_goal() {
+        assert_eq!(bfs_distance(3_u32, |_| [], |&n| n == 3), Some(0));
+    }
+
+    #[test]
+    fn test_bfs_distance_unreachable() {
+        // Only even numbers below 10 are reachable
+        let dist = bfs_distance(0_u32, |&n| (n < 10).then_some(n + 2), |&n| n == 7);
+        assert_eq!(dist, None);
+    }
+
+    /// A 5x5 grid with a wall down column 2, open only at the bottom row.
+    fn grid_moves((x, y): (i32, i32)) -> Vec<((i32, i32), u32)> {
+        [(0, 1), (1, 0), (0, -1), (-1, 0)]
+            .into_iter()
+            .map(|(dx, dy)| (x + dx, y + dy))
+            .filter(|&(x, y)| (0..5).contains(&x) && (0..5).contains(&y) && (x != 2 || y == 4))
+            .map(|p| (p, 1))
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs_returns_the_path() {
+        let path = bfs(
+            (0, 0),
+            |&p| grid_moves(p).into_iter().map(|(q, _)| q),
+            |&p| p == (4, 0),
+        )
+        .expect("reachable");
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.nodes.len(), 13);
+        assert_eq!(path.nodes.first(), Some(&(0, 0)));
+        assert_eq!(path.nodes.last(), Some(&(4, 0)));
+        assert!(path.nodes.contains(&(2, 4)));
+
+        let dist = bfs_distances((0, 0), |&p| grid_moves(p).into_iter().map(|(q, _)| q));
+        assert_eq!(dist.len(), 21);
+        assert_eq!(dist[&(4, 0)], 12);
+        assert_eq!(bfs(0, |&n: &u8| [n.saturating_add(2)], |&n| n == 7), None);
+    }
+
+    #[test]
+    fn test_dijkstra_prefers_cheap_detours() {
+        // a -> c costs 10 directly, or 3 through b.
+        let edges = |&n: &char| match n {
+            'a' => vec![('c', 10), ('b', 1)],
+            'b' => vec![('c', 2)],
+            'c' => vec![('d', 1)],
+            _ => vec![],
+        };
+        let path = dijkstra('a', edges, |&n| n == 'd').expect("reachable");
+        assert_eq!(path.nodes, ['a', 'b', 'c', 'd']);
+        assert_eq!(path.cost, 4);
+        assert_eq!(dijkstra('d', edges, |&n| n == 'a'), None);
+
+        let dist = dijkstra_distances('a', edges);
+        assert_eq!(
+            dist,
+            HashMap::from([('a', 0), ('b', 1), ('c', 3), ('d', 4)])
+        );
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let goal = (4, 0);
+        let manhattan =
+            |&(x, y): &(i32, i32)| (x - goal.0).unsigned_abs() + (y - goal.1).unsigned_abs();
+        let guided =
+            astar((0, 0), |&p| grid_moves(p), manhattan, |&p| p == goal).expect("reachable");
+        let plain = dijkstra((0, 0), |&p| grid_moves(p), |&p| p == goal).expect("reachable");
+        assert_eq!(guided.cost, 12);
+        assert_eq!(guided.cost, plain.cost);
+        assert_eq!(guided.nodes.len(), 13);
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // 1 <-> 2 -> 3 <-> 4 -> 5, and 6 on its own pointing at 1.
+        let edges = |&n: &u8| match n {
+            1 => vec![2],
+            2 => vec![1, 3],
+            3 => vec![4],
+            4 => vec![3, 5],
+            6 => vec![1],
+            _ => vec![],
+        };
+        let mut components = strongly_connected_components([1, 6], edges);
+        for component in &mut components {
+            component.sort_unstable();
+        }
+        assert_eq!(components, [vec![5], vec![3, 4], vec![1, 2], vec![6]]);
+
+        // A long chain doesn't recurse.
+        let chain = strongly_connected_components([0_u32], |&n| (n < 100_000).then_some(n + 1));
+        assert_eq!(chain.len(), 100_001);
+        assert_eq!(chain[0], [100_000]);
+    }
+
+    #[test]
+    fn test_toposort_orders_and_reports_cycles() {
+        let edges = |&n: &char| match n {
+            'a' => vec!['c', 'b'],
+            'b' | 'c' => vec!['d'],
+            _ => vec![],
+        };
+        assert_eq!(toposort(['a'], edges), Ok(vec!['a', 'c', 'b', 'd']));
+        assert_eq!(toposort(['d', 'b'], edges), Ok(vec!['b', 'd']));
+
+        // x -> y -> z -> y, with w hanging off z.
+        let cyclic = |&n: &char| match n {
+            'x' => vec!['y'],
+            'y' => vec!['z'],
+            'z' => vec!['y', 'w'],
+            _ => vec![],
+        };
+        let Cycle(cycle) = toposort(['x'], cyclic).expect_err("y and z form a cycle");
+        assert!(cycle == ['y', 'z'] || cycle == ['z', 'y'], "{cycle:?}");
+        for (i, n) in cycle.iter().enumerate() {
+            assert!(cyclic(n).contains(&cycle[(i + 1) % cycle.len()]));
+        }
+    }
+}
@@ -0,0 +1,695 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::parse::ParseError;
+
+/// A `(row, col)` cell coordinate. Row 0 is the top line of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    #[must_use]
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Moves one cell towards `dir`, returning `None` when that would leave
+    /// the first quadrant. Upper bounds are checked by [`Grid::step`].
+    #[must_use]
+    pub fn step(self, dir: Dir) -> Option<Self> {
+        let (dr, dc) = dir.delta();
+        Some(Self {
+            row: self.row.checked_add_signed(dr)?,
+            col: self.col.checked_add_signed(dc)?,
+        })
+    }
+}
+
+impl From<(usize, usize)> for Pos {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self { row, col }
+    }
+}
+
+/// One of the eight compass directions, with north pointing to row 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir {
+    /// The four orthogonal directions, clockwise from north.
+    pub const ORTHOGONAL: [Dir; 4] = [Dir::N, Dir::E, Dir::S, Dir::W];
+
+    /// The four diagonal directions, clockwise from north-east.
+    pub const DIAGONAL: [Dir; 4] = [Dir::NE, Dir::SE, Dir::SW, Dir::NW];
+
+    /// All eight directions, clockwise from north.
+    pub const ALL: [Dir; 8] = [
+        Dir::N,
+        Dir::NE,
+        Dir::E,
+        Dir::SE,
+        Dir::S,
+        Dir::SW,
+        Dir::W,
+        Dir::NW,
+    ];
+
+    /// `(row, col)` offset of a single step.
+    #[must_use]
+    pub const fn delta(self) -> (isize, isize) {
+        match self {
+            Dir::N => (-1, 0),
+            Dir::NE => (-1, 1),
+            Dir::E => (0, 1),
+            Dir::SE => (1, 1),
+            Dir::S => (1, 0),
+            Dir::SW => (1, -1),
+            Dir::W => (0, -1),
+            Dir::NW => (-1, -1),
+        }
+    }
+
+    /// Rotates 45 degrees clockwise.
+    #[must_use]
+    pub const fn turn_right_45(self) -> Self {
+        match self {
+            Dir::N => Dir::NE,
+            Dir::NE => Dir::E,
+            Dir::E => Dir::SE,
+            Dir::SE => Dir::S,
+            Dir::S => Dir::SW,
+            Dir::SW => Dir::W,
+            Dir::W => Dir::NW,
+            Dir::NW => Dir::N,
+        }
+    }
+
+    /// Rotates 90 degrees clockwise.
+    #[must_use]
+    pub const fn turn_right(self) -> Self {
+        self.turn_right_45().turn_right_45()
+    }
+
+    /// Rotates 90 degrees counter-clockwise.
+    #[must_use]
+    pub const fn turn_left(self) -> Self {
+        self.opposite().turn_right()
+    }
+
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        self.turn_right().turn_right()
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GridError {
+    #[error("row {row} has {found} cells, expected {expected}")]
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("{len} cells cannot fill a {width}x{height} grid")]
+    SizeMismatch {
+        len: usize,
+        width: usize,
+        height: usize,
+    },
+}
+
+impl GridError {
+    /// Points the error at the offending line of the text the grid was
+    /// parsed from.
+    #[must_use]
+    pub fn locate(&self, input: &str) -> ParseError {
+        match *self {
+            Self::RaggedRow { row, expected, .. } => {
+                let line = input.lines().nth(row).unwrap_or_default();
+                ParseError::at(input, line, self.to_string())
+                    .with_label(format!("expected {expected} cells"))
+            }
+            Self::SizeMismatch { .. } => ParseError::new(input, (0, 0), self.to_string()),
+        }
+    }
+}
+
+/// A dense, row-major 2D grid.
+///
+/// The grid may carry a border of `padding` sentinel cells on every side.
+/// Positions always address the logical (unpadded) area, but
+/// [`Grid::adjacent8`] reads through the border, which lets neighbour
+/// counting loops skip bounds checks on edge cells entirely.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    data: Vec<T>,
+    width: usize,
+    height: usize,
+    padding: usize,
+}
+
+impl<T> Grid<T> {
+    /// Wraps row-major `data` as a `width x height` grid.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::SizeMismatch`] when `data` does not hold exactly
+    /// `width * height` cells.
+    pub fn from_vec(width: usize, height: usize, data: Vec<T>) -> Result<Self, GridError> {
+        if data.len() != width * height {
+            return Err(GridError::SizeMismatch {
+                len: data.len(),
+                width,
+                height,
+            });
+        }
+        Ok(Self {
+            data,
+            width,
+            height,
+            padding: 0,
+        })
+    }
+
+    /// Builds a grid from equally sized rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::RaggedRow`] for the first row whose length differs
+    /// from the first one.
+    pub fn from_rows<R>(rows: impl IntoIterator<Item = R>) -> Result<Self, GridError>
+    where
+        R: IntoIterator<Item = T>,
+    {
+        let mut data = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for (row, cells) in rows.into_iter().enumerate() {
+            let before = data.len();
+            data.extend(cells);
+            let found = data.len() - before;
+            let expected = *width.get_or_insert(found);
+            if found != expected {
+                return Err(GridError::RaggedRow {
+                    row,
+                    expected,
+                    found,
+                });
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            data,
+            width: width.unwrap_or(0),
+            height,
+            padding: 0,
+        })
+    }
+
+    /// Parses one cell per character, one row per line.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::RaggedRow`] if lines have different lengths.
+    pub fn parse_with(input: &str, mut cell: impl FnMut(char) -> T) -> Result<Self, GridError> {
+        Self::from_rows(
+            input
+                .lines()
+                .map(|line| line.chars().map(&mut cell).collect::<Vec<_>>()),
+        )
+    }
+
+    /// Logical width, excluding padding.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Logical height, excluding padding.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of sentinel cells on each side.
+    #[must_use]
+    pub const fn padding(&self) -> usize {
+        self.padding
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    #[must_use]
+    pub const fn in_bounds(&self, pos: Pos) -> bool {
+        pos.row < self.height && pos.col < self.width
+    }
+
+    const fn stride(&self) -> usize {
+        self.width + 2 * self.padding
+    }
+
+    const fn index_of(&self, pos: Pos) -> usize {
+        (pos.row + self.padding) * self.stride() + pos.col + self.padding
+    }
+
+    #[must_use]
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.in_bounds(pos).then(|| &self.data[self.index_of(pos)])
+    }
+
+    #[must_use]
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.in_bounds(pos) {
+            let idx = self.index_of(pos);
+            Some(&mut self.data[idx])
+        } else {
+            None
+        }
+    }
+
+    /// Moves one cell towards `dir`, staying inside the logical area.
+    #[must_use]
+    pub fn step(&self, pos: Pos, dir: Dir) -> Option<Pos> {
+        pos.step(dir).filter(|&next| self.in_bounds(next))
+    }
+
+    /// In-bounds orthogonal neighbours of `pos`.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Dir::ORTHOGONAL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// In-bounds orthogonal and diagonal neighbours of `pos`.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Dir::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// Values of the eight cells around `pos`, including padding sentinels.
+    ///
+    /// Without padding, cells outside the grid are simply skipped.
+    pub fn adjacent8(&self, pos: Pos) -> impl Iterator<Item = &T> + '_ {
+        let total_rows = self.height + 2 * self.padding;
+        let stride = self.stride();
+        let (pr, pc) = (pos.row + self.padding, pos.col + self.padding);
+        Dir::ALL.into_iter().filter_map(move |dir| {
+            let (dr, dc) = dir.delta();
+            let r = pr.checked_add_signed(dr).filter(|&r| r < total_rows)?;
+            let c = pc.checked_add_signed(dc).filter(|&c| c < stride)?;
+            Some(&self.data[r * stride + c])
+        })
+    }
+
+    /// Cells visited when walking from `start` towards `dir`, `start` first.
+    pub fn ray(&self, start: Pos, dir: Dir) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(Some(start).filter(|&p| self.in_bounds(p)), move |&p| {
+            self.step(p, dir)
+        })
+    }
+
+    /// Logical row `row` as a slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row >= self.height()`.
+    #[must_use]
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.height, "row {row} out of bounds");
+        let start = self.index_of(Pos::new(row, 0));
+        &self.data[start..start + self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|r| self.row(r))
+    }
+
+    /// Cells of column `col`, top to bottom.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> + '_ {
+        (0..self.height).filter_map(move |row| self.get(Pos::new(row, col)))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|c| self.column(c))
+    }
+
+    /// Every `\` diagonal (walking south-east), starting from the bottom-left.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = Pos> + '_> + '_ {
+        let left = (0..self.height).rev().map(|row| Pos::new(row, 0));
+        let top = (1..self.width).map(|col| Pos::new(0, col));
+        left.chain(top).map(|start| self.ray(start, Dir::SE))
+    }
+
+    /// Every `/` diagonal (walking south-west), starting from the top-left.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = Pos> + '_> + '_ {
+        let top = (0..self.width).map(|col| Pos::new(0, col));
+        let right = (1..self.height).map(|row| Pos::new(row, self.width.saturating_sub(1)));
+        top.chain(right).map(|start| self.ray(start, Dir::SW))
+    }
+
+    /// All logical positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| Pos::new(row, col)))
+    }
+
+    /// All logical cells with their positions, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        self.positions().map(|pos| (pos, &self[pos]))
+    }
+
+    /// Applies `f` to every cell, padding included.
+    #[must_use]
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            data: self.data.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+            padding: self.padding,
+        }
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// First position holding `value`, in row-major order.
+    #[must_use]
+    pub fn find(&self, value: &T) -> Option<Pos> {
+        self.positions().find(|&pos| self[pos] == *value)
+    }
+
+    /// Every position holding `value`, in row-major order.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Pos> + 'a {
+        self.positions().filter(move |&pos| self[pos] == *value)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a `width x height` grid with every cell set to `fill`.
+    #[must_use]
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            data: vec![fill; width * height],
+            width,
+            height,
+            padding: 0,
+        }
+    }
+
+    /// Surrounds the grid with `padding` extra rings of `fill`, replacing any
+    /// existing padding.
+    #[must_use]
+    pub fn with_padding(self, padding: usize, fill: T) -> Self {
+        let stride = self.width + 2 * padding;
+        let mut data = vec![fill; stride * (self.height + 2 * padding)];
+        for (pos, value) in self.iter() {
+            data[(pos.row + padding) * stride + pos.col + padding] = value.clone();
+        }
+        Self {
+            data,
+            width: self.width,
+            height: self.height,
+            padding,
+        }
+    }
+
+    fn rebuild(&self, width: usize, height: usize, source: impl Fn(Pos) -> Pos) -> Self {
+        let padding = self.padding;
+        let mut grid = Self {
+            data: Vec::with_capacity(width * height),
+            width,
+            height,
+            padding: 0,
+        };
+        for row in 0..height {
+            for col in 0..width {
+                grid.data.push(self[source(Pos::new(row, col))].clone());
+            }
+        }
+        match self.data.first() {
+            Some(fill) if padding > 0 => grid.with_padding(padding, fill.clone()),
+            _ => grid,
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal.
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        self.rebuild(self.height, self.width, |p| Pos::new(p.col, p.row))
+    }
+
+    /// Rotates the grid 90 degrees clockwise.
+    #[must_use]
+    pub fn rotate_cw(&self) -> Self {
+        let height = self.height;
+        self.rebuild(self.height, self.width, |p| {
+            Pos::new(height - 1 - p.col, p.row)
+        })
+    }
+
+    /// Rotates the grid 90 degrees counter-clockwise.
+    #[must_use]
+    pub fn rotate_ccw(&self) -> Self {
+        let width = self.width;
+        self.rebuild(self.height, self.width, |p| {
+            Pos::new(p.col, width - 1 - p.row)
+        })
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        assert!(self.in_bounds(pos), "{pos:?} out of bounds");
+        &self.data[self.index_of(pos)]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        assert!(self.in_bounds(pos), "{pos:?} out of bounds");
+        let idx = self.index_of(pos);
+        &mut self.data[idx]
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, |c| c)
+    }
+}
+
+impl FromStr for Grid<u8> {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_rows(s.lines().map(str::bytes))
+    }
+}
+
+/// Renders the logical area, one line per row.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (r, row) in self.rows().enumerate() {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "abc\ndef";
+
+    fn sample() -> Grid<char> {
+        SAMPLE.parse().expect("sample grid is rectangular")
+    }
+
+    #[test]
+    fn test_parse_dimensions() {
+        let grid = sample();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[Pos::new(1, 2)], 'f');
+        assert_eq!(grid.get(Pos::new(2, 0)), None);
+    }
+
+    #[test]
+    fn test_parse_ragged_row() {
+        let err = "ab\nc"
+            .parse::<Grid<u8>>()
+            .expect_err("rows differ in length");
+        assert_eq!(
+            err,
+            GridError::RaggedRow {
+                row: 1,
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_ragged_row_points_at_line() {
+        let input = "ab\nc\nde";
+        let err = input
+            .parse::<Grid<u8>>()
+            .expect_err("rows differ in length")
+            .locate(input);
+        assert_eq!(err.span(), (3, 1).into());
+        assert_eq!(err.line_col(), (2, 1));
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        let grid: Grid<u8> = "".parse().expect("empty input is a valid grid");
+        assert!(grid.is_empty());
+        assert_eq!(grid.positions().count(), 0);
+    }
+
+    #[test]
+    fn test_from_vec_size_mismatch() {
+        assert!(Grid::from_vec(2, 2, vec![0; 3]).is_err());
+        assert!(Grid::from_vec(2, 2, vec![0; 4]).is_ok());
+    }
+
+    #[test]
+    fn test_dir_rotations() {
+        assert_eq!(Dir::N.turn_right(), Dir::E);
+        assert_eq!(Dir::N.turn_left(), Dir::W);
+        assert_eq!(Dir::SE.opposite(), Dir::NW);
+        for dir in Dir::ALL {
+            let (dr, dc) = dir.delta();
+            let (or, oc) = dir.opposite().delta();
+            assert_eq!((dr + or, dc + oc), (0, 0));
+        }
+    }
+
+    #[test]
+    fn test_neighbors_respect_bounds() {
+        let grid = sample();
+        let corner: Vec<Pos> = grid.neighbors4(Pos::new(0, 0)).collect();
+        assert_eq!(corner, vec![Pos::new(0, 1), Pos::new(1, 0)]);
+        assert_eq!(grid.neighbors8(Pos::new(0, 1)).count(), 5);
+    }
+
+    #[test]
+    fn test_padding_keeps_logical_coordinates() {
+        let grid = sample().with_padding(1, '.');
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.padding(), 1);
+        assert_eq!(grid[Pos::new(0, 0)], 'a');
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.to_string(), SAMPLE);
+    }
+
+    #[test]
+    fn test_adjacent8_reads_padding() {
+        let padded = sample().with_padding(1, '.');
+        let around: String = padded.adjacent8(Pos::new(0, 0)).collect();
+        assert_eq!(around, "..bed...");
+
+        let bare: String = sample().adjacent8(Pos::new(0, 0)).collect();
+        assert_eq!(bare, "bed");
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = sample();
+        let rows: Vec<String> = grid.rows().map(|r| r.iter().collect()).collect();
+        assert_eq!(rows, vec!["abc", "def"]);
+        let cols: Vec<String> = grid.columns().map(Iterator::collect).collect();
+        assert_eq!(cols, vec!["ad", "be", "cf"]);
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let grid = sample();
+        let diag: Vec<String> = grid
+            .diagonals()
+            .map(|d| d.map(|p| grid[p]).collect())
+            .collect();
+        assert_eq!(diag, vec!["d", "ae", "bf", "c"]);
+        let anti: Vec<String> = grid
+            .anti_diagonals()
+            .map(|d| d.map(|p| grid[p]).collect())
+            .collect();
+        assert_eq!(anti, vec!["a", "bd", "ce", "f"]);
+    }
+
+    #[test]
+    fn test_ray_stops_at_edge() {
+        let grid = sample();
+        let ray: Vec<Pos> = grid.ray(Pos::new(0, 0), Dir::E).collect();
+        assert_eq!(ray.len(), 3);
+        assert_eq!(grid.ray(Pos::new(5, 5), Dir::E).count(), 0);
+    }
+
+    #[test]
+    fn test_find() {
+        let grid: Grid<char> = "S.#\n#.#".parse().expect("rectangular grid");
+        assert_eq!(grid.find(&'S'), Some(Pos::new(0, 0)));
+        assert_eq!(grid.find(&'x'), None);
+        let walls: Vec<Pos> = grid.find_all(&'#').collect();
+        assert_eq!(walls, vec![Pos::new(0, 2), Pos::new(1, 0), Pos::new(1, 2)]);
+    }
+
+    #[test]
+    fn test_transpose_and_rotate() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    }
+
+    #[test]
+    fn test_rotate_keeps_padding() {
+        let rotated = sample().with_padding(1, '.').rotate_cw();
+        assert_eq!(rotated.padding(), 1);
+        assert_eq!(
+            rotated
+                .adjacent8(Pos::new(0, 0))
+                .filter(|&&c| c == '.')
+                .count(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_map_and_index_mut() {
+        let mut grid = sample().map(char::is_ascii_lowercase);
+        grid[Pos::new(1, 1)] = false;
+        assert_eq!(grid.iter().filter(|(_, v)| **v).count(), 5);
+    }
+}
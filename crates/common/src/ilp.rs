@@ -0,0 +1,720 @@
+//! Small integer linear programs, solved exactly.
+//!
+//! A [`Problem`] minimises an integer objective over integer variables
+//! subject to linear constraints and per-variable bounds. [`Problem::solve`]
+//! runs branch and bound: each node solves the linear relaxation with a
+//! two-phase simplex over exact rationals, then splits on the first
+//! fractional variable. Everything is dense and rebuilt per node, which
+//! suits puzzles with tens of variables, not industrial models.
+//!
+//! Branch and bound over unbounded variables need not terminate (think
+//! `2x - 2y = 1`), so the search gives up after a [node
+//! limit](Problem::node_limit). The rationals are `i128` fractions; models
+//! whose intermediate values outgrow that panic rather than wrap, so keep
+//! coefficients puzzle-sized.
+
+use std::cmp::Ordering;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use thiserror::Error;
+
+use crate::linalg::gcd;
+
+/// How a constraint's left-hand side relates to its right-hand side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Le,
+    Eq,
+    Ge,
+}
+
+impl Relation {
+    const fn flip(self) -> Self {
+        match self {
+            Self::Le => Self::Ge,
+            Self::Eq => Self::Eq,
+            Self::Ge => Self::Le,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Constraint {
+    coeffs: Vec<i64>,
+    relation: Relation,
+    rhs: i64,
+}
+
+/// A minimisation problem over integer variables, built up constraint by
+/// constraint.
+///
+/// Variables default to the bounds `0..` (non-negative, no upper bound).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    objective: Vec<i64>,
+    constraints: Vec<Constraint>,
+    lower: Vec<i64>,
+    upper: Vec<Option<i64>>,
+    node_limit: usize,
+}
+
+/// An optimal integer point and its objective value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub values: Vec<i64>,
+    pub objective: i64,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum IlpError {
+    #[error("no integer point satisfies the constraints")]
+    Infeasible,
+    #[error("the objective has no lower bound")]
+    Unbounded,
+    #[error("gave up after {0} branch-and-bound nodes")]
+    NodeLimit(usize),
+}
+
+/// Relaxations solved before [`Problem::solve`] gives up, unless
+/// [overridden](Problem::node_limit).
+pub const DEFAULT_NODE_LIMIT: usize = 100_000;
+
+impl Problem {
+    /// Minimise `sum(objective[i] * x[i])`, over as many variables as there
+    /// are coefficients.
+    #[must_use]
+    pub fn minimize(objective: Vec<i64>) -> Self {
+        let vars = objective.len();
+        Self {
+            objective,
+            constraints: Vec::new(),
+            lower: vec![0; vars],
+            upper: vec![None; vars],
+            node_limit: DEFAULT_NODE_LIMIT,
+        }
+    }
+
+    /// Adds the constraint `sum(coeffs[i] * x[i]) <relation> rhs`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not one coefficient per variable.
+    #[must_use]
+    pub fn subject_to(mut self, coeffs: Vec<i64>, relation: Relation, rhs: i64) -> Self {
+        assert_eq!(coeffs.len(), self.vars(), "one coefficient per variable");
+        self.constraints.push(Constraint {
+            coeffs,
+            relation,
+            rhs,
+        });
+        self
+    }
+
+    /// Restricts `x[var]` to `lower..=upper`, or to `lower..` without an
+    /// upper bound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `var` is out of bounds.
+    #[must_use]
+    pub fn bounded(mut self, var: usize, lower: i64, upper: Option<i64>) -> Self {
+        self.lower[var] = lower;
+        self.upper[var] = upper;
+        self
+    }
+
+    /// Gives up with [`IlpError::NodeLimit`] after solving `limit`
+    /// relaxations.
+    #[must_use]
+    pub const fn node_limit(mut self, limit: usize) -> Self {
+        self.node_limit = limit;
+        self
+    }
+
+    #[must_use]
+    pub fn vars(&self) -> usize {
+        self.objective.len()
+    }
+
+    /// Finds an optimal integer point.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IlpError::Infeasible`] if no integer point satisfies the
+    /// constraints, [`IlpError::Unbounded`] if the linear relaxation has no
+    /// minimum, and [`IlpError::NodeLimit`] if the search does not finish
+    /// within the node limit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an intermediate fraction overflows `i128`.
+    pub fn solve(&self) -> Result<Solution, IlpError> {
+        let mut best: Option<Solution> = None;
+        let mut stack = vec![(self.lower.clone(), self.upper.clone())];
+        let mut nodes = 0;
+
+        while let Some((lower, upper)) = stack.pop() {
+            if nodes == self.node_limit {
+                return Err(IlpError::NodeLimit(nodes));
+            }
+            nodes += 1;
+            let (value, x) = match self.relax(&lower, &upper) {
+                Relaxation::Optimal { value, x } => (value, x),
+                Relaxation::Infeasible => continue,
+                Relaxation::Unbounded => return Err(IlpError::Unbounded),
+            };
+            // Integer coefficients give integer objectives, so the bound
+            // rounds up.
+            if best
+                .as_ref()
+                .is_some_and(|b| value.ceil() >= b.objective.into())
+            {
+                continue;
+            }
+
+            let Some(var) = x.iter().position(|v| !v.is_integer()) else {
+                best = Some(Solution {
+                    values: x.iter().map(|v| v.to_i64()).collect(),
+                    objective: value.to_i64(),
+                });
+                continue;
+            };
+
+            // Explore `x[var] <= floor` first, then `x[var] >= ceil`.
+            let mut up = lower.clone();
+            up[var] = x[var].ceil().to_i64();
+            stack.push((up, upper.clone()));
+            let mut down = upper;
+            down[var] = Some(x[var].floor().to_i64());
+            stack.push((lower, down));
+        }
+
+        best.ok_or(IlpError::Infeasible)
+    }
+
+    /// Solves the linear relaxation within the given bounds.
+    ///
+    /// Each upper bound costs a tableau row, and the problem's own bounds
+    /// are usually slack, so those only become rows once a relaxed optimum
+    /// breaks them. Bounds tightened by branching are always rows: the
+    /// branch exists because the parent broke them.
+    fn relax(&self, lower: &[i64], upper: &[Option<i64>]) -> Relaxation {
+        let mut enforced: Vec<bool> = upper
+            .iter()
+            .zip(&self.upper)
+            .map(|(u, own)| u != own)
+            .collect();
+        loop {
+            let relaxation = self.relax_enforcing(lower, upper, &enforced);
+            let broken: Vec<usize> = match &relaxation {
+                Relaxation::Optimal { x, .. } => (0..x.len())
+                    .filter(|&var| {
+                        !enforced[var] && upper[var].is_some_and(|u| x[var] > Ratio::from(u))
+                    })
+                    .collect(),
+                // Dropped bounds might be what makes the objective bounded.
+                Relaxation::Unbounded => (0..upper.len())
+                    .filter(|&var| !enforced[var] && upper[var].is_some())
+                    .collect(),
+                Relaxation::Infeasible => Vec::new(),
+            };
+            if broken.is_empty() {
+                return relaxation;
+            }
+            for var in broken {
+                enforced[var] = true;
+            }
+        }
+    }
+
+    /// Solves the linear relaxation within `lower` and the `enforced` upper
+    /// bounds.
+    fn relax_enforcing(
+        &self,
+        lower: &[i64],
+        upper: &[Option<i64>],
+        enforced: &[bool],
+    ) -> Relaxation {
+        let n = self.vars();
+
+        // Shift every variable to `y = x - lower >= 0`; upper bounds become
+        // rows of their own.
+        let mut rows: Vec<(Vec<Ratio>, Relation, Ratio)> = Vec::new();
+        for c in &self.constraints {
+            let shift: i128 = c
+                .coeffs
+                .iter()
+                .zip(lower)
+                .map(|(&a, &l)| i128::from(a) * i128::from(l))
+                .sum();
+            let coeffs = c.coeffs.iter().map(|&a| Ratio::from(a)).collect();
+            rows.push((coeffs, c.relation, Ratio::int(i128::from(c.rhs) - shift)));
+        }
+        for (var, (&l, &u)) in lower.iter().zip(upper).enumerate() {
+            let Some(u) = u else { continue };
+            if !enforced[var] {
+                continue;
+            }
+            if u < l {
+                return Relaxation::Infeasible;
+            }
+            let mut coeffs = vec![Ratio::ZERO; n];
+            coeffs[var] = Ratio::ONE;
+            rows.push((
+                coeffs,
+                Relation::Le,
+                Ratio::int(i128::from(u) - i128::from(l)),
+            ));
+        }
+        for (coeffs, relation, rhs) in &mut rows {
+            if *rhs < Ratio::ZERO {
+                for a in coeffs.iter_mut() {
+                    *a = -*a;
+                }
+                *relation = relation.flip();
+                *rhs = -*rhs;
+            }
+        }
+
+        let slacks = rows.iter().filter(|r| r.1 != Relation::Eq).count();
+        let artificials = rows.iter().filter(|r| r.1 != Relation::Le).count();
+        let art_start = n + slacks;
+        let width = art_start + artificials;
+
+        let mut tableau = Tableau {
+            rows: Vec::with_capacity(rows.len()),
+            basis: Vec::with_capacity(rows.len()),
+            width,
+        };
+        let (mut slack, mut art) = (n, art_start);
+        for (coeffs, relation, rhs) in rows {
+            let mut row = coeffs;
+            row.resize(width + 1, Ratio::ZERO);
+            row[width] = rhs;
+            match relation {
+                Relation::Le => {
+                    row[slack] = Ratio::ONE;
+                    tableau.basis.push(slack);
+                    slack += 1;
+                }
+                Relation::Ge | Relation::Eq => {
+                    if relation == Relation::Ge {
+                        row[slack] = -Ratio::ONE;
+                        slack += 1;
+                    }
+                    row[art] = Ratio::ONE;
+                    tableau.basis.push(art);
+                    art += 1;
+                }
+            }
+            tableau.rows.push(row);
+        }
+
+        if artificials > 0 {
+            let mut cost = vec![Ratio::ZERO; width];
+            cost[art_start..].fill(Ratio::ONE);
+            tableau.minimize(&cost, width);
+            if tableau.value(&cost) > Ratio::ZERO {
+                return Relaxation::Infeasible;
+            }
+            tableau.drive_out(art_start);
+        }
+
+        let mut cost: Vec<Ratio> = self.objective.iter().map(|&c| Ratio::from(c)).collect();
+        cost.resize(width, Ratio::ZERO);
+        if !tableau.minimize(&cost, art_start) {
+            return Relaxation::Unbounded;
+        }
+
+        let mut x: Vec<Ratio> = lower.iter().map(|&l| Ratio::from(l)).collect();
+        for (row, &col) in tableau.rows.iter().zip(&tableau.basis) {
+            if col < n {
+                x[col] = x[col] + row[width];
+            }
+        }
+        let value = x
+            .iter()
+            .zip(&self.objective)
+            .fold(Ratio::ZERO, |acc, (&v, &c)| acc + v * Ratio::from(c));
+        Relaxation::Optimal { value, x }
+    }
+}
+
+enum Relaxation {
+    Optimal { value: Ratio, x: Vec<Ratio> },
+    Infeasible,
+    Unbounded,
+}
+
+/// A simplex tableau in canonical form: every row has a basic column with a
+/// one in it and zeros in every other row, and the last entry is the row's
+/// value.
+struct Tableau {
+    rows: Vec<Vec<Ratio>>,
+    basis: Vec<usize>,
+    width: usize,
+}
+
+impl Tableau {
+    /// Pivots to a basis minimising `cost`, letting only the columns before
+    /// `enter_below` into the basis. Returns false if the cost is unbounded.
+    ///
+    /// Bland's rule (lowest eligible column, then lowest basic column on
+    /// ties) rules out cycling.
+    fn minimize(&mut self, cost: &[Ratio], enter_below: usize) -> bool {
+        loop {
+            let entering = (0..enter_below)
+                .find(|&j| !self.basis.contains(&j) && self.reduced_cost(cost, j) < Ratio::ZERO);
+            let Some(col) = entering else {
+                return true;
+            };
+
+            let leaving = (0..self.rows.len())
+                .filter(|&i| self.rows[i][col] > Ratio::ZERO)
+                .min_by(|&a, &b| {
+                    let ratio = |i: usize| self.rows[i][self.width] / self.rows[i][col];
+                    ratio(a)
+                        .cmp(&ratio(b))
+                        .then(self.basis[a].cmp(&self.basis[b]))
+                });
+            let Some(row) = leaving else {
+                return false;
+            };
+            self.pivot(row, col);
+        }
+    }
+
+    fn reduced_cost(&self, cost: &[Ratio], col: usize) -> Ratio {
+        self.rows
+            .iter()
+            .zip(&self.basis)
+            .fold(cost[col], |acc, (row, &b)| acc - cost[b] * row[col])
+    }
+
+    fn value(&self, cost: &[Ratio]) -> Ratio {
+        self.rows
+            .iter()
+            .zip(&self.basis)
+            .fold(Ratio::ZERO, |acc, (row, &b)| {
+                acc + cost[b] * row[self.width]
+            })
+    }
+
+    fn pivot(&mut self, row: usize, col: usize) {
+        let p = self.rows[row][col];
+        for v in &mut self.rows[row] {
+            *v = *v / p;
+        }
+        let pivot_row = self.rows[row].clone();
+        for (i, other) in self.rows.iter_mut().enumerate() {
+            let factor = other[col];
+            if i == row || factor == Ratio::ZERO {
+                continue;
+            }
+            for (v, &q) in other.iter_mut().zip(&pivot_row) {
+                if q != Ratio::ZERO {
+                    *v = *v - factor * q;
+                }
+            }
+        }
+        self.basis[row] = col;
+    }
+
+    /// After a feasible phase one, swaps the artificial columns (from
+    /// `art_start` on) still in the basis for real ones, dropping rows that
+    /// turn out to be redundant.
+    fn drive_out(&mut self, art_start: usize) {
+        let mut i = 0;
+        while i < self.rows.len() {
+            if self.basis[i] < art_start {
+                i += 1;
+                continue;
+            }
+            if let Some(col) = (0..art_start).find(|&j| self.rows[i][j] != Ratio::ZERO) {
+                self.pivot(i, col);
+                i += 1;
+            } else {
+                self.rows.remove(i);
+                self.basis.remove(i);
+            }
+        }
+    }
+}
+
+/// An exact fraction in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Ratio {
+    num: i128,
+    den: i128,
+}
+
+impl Ratio {
+    const ZERO: Self = Self::int(0);
+    const ONE: Self = Self::int(1);
+
+    const fn int(num: i128) -> Self {
+        Self { num, den: 1 }
+    }
+
+    fn new(num: i128, den: i128) -> Self {
+        let g = gcd(num.abs(), den.abs()) * den.signum();
+        Self {
+            num: num / g,
+            den: den / g,
+        }
+    }
+
+    fn is_integer(self) -> bool {
+        self.den == 1
+    }
+
+    fn floor(self) -> Self {
+        Self::int(self.num.div_euclid(self.den))
+    }
+
+    fn ceil(self) -> Self {
+        Self::int(-(-self.num).div_euclid(self.den))
+    }
+
+    /// The value of an integral ratio.
+    fn to_i64(self) -> i64 {
+        debug_assert!(self.is_integer());
+        i64::try_from(self.num).expect("solution fits in i64")
+    }
+}
+
+/// `a * b`, panicking instead of wrapping; see the module docs.
+fn mul(a: i128, b: i128) -> i128 {
+    a.checked_mul(b).expect("ILP fraction overflowed i128")
+}
+
+/// `a + b`, panicking instead of wrapping; see the module docs.
+fn add(a: i128, b: i128) -> i128 {
+    a.checked_add(b).expect("ILP fraction overflowed i128")
+}
+
+impl From<i64> for Ratio {
+    fn from(n: i64) -> Self {
+        Self::int(n.into())
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        mul(self.num, other.den).cmp(&mul(other.num, self.den))
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Ratio {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // Tableaus are mostly zeros and integers; skip the gcd for those.
+        if self.den == 1 && rhs.den == 1 {
+            return Self::int(add(self.num, rhs.num));
+        }
+        Self::new(
+            add(mul(self.num, rhs.den), mul(rhs.num, self.den)),
+            mul(self.den, rhs.den),
+        )
+    }
+}
+
+impl Sub for Ratio {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for Ratio {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if self.den == 1 && rhs.den == 1 {
+            return Self::int(mul(self.num, rhs.num));
+        }
+        Self::new(mul(self.num, rhs.num), mul(self.den, rhs.den))
+    }
+}
+
+impl Div for Ratio {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        Self::new(mul(self.num, rhs.den), mul(self.den, rhs.num))
+    }
+}
+
+impl Neg for Ratio {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_ratio_arithmetic() {
+        let half = Ratio::new(1, 2);
+        let third = Ratio::new(-2, -6);
+        assert_eq!(half + third, Ratio::new(5, 6));
+        assert_eq!(half - third, Ratio::new(1, 6));
+        assert_eq!(half / third, Ratio::new(3, 2));
+        assert!(third < half);
+        assert_eq!(Ratio::new(-7, 2).floor(), Ratio::int(-4));
+        assert_eq!(Ratio::new(-7, 2).ceil(), Ratio::int(-3));
+    }
+
+    #[test]
+    fn test_relaxation_is_fractional_but_solution_is_not() {
+        // max x + y  s.t.  2x + 2y <= 3: the relaxation reaches 3/2.
+        let problem = Problem::minimize(vec![-1, -1]).subject_to(vec![2, 2], Relation::Le, 3);
+        let solution = problem.solve().expect("feasible");
+        assert_eq!(solution.objective, -1);
+    }
+
+    #[test]
+    fn test_knapsack() {
+        // Values 5, 4, 3 and weights 2, 3, 1 with capacity 5, each item at
+        // most once: items 0 and 1 fill it exactly for 9, beating the 8 of
+        // items 0 and 2.
+        let problem = Problem::minimize(vec![-5, -4, -3])
+            .subject_to(vec![2, 3, 1], Relation::Le, 5)
+            .bounded(0, 0, Some(1))
+            .bounded(1, 0, Some(1))
+            .bounded(2, 0, Some(1));
+        let solution = problem.solve().expect("feasible");
+        assert_eq!(solution.objective, -9);
+        assert_eq!(solution.values, [1, 1, 0]);
+    }
+
+    #[test]
+    fn test_shifted_lower_bounds() {
+        let problem = Problem::minimize(vec![1, 1])
+            .subject_to(vec![1, -1], Relation::Ge, 2)
+            .bounded(0, -5, Some(5))
+            .bounded(1, -3, None);
+        let solution = problem.solve().expect("feasible");
+        assert_eq!(solution.values, [-1, -3]);
+        assert_eq!(solution.objective, -4);
+    }
+
+    #[test]
+    fn test_infeasible_and_unbounded() {
+        let parity = Problem::minimize(vec![0, 0]).subject_to(vec![2, 4], Relation::Eq, 3);
+        assert_eq!(parity.solve(), Err(IlpError::Infeasible));
+
+        let contradiction = Problem::minimize(vec![1])
+            .subject_to(vec![1], Relation::Le, 1)
+            .subject_to(vec![1], Relation::Ge, 2);
+        assert_eq!(contradiction.solve(), Err(IlpError::Infeasible));
+
+        let open = Problem::minimize(vec![-1, 0]).subject_to(vec![1, -1], Relation::Eq, 0);
+        assert_eq!(open.solve(), Err(IlpError::Unbounded));
+    }
+
+    #[test]
+    fn test_node_limit_stops_endless_branching() {
+        // Every relaxation has a fractional point, but no integer one exists
+        // and nothing bounds the search.
+        let parity = Problem::minimize(vec![1, 1])
+            .subject_to(vec![2, -2], Relation::Eq, 1)
+            .node_limit(500);
+        assert_eq!(parity.solve(), Err(IlpError::NodeLimit(500)));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed i128")]
+    fn test_ratio_overflow_panics() {
+        let _ = Ratio::int(i128::MAX) * Ratio::int(2);
+    }
+
+    #[test]
+    fn test_redundant_equalities() {
+        let problem = Problem::minimize(vec![1, 2])
+            .subject_to(vec![1, 1], Relation::Eq, 4)
+            .subject_to(vec![2, 2], Relation::Eq, 8);
+        let solution = problem.solve().expect("feasible");
+        assert_eq!(solution.values, [4, 0]);
+    }
+
+    fn brute_force(problem: &Problem, side: i64) -> Option<i64> {
+        let mut x = vec![0; problem.vars()];
+        let mut best: Option<i64> = None;
+        loop {
+            let feasible = problem.constraints.iter().all(|c| {
+                let lhs: i64 = c.coeffs.iter().zip(&x).map(|(a, v)| a * v).sum();
+                match c.relation {
+                    Relation::Le => lhs <= c.rhs,
+                    Relation::Eq => lhs == c.rhs,
+                    Relation::Ge => lhs >= c.rhs,
+                }
+            });
+            if feasible {
+                let value = problem.objective.iter().zip(&x).map(|(c, v)| c * v).sum();
+                best = Some(best.map_or(value, |b| b.min(value)));
+            }
+            let Some(i) = (0..x.len()).find(|&i| x[i] < side) else {
+                return best;
+            };
+            x[i] += 1;
+            x[..i].fill(0);
+        }
+    }
+
+    fn problem() -> impl Strategy<Value = Problem> {
+        let relation = prop_oneof![Just(Relation::Le), Just(Relation::Eq), Just(Relation::Ge)];
+        (1..=3usize, 1..=3usize).prop_flat_map(move |(vars, rows)| {
+            let objective = prop::collection::vec(-3..=3i64, vars);
+            let constraints = prop::collection::vec(
+                (
+                    prop::collection::vec(-2..=3i64, vars),
+                    relation.clone(),
+                    -4..=8i64,
+                ),
+                rows,
+            );
+            (objective, constraints).prop_map(|(objective, constraints)| {
+                let vars = objective.len();
+                let mut problem = Problem::minimize(objective);
+                for (coeffs, relation, rhs) in constraints {
+                    problem = problem.subject_to(coeffs, relation, rhs);
+                }
+                (0..vars).fold(problem, |p, var| p.bounded(var, 0, Some(4)))
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_solve_matches_brute_force(problem in problem()) {
+            let expected = brute_force(&problem, 4);
+            match problem.solve() {
+                Ok(solution) => {
+                    prop_assert_eq!(Some(solution.objective), expected);
+                    prop_assert!(solution.values.iter().all(|v| (0..=4).contains(v)));
+                }
+                Err(err) => {
+                    prop_assert_eq!(err, IlpError::Infeasible);
+                    prop_assert_eq!(expected, None);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Runtime puzzle-input loading.
+//!
+//! Inputs are looked up in this order, first match wins:
+//!
+//! 1. an explicit path passed on the command line (`-` reads stdin),
+//! 2. the `AOC_INPUT` environment variable (a path, or `-` for stdin),
+//! 3. `<inputs dir>/<year>/day-<dd>.txt`, where the inputs directory is
+//!    `AOC_INPUTS_DIR` or `inputs/` at the workspace root,
+//! 4. the `input<part>.txt` checked in next to the day crate, then its
+//!    `input1.txt`, since both parts usually read the same input.
+
+use std::ffi::OsString;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Overrides every lookup with a single file, or `-` for stdin.
+pub const INPUT_ENV: &str = "AOC_INPUT";
+/// Directory holding `<year>/day-<dd>.txt` files.
+pub const INPUTS_DIR_ENV: &str = "AOC_INPUTS_DIR";
+
+/// Identifies one puzzle part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+}
+
+impl Puzzle {
+    #[must_use]
+    pub const fn new(year: u16, day: u8, part: u8) -> Self {
+        Self { year, day, part }
+    }
+
+    /// Loads the input using the environment only.
+    ///
+    /// # Errors
+    ///
+    /// See [`InputConfig::load`].
+    pub fn load(&self) -> Result<String, InputError> {
+        InputConfig::from_env().load(*self, None)
+    }
+
+    /// Loads the input, treating the first command-line argument (if any) as
+    /// an explicit path. This is what the per-day binaries use.
+    ///
+    /// # Errors
+    ///
+    /// See [`InputConfig::load`].
+    pub fn load_from_args(&self) -> Result<String, InputError> {
+        self.load_from_arg(std::env::args_os().nth(1))
+    }
+
+    /// Loads the input, treating `arg` (if any) as an explicit path. For
+    /// binaries that take flags besides the path.
+    ///
+    /// # Errors
+    ///
+    /// See [`InputConfig::load`].
+    pub fn load_from_arg(&self, arg: Option<OsString>) -> Result<String, InputError> {
+        InputConfig::from_env().load(*self, arg.map(Source::from_arg))
+    }
+
+    /// Loads the input like [`load_from_args`](Self::load_from_args), for
+    /// binaries that also take switches such as `--verbose`. Reports which
+    /// of `flags` were passed, in the same order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InputError::UnknownFlag`] for a `--` argument not in
+    /// `flags`, otherwise see [`InputConfig::load`].
+    pub fn load_with_flags<const N: usize>(
+        &self,
+        flags: [&str; N],
+    ) -> Result<([bool; N], String), InputError> {
+        let (present, path) = split_flags(std::env::args_os().skip(1), flags)?;
+        Ok((present, self.load_from_arg(path)?))
+    }
+}
+
+/// Separates the `flags` among `args` from the first path.
+fn split_flags<const N: usize>(
+    args: impl IntoIterator<Item = OsString>,
+    flags: [&str; N],
+) -> Result<([bool; N], Option<OsString>), InputError> {
+    let mut present = [false; N];
+    let mut path = None;
+    for arg in args {
+        let text = arg.to_string_lossy();
+        if let Some(i) = flags.iter().position(|&flag| text == flag) {
+            present[i] = true;
+        } else if text.starts_with("--") {
+            return Err(InputError::UnknownFlag {
+                flag: text.into_owned(),
+                expected: flags.map(|flag| format!("`{flag}`")).join(", "),
+            });
+        } else if path.is_none() {
+            path = Some(arg);
+        }
+    }
+    Ok((present, path))
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} day {:02} part {}", self.year, self.day, self.part)
+    }
+}
+
+/// Where an input is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// Interprets a command-line or environment value: `-` means stdin.
+    #[must_use]
+    pub fn from_arg(arg: impl Into<OsString>) -> Self {
+        let arg = arg.into();
+        if arg == "-" {
+            Self::Stdin
+        } else {
+            Self::File(arg.into())
+        }
+    }
+
+    /// Reads the whole source into a string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InputError::Read`] or [`InputError::Stdin`] when reading fails.
+    pub fn read(&self) -> Result<String, InputError> {
+        match self {
+            Self::File(path) => std::fs::read_to_string(path).map_err(|source| InputError::Read {
+                path: path.clone(),
+                source,
+            }),
+            Self::Stdin => {
+                let mut buffer = String::new();
+                io::stdin()
+                    .read_to_string(&mut buffer)
+                    .map_err(InputError::Stdin)?;
+                Ok(buffer)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum InputError {
+    #[error("no input found for {puzzle}")]
+    #[diagnostic(
+        code(common::input::not_found),
+        help(
+            "searched {searched}. Pass a path (or `-` for stdin), set AOC_INPUT, \
+             or put the file under AOC_INPUTS_DIR"
+        )
+    )]
+    NotFound { puzzle: Puzzle, searched: String },
+
+    #[error("failed to read input from {}", path.display())]
+    #[diagnostic(code(common::input::read))]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("failed to read input from stdin")]
+    #[diagnostic(code(common::input::stdin))]
+    Stdin(#[source] io::Error),
+
+    #[error("unknown flag `{flag}`")]
+    #[diagnostic(
+        code(common::input::unknown_flag),
+        help("this binary takes an input path and {expected}")
+    )]
+    UnknownFlag { flag: String, expected: String },
+}
+
+/// The lookup settings, normally taken from the environment.
+///
+/// Kept as plain data so resolution can be exercised without touching the
+/// process environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputConfig {
+    /// Overrides every lookup (`AOC_INPUT`).
+    pub input: Option<Source>,
+    /// `AOC_INPUTS_DIR`, defaulting to `inputs/` at the workspace root.
+    pub inputs_dir: PathBuf,
+    /// Directory containing `years/`, for the checked-in fallbacks.
+    pub workspace_dir: PathBuf,
+}
+
+impl InputConfig {
+    /// Reads `AOC_INPUT` and `AOC_INPUTS_DIR`.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let workspace_dir = workspace_dir();
+        Self {
+            input: std::env::var_os(INPUT_ENV).map(Source::from_arg),
+            inputs_dir: std::env::var_os(INPUTS_DIR_ENV)
+                .map_or_else(|| workspace_dir.join("inputs"), PathBuf::from),
+            workspace_dir,
+        }
+    }
+
+    /// The canonical cached input for a day, shared by both parts.
+    #[must_use]
+    pub fn cache_path(&self, year: u16, day: u8) -> PathBuf {
+        self.inputs_dir
+            .join(year.to_string())
+            .join(format!("day-{day:02}.txt"))
+    }
+
+    /// Files tried for `puzzle` when no explicit source is given, in order.
+    #[must_use]
+    pub fn candidates(&self, puzzle: Puzzle) -> Vec<PathBuf> {
+        let day_dir = self
+            .workspace_dir
+            .join("years")
+            .join(puzzle.year.to_string())
+            .join(format!("day-{:02}", puzzle.day));
+        let mut candidates = vec![
+            self.cache_path(puzzle.year, puzzle.day),
+            day_dir.join(format!("input{}.txt", puzzle.part)),
+        ];
+        if puzzle.part != 1 {
+            candidates.push(day_dir.join("input1.txt"));
+        }
+        candidates
+    }
+
+    /// Picks the source for `puzzle` without reading it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InputError::NotFound`] when nothing is configured and none of
+    /// the [candidates](Self::candidates) exists.
+    pub fn resolve(&self, puzzle: Puzzle, explicit: Option<Source>) -> Result<Source, InputError> {
+        if let Some(source) = explicit.or_else(|| self.input.clone()) {
+            return Ok(source);
+        }
+
+        let candidates = self.candidates(puzzle);
+        if let Some(found) = candidates.iter().find(|path| path.is_file()) {
+            return Ok(Source::File(found.clone()));
+        }
+
+        let searched = candidates
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(InputError::NotFound { puzzle, searched })
+    }
+
+    /// Resolves and reads the input for `puzzle`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InputError::NotFound`] when no input can be located, or a read
+    /// error when the chosen source cannot be read.
+    pub fn load(&self, puzzle: Puzzle, explicit: Option<Source>) -> Result<String, InputError> {
+        self.resolve(puzzle, explicit)?.read()
+    }
+}
+
+/// The workspace root, two levels above this crate.
+#[must_use]
+pub fn workspace_dir() -> PathBuf {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest
+        .parent()
+        .and_then(Path::parent)
+        .unwrap_or(manifest)
+        .to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: Puzzle = Puzzle::new(2025, 8, 2);
+
+    fn config(root: &Path) -> InputConfig {
+        InputConfig {
+            input: None,
+            inputs_dir: root.join("inputs"),
+            workspace_dir: root.to_path_buf(),
+        }
+    }
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().expect("file has a parent"))
+            .expect("create parent directories");
+        std::fs::write(path, contents).expect("write input file");
+    }
+
+    #[test]
+    fn test_source_from_arg() {
+        assert_eq!(Source::from_arg("-"), Source::Stdin);
+        assert_eq!(
+            Source::from_arg("in.txt"),
+            Source::File(PathBuf::from("in.txt"))
+        );
+    }
+
+    #[test]
+    fn test_split_flags() {
+        let args = |list: &[&str]| list.iter().map(OsString::from).collect::<Vec<_>>();
+
+        let (present, path) =
+            split_flags(args(&["in.txt", "--show"]), ["--verbose", "--show"]).expect("known flags");
+        assert_eq!(present, [false, true]);
+        assert_eq!(path, Some(OsString::from("in.txt")));
+
+        let (present, path) = split_flags(args(&["-"]), ["--show"]).expect("stdin path");
+        assert_eq!(present, [false]);
+        assert_eq!(path, Some(OsString::from("-")));
+
+        let err =
+            split_flags(args(&["--verbsoe", "in.txt"]), ["--verbose"]).expect_err("misspelt flag");
+        assert!(matches!(&err, InputError::UnknownFlag { flag, .. } if flag == "--verbsoe"));
+    }
+
+    #[test]
+    fn test_puzzle_display() {
+        assert_eq!(PUZZLE.to_string(), "2025 day 08 part 2");
+    }
+
+    #[test]
+    fn test_inputs_dir_wins_over_crate_fallback() {
+        let root = tempfile::tempdir().expect("temp dir");
+        let config = config(root.path());
+        write(
+            &root.path().join("years/2025/day-08/input2.txt"),
+            "fallback",
+        );
+        assert_eq!(
+            config.load(PUZZLE, None).expect("fallback input"),
+            "fallback"
+        );
+
+        write(&root.path().join("inputs/2025/day-08.txt"), "cached");
+        assert_eq!(config.load(PUZZLE, None).expect("cached input"), "cached");
+    }
+
+    #[test]
+    fn test_part_two_falls_back_to_part_one_input() {
+        let root = tempfile::tempdir().expect("temp dir");
+        let config = config(root.path());
+        write(&root.path().join("years/2025/day-08/input1.txt"), "shared");
+        assert_eq!(config.load(PUZZLE, None).expect("part 1 input"), "shared");
+
+        write(&root.path().join("years/2025/day-08/input2.txt"), "own");
+        assert_eq!(config.load(PUZZLE, None).expect("part 2 input"), "own");
+    }
+
+    #[test]
+    fn test_explicit_path_and_env_override() {
+        let root = tempfile::tempdir().expect("temp dir");
+        let mut config = config(root.path());
+        write(&root.path().join("inputs/2025/day-08.txt"), "cached");
+        write(&root.path().join("env.txt"), "from env");
+        write(&root.path().join("cli.txt"), "from cli");
+
+        config.input = Some(Source::File(root.path().join("env.txt")));
+        assert_eq!(config.load(PUZZLE, None).expect("env input"), "from env");
+
+        let explicit = Source::File(root.path().join("cli.txt"));
+        assert_eq!(
+            config.load(PUZZLE, Some(explicit)).expect("cli input"),
+            "from cli"
+        );
+    }
+
+    #[test]
+    fn test_missing_input_lists_searched_paths() {
+        let root = tempfile::tempdir().expect("temp dir");
+        let err = config(root.path())
+            .load(PUZZLE, None)
+            .expect_err("nothing on disk");
+        let InputError::NotFound { puzzle, searched } = &err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(*puzzle, PUZZLE);
+        assert!(searched.contains("day-08.txt"));
+        assert!(searched.contains("input2.txt"));
+        assert!(searched.contains("input1.txt"));
+    }
+
+    #[test]
+    fn test_unreadable_explicit_path() {
+        let root = tempfile::tempdir().expect("temp dir");
+        let missing = Source::File(root.path().join("missing.txt"));
+        let err = config(root.path())
+            .load(PUZZLE, Some(missing))
+            .expect_err("file does not exist");
+        assert!(matches!(err, InputError::Read { .. }));
+    }
+
+    #[test]
+    fn test_workspace_dir_contains_years() {
+        assert!(workspace_dir().join("years").is_dir());
+    }
+}
@@ -0,0 +1,557 @@
+use std::cmp::{max, min};
+use std::ops::{Add, Sub};
+
+/// Integer-like bounds that closed intervals can be built from.
+///
+/// Closed intervals need to know a value's neighbours: `[1, 3]` and `[4, 6]`
+/// touch and merge, and removing `[2, 2]` from `[1, 3]` leaves `[1, 1]` and
+/// `[3, 3]`.
+pub trait Discrete: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_succ(self) -> Option<Self>;
+    fn checked_pred(self) -> Option<Self>;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {
+        $(
+            impl Discrete for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn checked_pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_discrete!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// A closed interval `[start, end]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Ord + Copy> Interval<T> {
+    #[must_use]
+    pub const fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        value >= self.start && value <= self.end
+    }
+
+    #[must_use]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// The overlap of both intervals, if any.
+    #[must_use]
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        self.overlaps(other)
+            .then(|| Self::new(max(self.start, other.start), min(self.end, other.end)))
+    }
+}
+
+impl<T: Discrete> Interval<T> {
+    /// Builds the inclusive interval `start..=end`, or `None` when it is empty.
+    #[must_use]
+    pub fn inclusive(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Self::new(start, end))
+    }
+
+    /// Builds the half-open interval `start..end`, or `None` when it is empty.
+    #[must_use]
+    pub fn exclusive(start: T, end: T) -> Option<Self> {
+        (start < end).then(|| Self::new(start, end - T::ONE))
+    }
+
+    /// Number of integers covered by the interval.
+    #[must_use]
+    pub fn count(&self) -> T {
+        self.end - self.start + T::ONE
+    }
+}
+
+/// A set of integers stored as sorted, disjoint, non-adjacent closed
+/// intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of disjoint intervals in the set.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The disjoint intervals, in ascending order.
+    #[must_use]
+    pub fn as_slice(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval<T>> {
+        self.intervals.iter()
+    }
+
+    /// The smallest interval enclosing the whole set.
+    #[must_use]
+    pub fn bounds(&self) -> Option<Interval<T>> {
+        let first = self.intervals.first()?;
+        let last = self.intervals.last()?;
+        Some(Interval::new(first.start, last.end))
+    }
+
+    /// Adds `interval`, merging it with every interval it overlaps or touches.
+    /// An inverted interval, with `start > end`, is empty and ignored.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.start > interval.end {
+            return;
+        }
+        // First interval whose end reaches `interval.start - 1`
+        let lo = self.intervals.partition_point(|i| {
+            i.end < interval.start && i.end.checked_succ() != Some(interval.start)
+        });
+        // First interval starting past `interval.end + 1`
+        let hi = self.intervals.partition_point(|i| {
+            i.start <= interval.end || interval.end.checked_succ() == Some(i.start)
+        });
+
+        let merged = self.intervals[lo..hi].iter().fold(interval, |acc, i| {
+            Interval::new(min(acc.start, i.start), max(acc.end, i.end))
+        });
+        self.intervals.splice(lo..hi, [merged]);
+    }
+
+    /// Adds the inclusive range `start..=end`. Empty ranges are ignored.
+    pub fn insert_inclusive(&mut self, start: T, end: T) {
+        if let Some(interval) = Interval::inclusive(start, end) {
+            self.insert(interval);
+        }
+    }
+
+    /// Adds the half-open range `start..end`. Empty ranges are ignored.
+    pub fn insert_exclusive(&mut self, start: T, end: T) {
+        if let Some(interval) = Interval::exclusive(start, end) {
+            self.insert(interval);
+        }
+    }
+
+    /// Removes every value of `interval` from the set.
+    pub fn remove(&mut self, interval: Interval<T>) {
+        *self = self.difference(&Self::from(interval));
+    }
+
+    /// Whether `value` is covered, in `O(log n)`.
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self.intervals.partition_point(|i| i.start <= value);
+        idx > 0 && self.intervals[idx - 1].end >= value
+    }
+
+    /// Total number of values covered by the set.
+    #[must_use]
+    pub fn total_len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::ZERO, |acc, i| acc + i.count())
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for &interval in &other.intervals {
+            result.insert(interval);
+        }
+        result
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (0, 0);
+        while let (Some(x), Some(y)) = (self.intervals.get(a), other.intervals.get(b)) {
+            if let Some(overlap) = x.intersect(y) {
+                intervals.push(overlap);
+            }
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        Self { intervals }
+    }
+
+    /// Values in `self` that are not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut cuts = other.intervals.iter().peekable();
+
+        for &interval in &self.intervals {
+            let mut rest = Some(interval);
+            while let (Some(current), Some(cut)) = (rest, cuts.peek()) {
+                if cut.end < current.start {
+                    cuts.next();
+                    continue;
+                }
+                if cut.start > current.end {
+                    break;
+                }
+                if let Some(before) = cut.start.checked_pred()
+                    && current.start <= before
+                {
+                    intervals.push(Interval::new(current.start, before));
+                }
+                rest = cut
+                    .end
+                    .checked_succ()
+                    .filter(|&after| after <= current.end)
+                    .map(|after| Interval::new(after, current.end));
+                if rest.is_some() {
+                    cuts.next();
+                }
+            }
+            intervals.extend(rest);
+        }
+        Self { intervals }
+    }
+}
+
+/// Like [`IntervalSet::insert`], an inverted interval gives the empty set.
+impl<T: Discrete> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        Self::from_iter([interval])
+    }
+}
+
+/// Merges as [`IntervalSet::insert`] does, skipping inverted intervals.
+impl<T: Discrete> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut intervals: Vec<Interval<T>> =
+            iter.into_iter().filter(|i| i.start <= i.end).collect();
+        intervals.sort_unstable_by_key(|i| i.start);
+
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for next in intervals {
+            match merged.last_mut() {
+                Some(current)
+                    if next.start <= current.end
+                        || current.end.checked_succ() == Some(next.start) =>
+                {
+                    current.end = max(current.end, next.end);
+                }
+                _ => merged.push(next),
+            }
+        }
+        Self { intervals: merged }
+    }
+}
+
+impl<T: Discrete> Extend<Interval<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Interval<T>>>(&mut self, iter: I) {
+        for interval in iter {
+            self.insert(interval);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a IntervalSet<T> {
+    type Item = &'a Interval<T>;
+    type IntoIter = std::slice::Iter<'a, Interval<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.intervals.iter()
+    }
+}
+
+/// A piecewise translation of values: each source interval is shifted onto a
+/// destination start, everything else maps to itself.
+///
+/// This is the shape of "destination source length" almanac tables, where a
+/// whole set of ranges is pushed through several stages.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RangeMap<T> {
+    /// `(source, destination start)`, sorted by source start.
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T: Discrete> RangeMap<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { pieces: Vec::new() }
+    }
+
+    /// Maps every value of `source` onto `destination + (value - source.start)`.
+    ///
+    /// Sources should not overlap; where they do, the one starting first wins.
+    pub fn insert(&mut self, source: Interval<T>, destination: T) {
+        let idx = self
+            .pieces
+            .partition_point(|(s, _)| s.start <= source.start);
+        self.pieces.insert(idx, (source, destination));
+    }
+
+    /// Translates a single value.
+    #[must_use]
+    pub fn map_value(&self, value: T) -> T {
+        self.pieces
+            .iter()
+            .take_while(|(source, _)| source.start <= value)
+            .find(|(source, _)| source.contains(value))
+            .map_or(value, |&(source, destination)| {
+                destination + (value - source.start)
+            })
+    }
+
+    /// Translates every value of `set`, splitting intervals that straddle
+    /// piece boundaries.
+    #[must_use]
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut mapped = IntervalSet::new();
+        for &interval in set {
+            // Next value of `interval` not yet emitted
+            let mut cursor = Some(interval.start);
+            for &(source, destination) in &self.pieces {
+                let Some(from) = cursor else { break };
+                if source.start > interval.end {
+                    break;
+                }
+                let Some(overlap) = Interval::new(from, interval.end).intersect(&source) else {
+                    continue;
+                };
+                if from < overlap.start {
+                    mapped.insert(Interval::new(from, overlap.start - T::ONE));
+                }
+                mapped.insert(Interval::new(
+                    destination + (overlap.start - source.start),
+                    destination + (overlap.end - source.start),
+                ));
+                cursor = overlap.end.checked_succ();
+            }
+            if let Some(from) = cursor.filter(|&from| from <= interval.end) {
+                mapped.insert(Interval::new(from, interval.end));
+            }
+        }
+        mapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet<i64> {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn test_contains_is_inclusive() {
+        let interval = Interval::new(10, 20);
+        assert!(interval.contains(10));
+        assert!(interval.contains(20));
+        assert!(!interval.contains(9));
+        assert!(!interval.contains(21));
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let a = Interval::new(1, 5);
+        assert!(a.overlaps(&Interval::new(5, 8)));
+        assert!(!a.overlaps(&Interval::new(6, 8)));
+    }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(Interval::new(3_u64, 5).count(), 3);
+        assert_eq!(Interval::new(7_u64, 7).count(), 1);
+    }
+
+    #[test]
+    fn test_inclusive_and_exclusive_constructors() {
+        assert_eq!(Interval::inclusive(3, 5), Some(Interval::new(3, 5)));
+        assert_eq!(Interval::exclusive(3, 5), Some(Interval::new(3, 4)));
+        assert_eq!(Interval::exclusive(3, 3), None);
+        assert_eq!(Interval::inclusive(4_u8, 3), None);
+        assert_eq!(Interval::exclusive(0_u8, 0), None);
+    }
+
+    #[test]
+    fn test_set_insert_merges_overlapping_and_adjacent() {
+        let mut s = IntervalSet::new();
+        s.insert_inclusive(10, 12);
+        s.insert_inclusive(1, 3);
+        s.insert_exclusive(20, 25);
+        assert_eq!(s, set(&[(1, 3), (10, 12), (20, 24)]));
+
+        s.insert_inclusive(4, 5);
+        assert_eq!(s, set(&[(1, 5), (10, 12), (20, 24)]));
+
+        s.insert_inclusive(11, 21);
+        assert_eq!(s, set(&[(1, 5), (10, 24)]));
+
+        s.insert_exclusive(7, 7);
+        assert_eq!(s.len(), 2, "Empty ranges are ignored");
+
+        // Inverted intervals straddling existing ones used to panic.
+        s.insert(Interval::new(4, 4));
+        s.insert(Interval::new(30, 2));
+        assert_eq!(s, set(&[(1, 5), (10, 24)]));
+    }
+
+    #[test]
+    fn test_set_from_iter_matches_insert() {
+        let collected = set(&[(15, 20), (1, 5), (6, 6), (8, 10), (16, 18)]);
+        assert_eq!(
+            collected.as_slice(),
+            set(&[(1, 6), (8, 10), (15, 20)]).as_slice()
+        );
+
+        let mut inserted = IntervalSet::new();
+        inserted.extend(collected.iter().copied());
+        assert_eq!(inserted, collected);
+    }
+
+    #[test]
+    fn test_set_from_iter_skips_inverted_intervals() {
+        let s: IntervalSet<u64> = [Interval::new(5, 2)].into_iter().collect();
+        assert!(s.is_empty());
+        assert_eq!(s.total_len(), 0);
+
+        assert_eq!(set(&[(1, 3), (9, 4), (4, 6)]), set(&[(1, 6)]));
+    }
+
+    #[test]
+    fn test_set_from_inverted_interval_is_empty() {
+        let s = IntervalSet::from(Interval::new(5_u64, 2));
+        assert!(s.is_empty());
+        assert_eq!(s.total_len(), 0);
+
+        let mut s = set(&[(1, 10)]);
+        s.remove(Interval::new(8, 3));
+        assert_eq!(s, set(&[(1, 10)]));
+    }
+
+    #[test]
+    fn test_set_contains_and_total_len() {
+        let s = set(&[(3, 5), (10, 14), (16, 20)]);
+        let inside: Vec<i64> = (0..22).filter(|&v| s.contains(v)).collect();
+        assert_eq!(inside.len(), 13);
+        assert_eq!(s.total_len(), 13);
+        assert!(!s.contains(15));
+        assert_eq!(s.bounds(), Some(Interval::new(3, 20)));
+        assert_eq!(IntervalSet::<i64>::new().total_len(), 0);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(5, 22), (28, 40)]);
+
+        assert_eq!(a.union(&b), set(&[(1, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 22), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(1, 4), (23, 27)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19), (31, 40)]));
+    }
+
+    #[test]
+    fn test_set_difference_splits_and_handles_extremes() {
+        let mut s: IntervalSet<u8> = [Interval::new(0, 255)].into_iter().collect();
+        s.remove(Interval::new(0, 0));
+        s.remove(Interval::new(100, 109));
+        s.remove(Interval::new(255, 255));
+        assert_eq!(
+            s.as_slice(),
+            &[Interval::new(1, 99), Interval::new(110, 254)]
+        );
+        assert_eq!(s.total_len(), 244);
+    }
+
+    #[test]
+    fn test_set_operations_against_brute_force() {
+        let a = set(&[(0, 3), (7, 7), (9, 15), (30, 31)]);
+        let b = set(&[(2, 8), (12, 12), (14, 29)]);
+        for v in -1..33 {
+            let (in_a, in_b) = (a.contains(v), b.contains(v));
+            assert_eq!(a.union(&b).contains(v), in_a || in_b, "union at {v}");
+            assert_eq!(
+                a.intersection(&b).contains(v),
+                in_a && in_b,
+                "intersection at {v}"
+            );
+            assert_eq!(
+                a.difference(&b).contains(v),
+                in_a && !in_b,
+                "difference at {v}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_range_map_value() {
+        let mut map = RangeMap::new();
+        map.insert(Interval::new(98_u64, 99), 50);
+        map.insert(Interval::new(50, 97), 52);
+
+        assert_eq!(map.map_value(79), 81);
+        assert_eq!(map.map_value(14), 14);
+        assert_eq!(map.map_value(98), 50);
+        assert_eq!(map.map_value(100), 100);
+    }
+
+    #[test]
+    fn test_range_map_set_splits_across_pieces() {
+        let mut map = RangeMap::new();
+        map.insert(Interval::new(10, 19), 110);
+        map.insert(Interval::new(25, 29), 0);
+
+        let mapped = map.map_set(&set(&[(5, 27), (40, 41)]));
+        assert_eq!(
+            mapped,
+            set(&[(0, 2), (5, 9), (20, 24), (40, 41), (110, 119)])
+        );
+        for v in 5..=27 {
+            assert!(mapped.contains(map.map_value(v)), "value {v}");
+        }
+    }
+}
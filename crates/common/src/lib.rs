@@ -1,15 +1,22 @@
-#[must_use]
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
+//! Shared building blocks for the daily puzzle crates.
+//!
+//! Each module holds a small, tested primitive that kept being re-implemented
+//! across days: grid neighbourhoods, points, segments and polygons,
+//! inclusive intervals, disjoint sets, graph storage and search,
+//! nearest-neighbour queries, exact linear algebra over the integers and
+//! over GF(2), integer programming, exact cover, polyominoes, input loading
+//! and parsing helpers.
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+pub mod dsu;
+pub mod exact_cover;
+pub mod geometry;
+pub mod gf2;
+pub mod graph;
+pub mod grid;
+pub mod ilp;
+pub mod input;
+pub mod interval;
+pub mod linalg;
+pub mod parse;
+pub mod polyomino;
+pub mod spatial;
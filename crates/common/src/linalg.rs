@@ -0,0 +1,386 @@
+//! Exact linear algebra over the integers.
+//!
+//! [`Rref`] reduces a system `A x = b` to reduced row echelon form without
+//! leaving the integers: rows are combined fraction-free and divided by
+//! their gcd, so no rounding can creep in. The rows are `i128`; systems
+//! whose intermediate values outgrow that panic rather than wrap, which
+//! puzzle-sized coefficients stay well clear of.
+//! [`min_nonnegative`] builds on it to find the non-negative integer
+//! solution with the smallest sum. It enumerates the free variables, so it
+//! is kept as the brute-force oracle the [`ilp`](crate::ilp) solver is
+//! tested against rather than as a solver in its own right.
+
+/// The reduced row echelon form of an integer system `A x = b`.
+///
+/// Each pivot row reads `p * x[pivot] + sum(a[f] * x[f]) = rhs` over the
+/// free variables `f`, with `p > 0`; pivot variables follow from any choice
+/// of the free ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rref {
+    /// Pivot rows as coefficients followed by the right-hand side.
+    rows: Vec<Vec<i128>>,
+    /// Pivot column of each row in `rows`.
+    pivots: Vec<usize>,
+    free: Vec<usize>,
+    vars: usize,
+}
+
+impl Rref {
+    /// Reduces `a x = b`, or returns `None` if the system has no solution,
+    /// even over the rationals.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` have different lengths, the rows of `a`
+    /// differ in length, or elimination overflows `i128`.
+    #[must_use]
+    pub fn new(a: &[Vec<i64>], b: &[i64]) -> Option<Self> {
+        assert_eq!(a.len(), b.len(), "one right-hand side per equation");
+        let vars = a.first().map_or(0, Vec::len);
+        let mut rows: Vec<Vec<i128>> = a
+            .iter()
+            .zip(b)
+            .map(|(row, &rhs)| {
+                assert_eq!(row.len(), vars, "every equation has {vars} coefficients");
+                row.iter().chain([&rhs]).map(|&v| i128::from(v)).collect()
+            })
+            .collect();
+
+        let mut pivots = Vec::new();
+        let mut free = Vec::new();
+        for col in 0..vars {
+            let rank = pivots.len();
+            // The smallest pivot keeps the intermediate numbers small.
+            let Some(best) = (rank..rows.len())
+                .filter(|&r| rows[r][col] != 0)
+                .min_by_key(|&r| rows[r][col].abs())
+            else {
+                free.push(col);
+                continue;
+            };
+            rows.swap(rank, best);
+            if rows[rank][col] < 0 {
+                for v in &mut rows[rank] {
+                    *v = sub(0, *v);
+                }
+            }
+
+            let pivot_row = rows[rank].clone();
+            let p = pivot_row[col];
+            for (i, row) in rows.iter_mut().enumerate() {
+                let factor = row[col];
+                if i == rank || factor == 0 {
+                    continue;
+                }
+                for (v, &q) in row.iter_mut().zip(&pivot_row) {
+                    *v = sub(mul(p, *v), mul(factor, q));
+                }
+                normalize(row);
+            }
+            pivots.push(col);
+        }
+
+        // Leftover rows read `0 = rhs`.
+        if rows[pivots.len()..].iter().any(|row| row[vars] != 0) {
+            return None;
+        }
+        rows.truncate(pivots.len());
+        Some(Self {
+            rows,
+            pivots,
+            free,
+            vars,
+        })
+    }
+
+    /// Number of variables in the system.
+    #[must_use]
+    pub fn vars(&self) -> usize {
+        self.vars
+    }
+
+    #[must_use]
+    pub fn rank(&self) -> usize {
+        self.pivots.len()
+    }
+
+    /// Variables that can be chosen freely, in increasing order.
+    #[must_use]
+    pub fn free_vars(&self) -> &[usize] {
+        &self.free
+    }
+
+    /// The full solution for the given values of [`free_vars`](Self::free_vars),
+    /// or `None` if some pivot variable would not be an integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `free_values` does not hold one value per free variable, or
+    /// if back-substituting them overflows `i128`.
+    #[must_use]
+    pub fn solve(&self, free_values: &[i64]) -> Option<Vec<i64>> {
+        assert_eq!(
+            free_values.len(),
+            self.free.len(),
+            "one value per free variable"
+        );
+        let mut x = vec![0; self.vars];
+        for (&f, &v) in self.free.iter().zip(free_values) {
+            x[f] = v;
+        }
+        for (i, &col) in self.pivots.iter().enumerate() {
+            x[col] = self.pivot_value(i, free_values)?;
+        }
+        Some(x)
+    }
+
+    /// Value of the pivot variable of row `i`, if it is an integer.
+    fn pivot_value(&self, i: usize, free_values: &[i64]) -> Option<i64> {
+        let row = &self.rows[i];
+        let num = self
+            .free
+            .iter()
+            .zip(free_values)
+            .fold(row[self.vars], |acc, (&f, &v)| {
+                sub(acc, mul(row[f], i128::from(v)))
+            });
+        let p = row[self.pivots[i]];
+        if num % p != 0 {
+            return None;
+        }
+        i64::try_from(num / p).ok()
+    }
+}
+
+/// `a * b`, panicking instead of wrapping; see the module docs.
+fn mul(a: i128, b: i128) -> i128 {
+    a.checked_mul(b).expect("elimination overflowed i128")
+}
+
+/// `a - b`, panicking instead of wrapping; see the module docs.
+fn sub(a: i128, b: i128) -> i128 {
+    a.checked_sub(b).expect("elimination overflowed i128")
+}
+
+/// Divides a row through by the gcd of its entries.
+fn normalize(row: &mut [i128]) {
+    let g = row.iter().fold(0, |g, &v| gcd(g, v.abs()));
+    if g > 1 {
+        for v in row {
+            *v /= g;
+        }
+    }
+}
+
+pub(crate) fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// An optimal solution found by [`min_nonnegative`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Minimum {
+    pub values: Vec<i64>,
+    pub total: i64,
+}
+
+/// The solution of `a x = b` with `0 <= x[i] <= upper[i]` in integers that
+/// minimises `sum(x)`, or `None` if there is none.
+///
+/// The free variables are assigned one at a time within their bounds. Each
+/// pivot variable is checked as soon as the free variables it depends on are
+/// set, and a branch is cut once its running total reaches the best found,
+/// so the worst case grows with the product of the free variables' bounds.
+///
+/// # Panics
+///
+/// Panics on mismatched dimensions or overflow, as [`Rref::new`] does, or
+/// if `upper` does not hold one bound per variable.
+#[must_use]
+pub fn min_nonnegative(a: &[Vec<i64>], b: &[i64], upper: &[i64]) -> Option<Minimum> {
+    let rref = Rref::new(a, b)?;
+    assert_eq!(upper.len(), rref.vars(), "one bound per variable");
+
+    let free = rref.free_vars();
+    let mut ready = vec![Vec::new(); free.len() + 1];
+    for (i, row) in rref.rows.iter().enumerate() {
+        let depth = free.iter().rposition(|&f| row[f] != 0).map_or(0, |j| j + 1);
+        ready[depth].push(i);
+    }
+
+    let mut search = Search {
+        rref: &rref,
+        upper,
+        ready,
+        free_values: vec![0; free.len()],
+        best: None,
+    };
+    search.assign(0, 0);
+    search.best
+}
+
+struct Search<'a> {
+    rref: &'a Rref,
+    upper: &'a [i64],
+    /// Pivot rows by the number of free variables that fix them.
+    ready: Vec<Vec<usize>>,
+    free_values: Vec<i64>,
+    best: Option<Minimum>,
+}
+
+impl Search<'_> {
+    /// Explores assignments of the free variables from `depth` on, where
+    /// `partial` sums every variable fixed so far.
+    fn assign(&mut self, depth: usize, mut partial: i64) {
+        for &i in &self.ready[depth] {
+            let col = self.rref.pivots[i];
+            match self.rref.pivot_value(i, &self.free_values) {
+                Some(v) if (0..=self.upper[col]).contains(&v) => partial += v,
+                _ => return,
+            }
+        }
+        if self.best.as_ref().is_some_and(|best| partial >= best.total) {
+            return;
+        }
+
+        let Some(&var) = self.rref.free_vars().get(depth) else {
+            let values = self.rref.solve(&self.free_values).expect("checked above");
+            self.best = Some(Minimum {
+                values,
+                total: partial,
+            });
+            return;
+        };
+        for v in 0..=self.upper[var] {
+            self.free_values[depth] = v;
+            self.assign(depth + 1, partial + v);
+        }
+        self.free_values[depth] = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn apply(a: &[Vec<i64>], x: &[i64]) -> Vec<i64> {
+        a.iter()
+            .map(|row| row.iter().zip(x).map(|(c, v)| c * v).sum())
+            .collect()
+    }
+
+    /// Tries every vector within the bounds.
+    fn brute_force(a: &[Vec<i64>], b: &[i64], upper: &[i64]) -> Option<i64> {
+        let mut x = vec![0; upper.len()];
+        let mut best = None;
+        loop {
+            if apply(a, &x) == b {
+                let total = x.iter().sum::<i64>();
+                best = Some(best.map_or(total, |b: i64| b.min(total)));
+            }
+            let Some(i) = (0..x.len()).find(|&i| x[i] < upper[i]) else {
+                return best;
+            };
+            x[i] += 1;
+            x[..i].fill(0);
+        }
+    }
+
+    #[test]
+    fn test_rref_of_a_unique_solution() {
+        let a = vec![vec![2, 1], vec![1, 3]];
+        let rref = Rref::new(&a, &[5, 10]).expect("consistent");
+        assert_eq!(rref.rank(), 2);
+        assert!(rref.free_vars().is_empty());
+        assert_eq!(rref.solve(&[]), Some(vec![1, 3]));
+    }
+
+    #[test]
+    fn test_rref_rejects_inconsistent_systems() {
+        let a = vec![vec![1, 1], vec![2, 2]];
+        assert_eq!(Rref::new(&a, &[1, 3]), None);
+    }
+
+    #[test]
+    fn test_rref_detects_non_integer_pivots() {
+        let a = vec![vec![2, 1]];
+        let rref = Rref::new(&a, &[3]).expect("consistent");
+        assert_eq!(rref.free_vars(), [1]);
+        assert_eq!(rref.solve(&[0]), None);
+        assert_eq!(rref.solve(&[1]), Some(vec![1, 1]));
+    }
+
+    #[test]
+    fn test_rref_stays_exact_on_large_values() {
+        // 2^53 + 1 is the first integer an f64 cannot hold.
+        let big = (1 << 53) + 1;
+        let a = vec![vec![1, 1], vec![1, -1]];
+        let rref = Rref::new(&a, &[2 * big, 0]).expect("consistent");
+        assert_eq!(rref.solve(&[]), Some(vec![big, big]));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed i128")]
+    fn test_rref_overflow_panics() {
+        let m = i64::MAX;
+        let a = vec![vec![m, m - 1, 1], vec![m - 1, m, 1], vec![m - 2, 1, m]];
+        let _ = Rref::new(&a, &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_min_nonnegative_example() {
+        // Buttons (3) (1,3) (2) (2,3) (0,2) (0,1) against {3,5,4,7}.
+        let a = vec![
+            vec![0, 0, 0, 0, 1, 1],
+            vec![0, 1, 0, 0, 0, 1],
+            vec![0, 0, 1, 1, 1, 0],
+            vec![1, 1, 0, 1, 0, 0],
+        ];
+        let b = [3, 5, 4, 7];
+        let best = min_nonnegative(&a, &b, &[7, 5, 4, 4, 3, 3]).expect("solvable");
+        assert_eq!(best.total, 10);
+        assert_eq!(apply(&a, &best.values), b);
+    }
+
+    #[test]
+    fn test_min_nonnegative_respects_bounds() {
+        let a = vec![vec![1, 1]];
+        assert_eq!(min_nonnegative(&a, &[5], &[2, 2]), None);
+        let best = min_nonnegative(&a, &[4], &[2, 2]).expect("solvable");
+        assert_eq!(best.values, [2, 2]);
+    }
+
+    fn system() -> impl Strategy<Value = (Vec<Vec<i64>>, Vec<i64>)> {
+        (1..=4usize, 1..=4usize).prop_flat_map(|(eqs, vars)| {
+            let a = prop::collection::vec(prop::collection::vec(0..=1i64, vars), eqs);
+            let x = prop::collection::vec(0..=3i64, vars);
+            let noise = prop::collection::vec(-1..=1i64, eqs);
+            (a, x, noise, any::<bool>()).prop_map(|(a, x, noise, exact)| {
+                let mut b = apply(&a, &x);
+                if !exact {
+                    b.iter_mut()
+                        .zip(noise)
+                        .for_each(|(v, n)| *v = (*v + n).max(0));
+                }
+                (a, b)
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_min_nonnegative_matches_brute_force((a, b) in system()) {
+            let upper = vec![4; a[0].len()];
+            let found = min_nonnegative(&a, &b, &upper);
+            prop_assert_eq!(found.as_ref().map(|m| m.total), brute_force(&a, &b, &upper));
+            if let Some(best) = found {
+                prop_assert_eq!(apply(&a, &best.values), b);
+                prop_assert_eq!(best.values.iter().sum::<i64>(), best.total);
+                prop_assert!(best.values.iter().all(|v| (0..=4).contains(v)));
+            }
+        }
+    }
+}
@@ -0,0 +1,590 @@
+//! Input parsing helpers and the span-carrying [`ParseError`].
+//!
+//! Parsers report where the input went wrong instead of panicking. Errors
+//! are usually built against the slice being parsed (often a single line)
+//! with [`ParseError::at`], then moved into the coordinates of the whole
+//! puzzle input with [`ParseError::within`] so the final miette report shows
+//! the right line and column.
+//!
+//! The combinators ([`int`], [`comma_list`], [`delimited`], [`key_value`],
+//! [`char_grid`], ...) follow the same rule: each reports errors relative to
+//! the slice it was given, and moves errors from the parsers it calls into
+//! that slice. Nesting them therefore needs no manual [`ParseError::within`].
+
+use std::fmt;
+use std::num::{IntErrorKind, ParseIntError};
+use std::str::FromStr;
+
+use miette::{Diagnostic, NamedSource, SourceSpan};
+use thiserror::Error;
+
+use crate::grid::Grid;
+
+/// Name shown in reports when none is given.
+const DEFAULT_NAME: &str = "input";
+
+/// A parse failure pointing at the offending part of the input.
+///
+/// Boxed so that parser results stay small on the happy path.
+#[derive(Debug, Error, Diagnostic)]
+#[error(transparent)]
+#[diagnostic(transparent)]
+pub struct ParseError(Box<Details>);
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("{message}")]
+#[diagnostic(code(common::parse))]
+struct Details {
+    message: String,
+    #[source_code]
+    src: NamedSource<String>,
+    #[label("{label}")]
+    span: SourceSpan,
+    label: String,
+    #[help]
+    help: Option<String>,
+}
+
+impl ParseError {
+    /// An error covering `span` (byte offsets) of `input`.
+    #[must_use]
+    pub fn new(input: &str, span: impl Into<SourceSpan>, message: impl Into<String>) -> Self {
+        Self(Box::new(Details {
+            message: message.into(),
+            src: NamedSource::new(DEFAULT_NAME, input.to_string()),
+            span: span.into(),
+            label: "here".to_string(),
+            help: None,
+        }))
+    }
+
+    /// An error covering `fragment`, which must be a subslice of `input`
+    /// (as returned by `lines`, `split`, `trim` and friends).
+    #[must_use]
+    pub fn at(input: &str, fragment: &str, message: impl Into<String>) -> Self {
+        let offset = offset_in(input, fragment).unwrap_or(0);
+        Self::new(input, (offset, fragment.len()), message)
+    }
+
+    /// Re-anchors an error built against `fragment` onto the enclosing
+    /// `input`, shifting its span by the fragment's offset.
+    #[must_use]
+    pub fn within(mut self, input: &str, fragment: &str) -> Self {
+        let shift = offset_in(input, fragment).unwrap_or(0);
+        self.0.span = (self.0.span.offset() + shift, self.0.span.len()).into();
+        self.0.src = NamedSource::new(self.0.src.name(), input.to_string());
+        self
+    }
+
+    /// Replaces the default `here` label under the span.
+    #[must_use]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.0.label = label.into();
+        self
+    }
+
+    #[must_use]
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.0.help = Some(help.into());
+        self
+    }
+
+    /// Names the source in the report, e.g. `2025 day 08 part 1`.
+    #[must_use]
+    pub fn with_name(mut self, name: impl AsRef<str>) -> Self {
+        self.0.src = NamedSource::new(name, self.0.src.inner().clone());
+        self
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.0.message
+    }
+
+    #[must_use]
+    pub fn span(&self) -> SourceSpan {
+        self.0.span
+    }
+
+    /// One-based line and column of the start of the span.
+    #[must_use]
+    pub fn line_col(&self) -> (usize, usize) {
+        let source = self.0.src.inner();
+        let before = &source[..self.0.span.offset().min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+        (line, column)
+    }
+}
+
+/// Byte offset of `fragment` inside `input`, if it is a subslice of it.
+#[must_use]
+pub fn offset_in(input: &str, fragment: &str) -> Option<usize> {
+    let start = input.as_ptr() as usize;
+    let at = fragment.as_ptr() as usize;
+    (at >= start && at + fragment.len() <= start + input.len()).then(|| at - start)
+}
+
+/// Parses a trimmed token, reporting a failure at the token itself.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] spanning `token` when `T::from_str` fails.
+pub fn parse_token<T>(input: &str, token: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let trimmed = token.trim();
+    trimmed.parse().map_err(|err: T::Err| {
+        let what = if trimmed.is_empty() {
+            "expected a value".to_string()
+        } else {
+            format!("invalid value `{trimmed}`")
+        };
+        ParseError::at(input, trimmed, what).with_label(err.to_string())
+    })
+}
+
+/// Parses every non-blank line of `input`, moving each error into the
+/// coordinates of the whole input.
+///
+/// # Errors
+///
+/// Returns the first line's [`ParseError`].
+pub fn lines<'a, T>(
+    input: &'a str,
+    mut parse: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse(line).map_err(|err| err.within(input, line)))
+        .collect()
+}
+
+/// Splits `input` into blocks separated by one or more blank lines.
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        rest = rest.trim_start_matches(['\n', '\r']);
+        if rest.is_empty() {
+            return None;
+        }
+        let mut end = rest.len();
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim().is_empty() {
+                end = offset;
+                break;
+            }
+            offset += line.len();
+        }
+        let (block, tail) = rest.split_at(end);
+        rest = tail;
+        Some(block.trim_end())
+    })
+}
+
+/// Parses a trimmed integer; `T` decides whether a sign is accepted.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] spanning the token when it is empty, not a
+/// number, negative for an unsigned `T`, or out of range for `T`.
+pub fn int<T>(s: &str) -> Result<T, ParseError>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    let token = s.trim();
+    token.parse().map_err(|err: ParseIntError| {
+        let error = |message: String| ParseError::at(s, token, message);
+        match err.kind() {
+            IntErrorKind::Empty => error("expected an integer".to_string()),
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                error(format!("integer `{token}` out of range"))
+                    .with_label(format!("does not fit in {}", std::any::type_name::<T>()))
+            }
+            _ if token.starts_with('-') && "-1".parse::<T>().is_err() => {
+                error(format!("expected a non-negative integer, found `{token}`"))
+            }
+            _ => error(format!("invalid integer `{token}`")).with_label(err.to_string()),
+        }
+    })
+}
+
+/// Splits `s` on `sep` and parses every trimmed piece. A blank `s` is an
+/// empty list, and one trailing separator is allowed.
+///
+/// # Errors
+///
+/// Returns the first item's [`ParseError`], relative to `s`.
+pub fn separated<'a, T>(
+    s: &'a str,
+    sep: char,
+    mut item: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    let trimmed = s.trim();
+    let body = trimmed.strip_suffix(sep).unwrap_or(trimmed);
+    if body.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    body.split(sep)
+        .map(|piece| {
+            let piece = piece.trim();
+            item(piece).map_err(|err| err.within(s, piece))
+        })
+        .collect()
+}
+
+/// A comma-separated list such as `3,5,4,7`.
+///
+/// # Errors
+///
+/// Returns the first item's [`ParseError`], relative to `s`.
+pub fn comma_list<'a, T>(
+    s: &'a str,
+    item: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    separated(s, ',', item)
+}
+
+/// A list of whitespace-separated tokens such as `41 48 83`.
+///
+/// # Errors
+///
+/// Returns the first item's [`ParseError`], relative to `s`.
+pub fn whitespace_list<'a, T>(
+    s: &'a str,
+    mut item: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    s.split_whitespace()
+        .map(|token| item(token).map_err(|err| err.within(s, token)))
+        .collect()
+}
+
+/// Splits `s` at the first `sep` and parses both trimmed halves, as in
+/// `3-5`, `7,1` or `12x5`.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] when `sep` is missing, or the first half's
+/// error, relative to `s`.
+pub fn pair<'a, A, B>(
+    s: &'a str,
+    sep: char,
+    first: impl FnOnce(&'a str) -> Result<A, ParseError>,
+    second: impl FnOnce(&'a str) -> Result<B, ParseError>,
+) -> Result<(A, B), ParseError> {
+    let token = s.trim();
+    let (a, b) = token.split_once(sep).ok_or_else(|| {
+        ParseError::at(
+            s,
+            token,
+            format!("expected two values separated by `{sep}`"),
+        )
+        .with_label(format!("no `{sep}`"))
+    })?;
+    let (a, b) = (a.trim(), b.trim());
+    Ok((
+        first(a).map_err(|err| err.within(s, a))?,
+        second(b).map_err(|err| err.within(s, b))?,
+    ))
+}
+
+/// A `key: value` line. The value may be empty, as in a `0:` header.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] when there is no `:` or the key is blank, or
+/// the key's or value's error, relative to `line`.
+pub fn key_value<'a, K, V>(
+    line: &'a str,
+    key: impl FnOnce(&'a str) -> Result<K, ParseError>,
+    value: impl FnOnce(&'a str) -> Result<V, ParseError>,
+) -> Result<(K, V), ParseError> {
+    let token = line.trim();
+    match token.split_once(':') {
+        Some((k, _)) if !k.trim().is_empty() => pair(line, ':', key, value),
+        _ => Err(
+            ParseError::at(line, token, "expected `key: value`").with_label(
+                if token.contains(':') {
+                    "missing key"
+                } else {
+                    "no `:`"
+                },
+            ),
+        ),
+    }
+}
+
+/// The bracket pairs understood by [`delimited`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delim {
+    /// `[..]`
+    Square,
+    /// `(..)`
+    Paren,
+    /// `{..}`
+    Brace,
+}
+
+impl Delim {
+    #[must_use]
+    pub const fn open(self) -> char {
+        match self {
+            Self::Square => '[',
+            Self::Paren => '(',
+            Self::Brace => '{',
+        }
+    }
+
+    #[must_use]
+    pub const fn close(self) -> char {
+        match self {
+            Self::Square => ']',
+            Self::Paren => ')',
+            Self::Brace => '}',
+        }
+    }
+}
+
+/// Parses the contents of a group that wraps the whole trimmed `s`, such
+/// as `[.##.]` or `(1,3)`.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] when the opening or closing character is
+/// missing, or the contents' error, relative to `s`.
+pub fn delimited<'a, T>(
+    s: &'a str,
+    delim: Delim,
+    inner: impl FnOnce(&'a str) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    let (open, close) = (delim.open(), delim.close());
+    let token = s.trim();
+    let Some(rest) = token.strip_prefix(open) else {
+        let found = token
+            .chars()
+            .next()
+            .map_or(token, |c| &token[..c.len_utf8()]);
+        return Err(ParseError::at(s, found, format!("expected `{open}`")));
+    };
+    let Some(contents) = rest.strip_suffix(close) else {
+        return Err(ParseError::at(s, &token[..1], format!("unclosed `{open}`"))
+            .with_label(format!("this `{open}` has no matching `{close}`")));
+    };
+    inner(contents).map_err(|err| err.within(s, contents))
+}
+
+/// Parses every blank-line-separated block of `input` (see [`blocks`]),
+/// moving each error into the coordinates of the whole input.
+///
+/// # Errors
+///
+/// Returns the first block's [`ParseError`].
+pub fn blocks_with<'a, T>(
+    input: &'a str,
+    mut parse: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    blocks(input)
+        .map(|block| parse(block).map_err(|err| err.within(input, block)))
+        .collect()
+}
+
+/// Parses one cell per character and one row per line. `cell` returns
+/// `None` for characters that do not belong in the grid, which are
+/// reported with `expected` as the label.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] at the first rejected character, or at the
+/// first row whose length differs from the first one.
+pub fn char_grid<T>(
+    input: &str,
+    mut cell: impl FnMut(char) -> Option<T>,
+    expected: &str,
+) -> Result<Grid<T>, ParseError> {
+    let rows = input
+        .lines()
+        .map(|line| {
+            line.char_indices()
+                .map(|(i, c)| {
+                    cell(c).ok_or_else(|| {
+                        ParseError::at(
+                            input,
+                            &line[i..i + c.len_utf8()],
+                            format!("unexpected `{c}`"),
+                        )
+                        .with_label(expected)
+                    })
+                })
+                .collect::<Result<Vec<T>, ParseError>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Grid::from_rows(rows).map_err(|err| err.locate(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks() {
+        let input = "a\nb\n\n\nc\n\nd\n";
+        let found: Vec<&str> = blocks(input).collect();
+        assert_eq!(found, vec!["a\nb", "c", "d"]);
+    }
+
+    #[test]
+    fn test_blocks_empty() {
+        assert_eq!(blocks("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn test_offset_in() {
+        let input = "abc\ndef";
+        let line = input.lines().nth(1).expect("two lines");
+        assert_eq!(offset_in(input, line), Some(4));
+        assert_eq!(offset_in(input, &line[1..]), Some(5));
+        assert_eq!(offset_in(input, "def"), None);
+    }
+
+    #[test]
+    fn test_parse_token() {
+        let input = "x= 12 ,y=oops";
+        let value: u32 = parse_token(input, &input[2..6]).expect("a number");
+        assert_eq!(value, 12);
+
+        let err = parse_token::<u32>(input, &input[9..]).expect_err("not a number");
+        assert_eq!(err.message(), "invalid value `oops`");
+        assert_eq!(err.span(), (9, 4).into());
+    }
+
+    #[test]
+    fn test_lines_reports_line_and_column() {
+        let input = "1,2\n3,4\n\n5,x\n";
+        let err = lines(input, |line| {
+            let (a, b) = line
+                .split_once(',')
+                .ok_or_else(|| ParseError::at(line, line, "no comma"))?;
+            Ok((parse_token::<u8>(line, a)?, parse_token::<u8>(line, b)?))
+        })
+        .expect_err("last line is bad");
+        assert_eq!(err.span(), (11, 1).into());
+        assert_eq!(err.line_col(), (4, 3));
+    }
+
+    #[test]
+    fn test_int() {
+        assert_eq!(int::<i64>(" -42 ").expect("signed"), -42);
+        assert_eq!(int::<u8>("255").expect("fits"), 255);
+
+        let err = int::<u8>("256").expect_err("overflow");
+        assert_eq!(err.message(), "integer `256` out of range");
+        let err = int::<u32>("-3").expect_err("unsigned");
+        assert_eq!(err.message(), "expected a non-negative integer, found `-3`");
+        let err = int::<i32>(" 1x").expect_err("not a number");
+        assert_eq!(err.message(), "invalid integer `1x`");
+        assert_eq!(err.span(), (1, 2).into());
+        assert_eq!(
+            int::<i32>("").expect_err("empty").message(),
+            "expected an integer"
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(
+            comma_list("3, 5,4,", int::<u8>).expect("list"),
+            vec![3, 5, 4]
+        );
+        assert_eq!(comma_list(" ", int::<u8>).expect("empty"), Vec::<u8>::new());
+        assert_eq!(
+            whitespace_list("1  2\t3", int::<u8>).expect("list"),
+            vec![1, 2, 3]
+        );
+
+        let err = comma_list("1,,3", int::<u8>).expect_err("empty item");
+        assert_eq!(err.span(), (2, 0).into());
+        let err = whitespace_list("1 2 x", int::<u8>).expect_err("bad item");
+        assert_eq!(err.span(), (4, 1).into());
+    }
+
+    #[test]
+    fn test_pair_and_key_value() {
+        assert_eq!(
+            pair("3-5", '-', int::<u8>, int::<u8>).expect("range"),
+            (3, 5)
+        );
+        let err = pair("12x", 'x', int::<u8>, int::<u8>).expect_err("no height");
+        assert_eq!(err.span(), (3, 0).into());
+        let err = pair("12", 'x', int::<u8>, int::<u8>).expect_err("no separator");
+        assert_eq!(err.message(), "expected two values separated by `x`");
+
+        let (key, value) = key_value("aaa: bbb ccc", Ok, Ok).expect("line");
+        assert_eq!((key, value), ("aaa", "bbb ccc"));
+        let (id, rest) = key_value("4:", int::<u8>, Ok).expect("header");
+        assert_eq!((id, rest), (4, ""));
+        assert!(key_value("aaa bbb", Ok, Ok).is_err());
+        assert!(key_value(": bbb", Ok, Ok).is_err());
+    }
+
+    #[test]
+    fn test_delimited() {
+        let button = |s| delimited(s, Delim::Paren, |inner| comma_list(inner, int::<u8>));
+        assert_eq!(button(" (1,3)").expect("group"), vec![1, 3]);
+        assert_eq!(button("()").expect("empty group"), Vec::<u8>::new());
+
+        let err = button("1,3)").expect_err("no opening");
+        assert_eq!(err.message(), "expected `(`");
+        assert_eq!(err.span(), (0, 1).into());
+        let err = button(" (1,3").expect_err("no closing");
+        assert_eq!(err.message(), "unclosed `(`");
+        assert_eq!(err.span(), (1, 1).into());
+        let err = button("(1,x)").expect_err("bad item");
+        assert_eq!(err.span(), (3, 1).into());
+    }
+
+    #[test]
+    fn test_blocks_with() {
+        let input = "1\n2\n\n3\nx\n";
+        let err = blocks_with(input, |block| lines(block, int::<u8>)).expect_err("bad");
+        assert_eq!(err.line_col(), (5, 1));
+    }
+
+    #[test]
+    fn test_char_grid() {
+        let cell = |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        let grid = char_grid("#.\n.#\n", cell, "expected `#` or `.`").expect("grid");
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+
+        let err = char_grid("#.\n.o\n", cell, "expected `#` or `.`").expect_err("bad cell");
+        assert_eq!(err.message(), "unexpected `o`");
+        assert_eq!(err.line_col(), (2, 2));
+        let err = char_grid("#.\n.\n", cell, "expected `#` or `.`").expect_err("ragged");
+        assert_eq!(err.line_col(), (2, 1));
+    }
+
+    #[test]
+    fn test_report_renders_span() {
+        let input = "L10\nX5\n";
+        let line = input.lines().nth(1).expect("two lines");
+        let err = ParseError::at(line, &line[..1], "unknown direction")
+            .with_label("expected `L` or `R`")
+            .within(input, line)
+            .with_name("2025 day 01");
+
+        let mut rendered = String::new();
+        miette::GraphicalReportHandler::new_themed(miette::GraphicalTheme::unicode_nocolor())
+            .render_report(&mut rendered, &err)
+            .expect("renders");
+        assert!(rendered.contains("unknown direction"), "{rendered}");
+        assert!(rendered.contains("[2025 day 01:2:1]"), "{rendered}");
+        assert!(rendered.contains("expected `L` or `R`"), "{rendered}");
+    }
+}
@@ -0,0 +1,470 @@
+//! Polyominoes: normalised cell sets, their eight orientations, and row
+//! bitmasks for placing them on boards up to 128 cells wide.
+//!
+//! A [`Board`] tests a piece against a whole row of cells with one `AND`,
+//! which suits backtracking packers. Day 12 packs with
+//! [`exact_cover`](crate::exact_cover) instead, so the boards are only
+//! exercised by this module's tests.
+//!
+//! Shapes come in blocks of an `N:` header followed by `#`/`.` rows:
+//!
+//! ```text
+//! 0:
+//! ###
+//! ##.
+//! ```
+
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Shl};
+
+use crate::grid::Pos;
+use crate::parse::{self, ParseError};
+
+/// A set of cells shifted so that its bounding box starts at `(0, 0)`, with
+/// the cells sorted. Two polyominoes are equal exactly when they have the
+/// same cells in the same orientation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Polyomino {
+    cells: Vec<Pos>,
+    height: usize,
+    width: usize,
+}
+
+impl Polyomino {
+    /// Normalises `cells`, dropping duplicates.
+    #[must_use]
+    pub fn new(cells: impl IntoIterator<Item = Pos>) -> Self {
+        let mut cells: Vec<Pos> = cells.into_iter().collect();
+        let min_row = cells.iter().map(|p| p.row).min().unwrap_or(0);
+        let min_col = cells.iter().map(|p| p.col).min().unwrap_or(0);
+        for p in &mut cells {
+            *p = Pos::new(p.row - min_row, p.col - min_col);
+        }
+        cells.sort_unstable();
+        cells.dedup();
+
+        let height = cells.iter().map(|p| p.row + 1).max().unwrap_or(0);
+        let width = cells.iter().map(|p| p.col + 1).max().unwrap_or(0);
+        Self {
+            cells,
+            height,
+            width,
+        }
+    }
+
+    #[must_use]
+    pub fn cells(&self) -> &[Pos] {
+        &self.cells
+    }
+
+    /// Number of cells.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Turns the shape a quarter clockwise.
+    #[must_use]
+    pub fn rotate_cw(&self) -> Self {
+        let height = self.height;
+        Self::new(
+            self.cells
+                .iter()
+                .map(|p| Pos::new(p.col, height - 1 - p.row)),
+        )
+    }
+
+    /// Mirrors the shape left to right.
+    #[must_use]
+    pub fn flip(&self) -> Self {
+        let width = self.width;
+        Self::new(
+            self.cells
+                .iter()
+                .map(|p| Pos::new(p.row, width - 1 - p.col)),
+        )
+    }
+
+    /// The distinct images of the shape under the eight rotations and
+    /// reflections of the square, in sorted order.
+    #[must_use]
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orbit = Vec::with_capacity(8);
+        let mut current = self.clone();
+        for _ in 0..4 {
+            orbit.push(current.flip());
+            let next = current.rotate_cw();
+            orbit.push(current);
+            current = next;
+        }
+        orbit.sort_unstable();
+        orbit.dedup();
+        orbit
+    }
+
+    /// The smallest orientation, shared by every shape that is a rotation
+    /// or reflection of this one.
+    #[must_use]
+    pub fn canonical(&self) -> Self {
+        self.orientations()
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// One mask per row, with bit `c` set for a cell in column `c`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shape is wider than `R` has bits.
+    #[must_use]
+    pub fn bitmap<R: RowMask>(&self) -> Bitmap<R> {
+        assert!(
+            self.width <= R::BITS,
+            "{} columns need wider rows",
+            self.width
+        );
+        let mut rows = vec![R::ZERO; self.height];
+        for p in &self.cells {
+            rows[p.row] = rows[p.row] | R::bit(p.col);
+        }
+        Bitmap {
+            rows,
+            width: self.width,
+        }
+    }
+}
+
+impl fmt::Display for Polyomino {
+    /// Draws the bounding box with `#` for cells and `.` for gaps.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut next = self.cells.iter().peekable();
+        for row in 0..self.height {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for col in 0..self.width {
+                let filled = next.next_if_eq(&&Pos::new(row, col)).is_some();
+                write!(f, "{}", if filled { '#' } else { '.' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A numbered shape with its orientations worked out once up front.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shape {
+    pub id: usize,
+    base: Polyomino,
+    orientations: Vec<Polyomino>,
+}
+
+impl Shape {
+    #[must_use]
+    pub fn new(id: usize, base: Polyomino) -> Self {
+        let orientations = base.orientations();
+        Self {
+            id,
+            base,
+            orientations,
+        }
+    }
+
+    /// The shape as it was drawn.
+    #[must_use]
+    pub fn base(&self) -> &Polyomino {
+        &self.base
+    }
+
+    /// Every distinct orientation, in sorted order.
+    #[must_use]
+    pub fn orientations(&self) -> &[Polyomino] {
+        &self.orientations
+    }
+
+    /// Number of cells.
+    #[must_use]
+    pub fn area(&self) -> usize {
+        self.base.len()
+    }
+
+    /// Side of the smallest square that holds the shape in any orientation.
+    #[must_use]
+    pub fn side(&self) -> usize {
+        self.base.width.max(self.base.height)
+    }
+}
+
+/// An unsigned integer used as one row of a bitmask board.
+pub trait RowMask:
+    Copy + Eq + BitAnd<Output = Self> + BitOr<Output = Self> + Shl<usize, Output = Self>
+{
+    const BITS: usize;
+    const ZERO: Self;
+
+    /// The mask with only bit `col` set.
+    fn bit(col: usize) -> Self;
+}
+
+macro_rules! row_mask {
+    ($($t:ty),*) => {$(
+        impl RowMask for $t {
+            const BITS: usize = <$t>::BITS as usize;
+            const ZERO: Self = 0;
+
+            fn bit(col: usize) -> Self {
+                1 << col
+            }
+        }
+    )*};
+}
+
+row_mask!(u64, u128);
+
+/// A polyomino as row masks, ready to be shifted into place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap<R> {
+    rows: Vec<R>,
+    width: usize,
+}
+
+/// A rectangle of cells, one mask per row, that pieces are placed on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board<R> {
+    rows: Vec<R>,
+    width: usize,
+}
+
+impl<R: RowMask> Board<R> {
+    /// An empty board.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is more than `R` has bits.
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(width <= R::BITS, "{width} columns need wider rows");
+        Self {
+            rows: vec![R::ZERO; height],
+            width,
+        }
+    }
+
+    #[must_use]
+    pub fn is_filled(&self, pos: Pos) -> bool {
+        self.rows
+            .get(pos.row)
+            .is_some_and(|&row| pos.col < self.width && row & R::bit(pos.col) != R::ZERO)
+    }
+
+    /// Whether `piece` with its top-left corner at `at` stays on the board
+    /// and clear of every filled cell.
+    #[must_use]
+    pub fn fits(&self, piece: &Bitmap<R>, at: Pos) -> bool {
+        at.row + piece.rows.len() <= self.rows.len()
+            && at.col + piece.width <= self.width
+            && self.rows[at.row..]
+                .iter()
+                .zip(&piece.rows)
+                .all(|(&row, &mask)| row & (mask << at.col) == R::ZERO)
+    }
+
+    /// Fills the cells of `piece` at `at` if it [`fits`](Self::fits), and
+    /// reports whether it did.
+    pub fn place(&mut self, piece: &Bitmap<R>, at: Pos) -> bool {
+        if !self.fits(piece, at) {
+            return false;
+        }
+        for (row, &mask) in self.rows[at.row..].iter_mut().zip(&piece.rows) {
+            *row = *row | (mask << at.col);
+        }
+        true
+    }
+}
+
+/// Parses `N:` headers, each followed by the rows of its shape. Blank lines
+/// between blocks are ignored.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] at a header that is not an index followed by a
+/// `:`, or at a row character other than `#` and `.`.
+pub fn parse_shapes(input: &str) -> Result<Vec<Shape>, ParseError> {
+    let mut shapes = Vec::new();
+    let mut lines = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+
+    while let Some(line) = lines.next() {
+        let (id, ()) = parse::key_value(line, parse::int, |rest| {
+            if rest.is_empty() {
+                Ok(())
+            } else {
+                Err(ParseError::at(
+                    rest,
+                    rest,
+                    "unexpected text after a shape header",
+                ))
+            }
+        })
+        .map_err(|err| {
+            err.within(input, line)
+                .with_help("each shape starts with its index, like `0:`")
+        })?;
+
+        // Shape rows are the lines without a `:`, parsed as one slice of the
+        // input so errors point at the right line.
+        let mut cells = Vec::new();
+        if let Some(first) = lines.next_if(|line| !line.contains(':')) {
+            let mut last = first;
+            while let Some(row) = lines.next_if(|line| !line.contains(':')) {
+                last = row;
+            }
+            let start = parse::offset_in(input, first).unwrap_or(0);
+            let end = parse::offset_in(input, last).unwrap_or(start) + last.len();
+            let rows = &input[start..end];
+            let grid = parse::char_grid(
+                rows,
+                |c| match c {
+                    '#' => Some(true),
+                    '.' => Some(false),
+                    _ => None,
+                },
+                "expected `#` or `.`",
+            )
+            .map_err(|err| err.within(input, rows))?;
+            cells.extend(
+                grid.iter()
+                    .filter(|(_, filled)| **filled)
+                    .map(|(pos, _)| pos),
+            );
+        }
+        shapes.push(Shape::new(id, Polyomino::new(cells)));
+    }
+    Ok(shapes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(rows: &str) -> Polyomino {
+        Polyomino::new(rows.lines().enumerate().flat_map(|(r, line)| {
+            line.char_indices()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(c, _)| Pos::new(r, c))
+        }))
+    }
+
+    #[test]
+    fn test_new_normalises() {
+        let p = Polyomino::new([(5, 3), (4, 4), (4, 3), (4, 3)].map(Pos::from));
+        assert_eq!(p.cells(), [(0, 0), (0, 1), (1, 0)].map(Pos::from));
+        assert_eq!((p.height(), p.width(), p.len()), (2, 2, 3));
+        assert_eq!(p.to_string(), "##\n#.");
+    }
+
+    #[test]
+    fn test_rotate_and_flip() {
+        let l = shape("#.\n#.\n##");
+        assert_eq!(l.rotate_cw().to_string(), "###\n#..");
+        assert_eq!(l.flip().to_string(), ".#\n.#\n##");
+        assert_eq!(l.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), l);
+    }
+
+    #[test]
+    fn test_orientation_counts() {
+        assert_eq!(shape("##\n##").orientations().len(), 1);
+        assert_eq!(shape("####").orientations().len(), 2);
+        assert_eq!(shape("###\n.#.").orientations().len(), 4);
+        assert_eq!(shape("##.\n.##").orientations().len(), 4);
+        assert_eq!(shape("#.\n#.\n##").orientations().len(), 8);
+        assert!(Polyomino::new([]).orientations()[0].is_empty());
+    }
+
+    #[test]
+    fn test_canonical_identifies_free_polyominoes() {
+        let l = shape("#.\n#.\n##");
+        for orientation in l.orientations() {
+            assert_eq!(orientation.canonical(), l.canonical());
+        }
+        assert_ne!(shape("##.\n.##").canonical(), l.canonical());
+    }
+
+    #[test]
+    fn test_board_placement() {
+        let l = shape("#.\n#.\n##").bitmap::<u64>();
+        let mut board = Board::<u64>::new(3, 3);
+        assert!(board.place(&l, Pos::new(0, 0)));
+        assert!(board.is_filled(Pos::new(2, 1)));
+        assert!(!board.is_filled(Pos::new(0, 1)));
+
+        let bar = shape("#\n#\n#").bitmap();
+        assert!(!board.fits(&bar, Pos::new(0, 0)), "overlaps the L");
+        assert!(!board.fits(&bar, Pos::new(1, 1)), "runs off the bottom");
+        assert!(!board.fits(&bar, Pos::new(0, 3)), "runs off the side");
+        assert!(!board.place(&bar, Pos::new(0, 0)));
+        assert!(
+            !board.is_filled(Pos::new(0, 1)),
+            "a failed place changes nothing"
+        );
+        assert!(board.place(&bar, Pos::new(0, 2)));
+        assert!((0..3).all(|row| board.is_filled(Pos::new(row, 2))));
+    }
+
+    #[test]
+    fn test_wide_boards() {
+        let domino = shape("##").bitmap::<u128>();
+        let mut board = Board::<u128>::new(128, 1);
+        assert!(board.place(&domino, Pos::new(0, 126)));
+        assert!(board.is_filled(Pos::new(0, 127)));
+        assert!(!board.fits(&domino, Pos::new(0, 127)));
+        assert!(!board.fits(&domino, Pos::new(0, 125)));
+    }
+
+    #[test]
+    fn test_parse_shapes() {
+        let input = "0:\n###\n##.\n##.\n\n1:\n..#\n.##\n##.\n";
+        let shapes = parse_shapes(input).expect("valid shapes");
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(shapes[0].id, 0);
+        assert_eq!(shapes[0].base().to_string(), "###\n##.\n##.");
+        assert_eq!(shapes[0].area(), 7);
+        assert_eq!(shapes[0].orientations().len(), 8);
+        assert_eq!(shapes[1].base().to_string(), "..#\n.##\n##.");
+        assert_eq!(shapes[1].orientations().len(), 4);
+    }
+
+    #[test]
+    fn test_parse_errors_point_into_the_input() {
+        let err = parse_shapes("0:\n#.#\n#o#\n").expect_err("bad cell");
+        assert_eq!(err.message(), "unexpected `o`");
+        assert_eq!(err.line_col(), (3, 2));
+
+        let err = parse_shapes("a:\n###\n").expect_err("bad id");
+        assert_eq!(err.message(), "invalid integer `a`");
+
+        let err = parse_shapes("###\n").expect_err("no header");
+        assert_eq!(err.message(), "expected `key: value`");
+
+        let err = parse_shapes("0: #\n").expect_err("text after the header");
+        assert_eq!(err.message(), "unexpected text after a shape header");
+    }
+}
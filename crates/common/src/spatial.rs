@@ -0,0 +1,288 @@
+//! Nearest-neighbour queries over points in 3D space.
+//!
+//! [`KdTree::nearest`] answers k-nearest queries, and
+//! [`KdTree::closest_pairs`] streams every pair of points closest first
+//! without building the quadratic list of all pairs up front.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::geometry::Point3;
+
+/// Neighbours fetched per point before the first refill.
+const INITIAL_NEIGHBOURS: usize = 8;
+
+/// A static k-d tree over a set of points, addressed by their index in the
+/// slice it was built from.
+#[derive(Debug, Clone)]
+pub struct KdTree {
+    points: Vec<Point3>,
+    /// Point indices in implicit tree order: the node of `lo..hi` sits at the
+    /// midpoint, with its left and right subtrees on either side.
+    order: Vec<usize>,
+}
+
+/// Two points and their squared distance, with `a < b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pair {
+    pub a: usize,
+    pub b: usize,
+    pub dist_sq: i64,
+}
+
+fn coord(point: &Point3, axis: usize) -> i64 {
+    match axis {
+        0 => point.x,
+        1 => point.y,
+        _ => point.z,
+    }
+}
+
+impl KdTree {
+    #[must_use]
+    pub fn new(points: &[Point3]) -> Self {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        build(points, &mut order, 0);
+        Self {
+            points: points.to_vec(),
+            order,
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    #[must_use]
+    pub fn points(&self) -> &[Point3] {
+        &self.points
+    }
+
+    /// The `k` points nearest to `target` as `(dist_sq, index)`, nearest
+    /// first, ties broken by the smaller index.
+    #[must_use]
+    pub fn nearest(&self, target: &Point3, k: usize) -> Vec<(i64, usize)> {
+        self.nearest_where(target, k, |_| true)
+    }
+
+    /// Like [`nearest`](Self::nearest), but only over points whose index
+    /// passes `keep`.
+    #[must_use]
+    pub fn nearest_where(
+        &self,
+        target: &Point3,
+        k: usize,
+        keep: impl Fn(usize) -> bool,
+    ) -> Vec<(i64, usize)> {
+        let mut best = Vec::with_capacity(k + 1);
+        if k > 0 {
+            self.search(target, k, &keep, 0, self.len(), 0, &mut best);
+        }
+        best
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &self,
+        target: &Point3,
+        k: usize,
+        keep: &impl Fn(usize) -> bool,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        best: &mut Vec<(i64, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let index = self.order[mid];
+        let point = &self.points[index];
+        if keep(index) {
+            let entry = (point.dist_sq(target), index);
+            if best.len() < k || entry < best[k - 1] {
+                let at = best.partition_point(|&e| e < entry);
+                best.insert(at, entry);
+                best.truncate(k);
+            }
+        }
+
+        let axis = depth % 3;
+        let diff = coord(target, axis) - coord(point, axis);
+        let (near, far) = if diff < 0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.search(target, k, keep, near.0, near.1, depth + 1, best);
+        // The far side is at least as far away as the splitting plane.
+        if best.len() < k || diff * diff <= best[k - 1].0 {
+            self.search(target, k, keep, far.0, far.1, depth + 1, best);
+        }
+    }
+
+    /// Every pair of points, closest first, ties broken by `(a, b)`.
+    ///
+    /// Pairs are produced lazily, so taking the first few is much cheaper
+    /// than enumerating and sorting all `n * (n - 1) / 2` of them.
+    #[must_use]
+    pub fn closest_pairs(&self) -> ClosestPairs<'_> {
+        let mut pairs = ClosestPairs {
+            tree: self,
+            neighbours: vec![Neighbours::default(); self.len()],
+            heap: BinaryHeap::with_capacity(self.len()),
+        };
+        for a in 0..self.len() {
+            pairs.refill(a);
+            pairs.push_next(a);
+        }
+        pairs
+    }
+}
+
+/// Arranges `order` into implicit k-d tree order, splitting on the median.
+fn build(points: &[Point3], order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+    let mid = order.len() / 2;
+    let axis = depth % 3;
+    order.select_nth_unstable_by_key(mid, |&i| coord(&points[i], axis));
+    let (left, right) = order.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+/// The nearest neighbours fetched so far for one point.
+#[derive(Debug, Clone, Default)]
+struct Neighbours {
+    found: Vec<(i64, usize)>,
+    /// Position in `found` of the next neighbour to hand out.
+    next: usize,
+    /// Whether `found` already holds every other point.
+    complete: bool,
+}
+
+/// Iterator returned by [`KdTree::closest_pairs`].
+///
+/// Each point keeps a buffer of its nearest neighbours, and a heap merges the
+/// buffers' heads. An exhausted buffer is refetched twice as large. Every
+/// pair shows up from both ends and is reported from its lower index.
+#[derive(Debug)]
+pub struct ClosestPairs<'a> {
+    tree: &'a KdTree,
+    neighbours: Vec<Neighbours>,
+    heap: BinaryHeap<Reverse<(i64, usize, usize)>>,
+}
+
+impl ClosestPairs<'_> {
+    fn refill(&mut self, a: usize) {
+        let others = self.tree.len() - 1;
+        let entry = &mut self.neighbours[a];
+        let want = (2 * entry.found.len()).max(INITIAL_NEIGHBOURS).min(others);
+        let target = &self.tree.points[a];
+        entry.found = self.tree.nearest_where(target, want, |b| b != a);
+        entry.complete = want == others;
+    }
+
+    /// Queues the next neighbour of `a`, refilling its buffer if needed.
+    fn push_next(&mut self, a: usize) {
+        if self.neighbours[a].next == self.neighbours[a].found.len() {
+            if self.neighbours[a].complete {
+                return;
+            }
+            self.refill(a);
+        }
+        let entry = &mut self.neighbours[a];
+        if let Some(&(dist_sq, b)) = entry.found.get(entry.next) {
+            entry.next += 1;
+            self.heap.push(Reverse((dist_sq, a, b)));
+        }
+    }
+}
+
+impl Iterator for ClosestPairs<'_> {
+    type Item = Pair;
+
+    fn next(&mut self) -> Option<Pair> {
+        loop {
+            let Reverse((dist_sq, a, b)) = self.heap.pop()?;
+            self.push_next(a);
+            if a < b {
+                return Some(Pair { a, b, dist_sq });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random points in a small cube, so ties occur.
+    fn scatter(n: usize, side: i64) -> Vec<Point3> {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            i64::try_from(state % side.unsigned_abs()).expect("small")
+        };
+        (0..n)
+            .map(|_| Point3::new(next(), next(), next()))
+            .collect()
+    }
+
+    fn brute_force_pairs(points: &[Point3]) -> Vec<Pair> {
+        let mut pairs = Vec::new();
+        for a in 0..points.len() {
+            for b in a + 1..points.len() {
+                let dist_sq = points[a].dist_sq(&points[b]);
+                pairs.push(Pair { a, b, dist_sq });
+            }
+        }
+        pairs.sort_by_key(|p| (p.dist_sq, p.a, p.b));
+        pairs
+    }
+
+    #[test]
+    fn test_nearest_matches_brute_force() {
+        let points = scatter(300, 50);
+        let tree = KdTree::new(&points);
+        for target in scatter(20, 60) {
+            let mut expected: Vec<(i64, usize)> = points
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (p.dist_sq(&target), i))
+                .collect();
+            expected.sort_unstable();
+            expected.truncate(7);
+            assert_eq!(tree.nearest(&target, 7), expected);
+        }
+    }
+
+    #[test]
+    fn test_closest_pairs_matches_brute_force() {
+        for (n, side) in [(0, 1), (1, 1), (2, 5), (60, 4), (250, 1_000)] {
+            let points = scatter(n, side);
+            let tree = KdTree::new(&points);
+            let streamed: Vec<Pair> = tree.closest_pairs().collect();
+            assert_eq!(streamed, brute_force_pairs(&points), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_closest_pairs_with_duplicates() {
+        let points = vec![Point3::new(1, 1, 1); 5];
+        let tree = KdTree::new(&points);
+        let pairs: Vec<(usize, usize)> = tree.closest_pairs().map(|p| (p.a, p.b)).collect();
+        assert_eq!(pairs.len(), 10);
+        assert_eq!(pairs[..3], [(0, 1), (0, 2), (0, 3)]);
+    }
+}
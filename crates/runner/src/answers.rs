@@ -0,0 +1,504 @@
+//! The `answers.toml` registry of accepted answers.
+//!
+//! Answers are grouped by year and day, one string per part, followed by any
+//! submissions the site has judged:
+//!
+//! ```toml
+//! [2025.day-08]
+//! part1 = "75582"
+//! part2 = "59039696"
+//!
+//! [[2025.day-08.attempts.part1]]
+//! answer = "80000"
+//! verdict = "too_high"
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use common::input::Puzzle;
+use miette::Diagnostic;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Overrides the location of the answers file.
+pub const ANSWERS_ENV: &str = "AOC_ANSWERS";
+
+/// Written above the tables on every save, since serializing drops comments.
+const HEADER: &str = "# Accepted answers, checked by `aoc verify` and `cargo test -p runner`.\n\n";
+
+/// How the site judged a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Correct => "correct",
+            Self::TooHigh => "too high",
+            Self::TooLow => "too low",
+            Self::Wrong => "wrong",
+        })
+    }
+}
+
+/// One judged submission.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Attempt {
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// Submission history for both parts of one day.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Attempts {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub part1: Vec<Attempt>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub part2: Vec<Attempt>,
+}
+
+impl Attempts {
+    fn is_empty(&self) -> bool {
+        self.part1.is_empty() && self.part2.is_empty()
+    }
+}
+
+/// Stored answers for both parts of one day.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DayAnswers {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+    #[serde(default, skip_serializing_if = "Attempts::is_empty")]
+    pub attempts: Attempts,
+}
+
+/// What the registry already knows about a candidate answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Known {
+    /// It is the stored accepted answer.
+    Accepted,
+    /// Another answer has already been accepted.
+    Conflicts { accepted: String },
+    /// It was rejected before, or is ruled out by an earlier too-high or
+    /// too-low verdict.
+    Rejected { verdict: Verdict, previous: String },
+}
+
+impl DayAnswers {
+    fn part(&self, part: u8) -> Option<&String> {
+        match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn part_mut(&mut self, part: u8) -> Option<&mut Option<String>> {
+        match part {
+            1 => Some(&mut self.part1),
+            2 => Some(&mut self.part2),
+            _ => None,
+        }
+    }
+
+    fn attempts(&self, part: u8) -> &[Attempt] {
+        match part {
+            1 => &self.attempts.part1,
+            2 => &self.attempts.part2,
+            _ => &[],
+        }
+    }
+
+    fn attempts_mut(&mut self, part: u8) -> Option<&mut Vec<Attempt>> {
+        match part {
+            1 => Some(&mut self.attempts.part1),
+            2 => Some(&mut self.attempts.part2),
+            _ => None,
+        }
+    }
+}
+
+/// Every known answer, keyed by year (`"2025"`) then day (`"day-08"`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Answers {
+    years: BTreeMap<String, BTreeMap<String, DayAnswers>>,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum AnswersError {
+    #[error("failed to read {}", path.display())]
+    #[diagnostic(code(runner::answers::read))]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("failed to write {}", path.display())]
+    #[diagnostic(code(runner::answers::write))]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("invalid answers file")]
+    #[diagnostic(code(runner::answers::parse))]
+    Parse(#[from] toml::de::Error),
+
+    #[error("failed to serialize answers")]
+    #[diagnostic(code(runner::answers::serialize))]
+    Serialize(#[from] toml::ser::Error),
+
+    #[error("{puzzle} is not a valid puzzle part")]
+    #[diagnostic(code(runner::answers::invalid_part))]
+    InvalidPart { puzzle: Puzzle },
+}
+
+impl Answers {
+    /// `AOC_ANSWERS`, defaulting to `answers.toml` at the workspace root.
+    #[must_use]
+    pub fn default_path() -> PathBuf {
+        std::env::var_os(ANSWERS_ENV).map_or_else(
+            || common::input::workspace_dir().join("answers.toml"),
+            PathBuf::from,
+        )
+    }
+
+    /// Reads the answers file. A missing file is an empty registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AnswersError::Read`] when the file exists but cannot be read,
+    /// or [`AnswersError::Parse`] when it is not a valid answers file.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => contents.parse(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(AnswersError::Read {
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    /// Writes the registry back out, sorted by year and day.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AnswersError::Write`] when the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), AnswersError> {
+        std::fs::write(path, self.to_toml()?).map_err(|source| AnswersError::Write {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Renders the registry as TOML, under the file's header comment.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AnswersError::Serialize`] if serialization fails.
+    pub fn to_toml(&self) -> Result<String, AnswersError> {
+        Ok(format!("{HEADER}{}", toml::to_string(self)?))
+    }
+
+    /// The stored answer for `puzzle`, if any.
+    #[must_use]
+    pub fn get(&self, puzzle: Puzzle) -> Option<&str> {
+        self.day(puzzle)?.part(puzzle.part).map(String::as_str)
+    }
+
+    /// Judged submissions for `puzzle`, oldest first.
+    #[must_use]
+    pub fn attempts(&self, puzzle: Puzzle) -> &[Attempt] {
+        self.day(puzzle)
+            .map_or(&[], |day| day.attempts(puzzle.part))
+    }
+
+    /// Checks `answer` against the accepted answer and earlier attempts, so
+    /// it is not submitted again when the outcome is already known.
+    #[must_use]
+    pub fn known(&self, puzzle: Puzzle, answer: &str) -> Option<Known> {
+        if let Some(accepted) = self.get(puzzle) {
+            return Some(if accepted == answer {
+                Known::Accepted
+            } else {
+                Known::Conflicts {
+                    accepted: accepted.to_string(),
+                }
+            });
+        }
+
+        let value = answer.parse::<i128>().ok();
+        self.attempts(puzzle).iter().find_map(|attempt| {
+            let ruled_out = attempt.answer == answer
+                || match (attempt.verdict, value, attempt.answer.parse::<i128>()) {
+                    (Verdict::TooHigh, Some(value), Ok(bound)) => value >= bound,
+                    (Verdict::TooLow, Some(value), Ok(bound)) => value <= bound,
+                    _ => false,
+                };
+            (ruled_out && attempt.verdict != Verdict::Correct).then(|| Known::Rejected {
+                verdict: attempt.verdict,
+                previous: attempt.answer.clone(),
+            })
+        })
+    }
+
+    /// Records a judged submission. A correct one also becomes the stored
+    /// answer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AnswersError::InvalidPart`] unless the part is 1 or 2.
+    pub fn record(
+        &mut self,
+        puzzle: Puzzle,
+        answer: impl Into<String>,
+        verdict: Verdict,
+    ) -> Result<(), AnswersError> {
+        let answer = answer.into();
+        if verdict == Verdict::Correct {
+            self.insert(puzzle, answer.clone())?;
+        }
+        self.day_mut(puzzle)
+            .attempts_mut(puzzle.part)
+            .ok_or(AnswersError::InvalidPart { puzzle })?
+            .push(Attempt { answer, verdict });
+        Ok(())
+    }
+
+    /// Stores `answer` for `puzzle`, returning the answer it replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AnswersError::InvalidPart`] unless the part is 1 or 2.
+    pub fn insert(
+        &mut self,
+        puzzle: Puzzle,
+        answer: impl Into<String>,
+    ) -> Result<Option<String>, AnswersError> {
+        let slot = self
+            .day_mut(puzzle)
+            .part_mut(puzzle.part)
+            .ok_or(AnswersError::InvalidPart { puzzle })?;
+        Ok(slot.replace(answer.into()))
+    }
+
+    fn day(&self, puzzle: Puzzle) -> Option<&DayAnswers> {
+        self.years
+            .get(&puzzle.year.to_string())?
+            .get(&day_key(puzzle.day))
+    }
+
+    fn day_mut(&mut self, puzzle: Puzzle) -> &mut DayAnswers {
+        self.years
+            .entry(puzzle.year.to_string())
+            .or_default()
+            .entry(day_key(puzzle.day))
+            .or_default()
+    }
+
+    /// Number of stored part answers.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.years
+            .values()
+            .flat_map(BTreeMap::values)
+            .map(|day| usize::from(day.part1.is_some()) + usize::from(day.part2.is_some()))
+            .sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = AnswersError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(toml::from_str(s)?)
+    }
+}
+
+fn day_key(day: u8) -> String {
+    format!("day-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+[2023.day-01]
+part1 = "57346"
+part2 = "57345"
+
+[2025.day-08]
+part1 = "75582"
+"#;
+
+    #[test]
+    fn test_get_by_puzzle() {
+        let answers: Answers = SAMPLE.parse().expect("valid answers");
+        assert_eq!(answers.get(Puzzle::new(2023, 1, 2)), Some("57345"));
+        assert_eq!(answers.get(Puzzle::new(2025, 8, 1)), Some("75582"));
+        assert_eq!(answers.get(Puzzle::new(2025, 8, 2)), None);
+        assert_eq!(answers.get(Puzzle::new(2024, 1, 1)), None);
+        assert_eq!(answers.len(), 3);
+    }
+
+    #[test]
+    fn test_insert_round_trips() {
+        let mut answers: Answers = SAMPLE.parse().expect("valid answers");
+        let previous = answers
+            .insert(Puzzle::new(2025, 8, 2), "59039696")
+            .expect("valid part");
+        assert_eq!(previous, None);
+        answers
+            .insert(Puzzle::new(2024, 3, 1), "171183089")
+            .expect("valid part");
+
+        let rendered = answers.to_toml().expect("serializable");
+        assert!(rendered.contains("[2024.day-03]"), "{rendered}");
+        let reparsed: Answers = rendered.parse().expect("round trip");
+        assert_eq!(reparsed, answers);
+        assert_eq!(reparsed.get(Puzzle::new(2025, 8, 2)), Some("59039696"));
+    }
+
+    #[test]
+    fn test_insert_rejects_part_three() {
+        let mut answers = Answers::default();
+        let err = answers
+            .insert(Puzzle::new(2025, 8, 3), "0")
+            .expect_err("only two parts");
+        assert!(matches!(err, AnswersError::InvalidPart { .. }));
+        assert!(answers.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let err = "[2025.day-08]\npart3 = \"1\"\n"
+            .parse::<Answers>()
+            .expect_err("part3 is not a field");
+        assert!(matches!(err, AnswersError::Parse(_)));
+    }
+
+    #[test]
+    fn test_record_attempts() {
+        let puzzle = Puzzle::new(2025, 9, 2);
+        let mut answers = Answers::default();
+        answers
+            .record(puzzle, "2000000000", Verdict::TooHigh)
+            .expect("valid part");
+        answers
+            .record(puzzle, "1000", Verdict::TooLow)
+            .expect("valid part");
+        answers
+            .record(puzzle, "1500000000", Verdict::Wrong)
+            .expect("valid part");
+        assert_eq!(answers.attempts(puzzle).len(), 3);
+        assert_eq!(answers.get(puzzle), None);
+
+        let rendered = answers.to_toml().expect("serializable");
+        assert!(
+            rendered.contains("[[2025.day-09.attempts.part2]]"),
+            "{rendered}"
+        );
+        assert!(rendered.contains(r#"verdict = "too_high""#), "{rendered}");
+        assert_eq!(rendered.parse::<Answers>().expect("round trip"), answers);
+
+        answers
+            .record(puzzle, "1498673376", Verdict::Correct)
+            .expect("valid part");
+        assert_eq!(answers.get(puzzle), Some("1498673376"));
+        assert_eq!(answers.attempts(puzzle).len(), 4);
+    }
+
+    #[test]
+    fn test_known_answers_are_not_resubmitted() {
+        let puzzle = Puzzle::new(2025, 9, 2);
+        let mut answers = Answers::default();
+        answers
+            .record(puzzle, "2000", Verdict::TooHigh)
+            .expect("valid part");
+        answers
+            .record(puzzle, "100", Verdict::TooLow)
+            .expect("valid part");
+        answers
+            .record(puzzle, "1234", Verdict::Wrong)
+            .expect("valid part");
+
+        let rejected = |verdict, previous: &str| {
+            Some(Known::Rejected {
+                verdict,
+                previous: previous.to_string(),
+            })
+        };
+        assert_eq!(
+            answers.known(puzzle, "1234"),
+            rejected(Verdict::Wrong, "1234")
+        );
+        assert_eq!(
+            answers.known(puzzle, "2500"),
+            rejected(Verdict::TooHigh, "2000")
+        );
+        assert_eq!(
+            answers.known(puzzle, "2000"),
+            rejected(Verdict::TooHigh, "2000")
+        );
+        assert_eq!(
+            answers.known(puzzle, "-5"),
+            rejected(Verdict::TooLow, "100")
+        );
+        assert_eq!(answers.known(puzzle, "1500"), None);
+        assert_eq!(answers.known(puzzle, "abc"), None);
+
+        answers
+            .record(puzzle, "1500", Verdict::Correct)
+            .expect("valid part");
+        assert_eq!(answers.known(puzzle, "1500"), Some(Known::Accepted));
+        assert_eq!(
+            answers.known(puzzle, "1501"),
+            Some(Known::Conflicts {
+                accepted: "1500".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_and_save() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("answers.toml");
+        assert!(Answers::load(&path).expect("missing is empty").is_empty());
+
+        let answers: Answers = SAMPLE.parse().expect("valid answers");
+        answers.save(&path).expect("writable");
+        assert_eq!(Answers::load(&path).expect("saved file"), answers);
+        let saved = std::fs::read_to_string(&path).expect("saved file");
+        assert!(saved.starts_with(HEADER));
+    }
+
+    #[test]
+    fn test_checked_in_file_survives_a_save() {
+        let path = common::input::workspace_dir().join("answers.toml");
+        let contents = std::fs::read_to_string(&path).expect("answers.toml");
+        let answers: Answers = contents.parse().expect("valid answers");
+        assert_eq!(answers.to_toml().expect("serializable"), contents);
+    }
+}
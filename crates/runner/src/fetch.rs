@@ -0,0 +1,267 @@
+//! Downloading puzzle inputs into the local cache.
+//!
+//! Each day has one canonical cached file, `<inputs dir>/<year>/day-<dd>.txt`,
+//! which [`common::input`] already prefers over the checked-in copies. The
+//! inputs directory is git-ignored: inputs are not meant to be redistributed.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use common::input::InputConfig;
+use miette::Diagnostic;
+use thiserror::Error;
+
+use crate::http::{Client, Transport};
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum FetchError {
+    #[error("{year} day {day} is not a puzzle")]
+    #[diagnostic(code(runner::fetch::no_such_day))]
+    NoSuchDay { year: u16, day: u8 },
+
+    #[error("{year} day {day} has not unlocked yet")]
+    #[diagnostic(
+        code(runner::fetch::locked),
+        help("puzzles unlock at midnight US Eastern time")
+    )]
+    Locked { year: u16, day: u8 },
+
+    #[error("the session was rejected while fetching {url}")]
+    #[diagnostic(
+        code(runner::fetch::bad_session),
+        help("the session cookie has probably expired; copy a fresh one into AOC_SESSION")
+    )]
+    BadSession { url: String },
+
+    #[error("unexpected HTTP {status} from {url}")]
+    #[diagnostic(code(runner::fetch::status))]
+    Status { url: String, status: u16 },
+
+    #[error("request to {url} failed")]
+    #[diagnostic(code(runner::fetch::transport))]
+    Transport {
+        url: String,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("failed to write {}", path.display())]
+    #[diagnostic(code(runner::fetch::cache))]
+    Cache {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// When `day` of `year` unlocks: midnight UTC-5 on December `day`.
+#[must_use]
+pub fn unlock_time(year: u16, day: u8) -> SystemTime {
+    let days = days_from_civil(i64::from(year), 12, i64::from(day));
+    let seconds = days * 86_400 + 5 * 3_600;
+    UNIX_EPOCH + Duration::from_secs(seconds.try_into().unwrap_or(0))
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+impl<T: Transport> Client<T> {
+    /// Downloads the input for one day, refusing days that are not out yet
+    /// so nothing is requested that the site would reject anyway.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FetchError::NoSuchDay`] or [`FetchError::Locked`] without
+    /// making a request, and the other variants when the request fails.
+    pub fn fetch_input(&mut self, year: u16, day: u8) -> Result<String, FetchError> {
+        if year < 2015 || !(1..=25).contains(&day) {
+            return Err(FetchError::NoSuchDay { year, day });
+        }
+        if SystemTime::now() < unlock_time(year, day) {
+            return Err(FetchError::Locked { year, day });
+        }
+
+        let path = format!("/{year}/day/{day}/input");
+        let url = self.url(&path);
+        let response = self.get(&path).map_err(|source| FetchError::Transport {
+            url: url.clone(),
+            source,
+        })?;
+        match response.status {
+            200 => Ok(response.body),
+            // "Puzzle inputs differ by user. Please log in to get your puzzle input."
+            400 | 401 | 403 => Err(FetchError::BadSession { url }),
+            404 => Err(FetchError::Locked { year, day }),
+            status => Err(FetchError::Status { url, status }),
+        }
+    }
+}
+
+/// The on-disk cache of downloaded inputs.
+#[derive(Debug, Clone)]
+pub struct InputCache {
+    config: InputConfig,
+}
+
+impl InputCache {
+    #[must_use]
+    pub const fn new(config: InputConfig) -> Self {
+        Self { config }
+    }
+
+    /// Uses the inputs directory from the environment.
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::new(InputConfig::from_env())
+    }
+
+    #[must_use]
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.config.cache_path(year, day)
+    }
+
+    #[must_use]
+    pub fn contains(&self, year: u16, day: u8) -> bool {
+        self.path(year, day).is_file()
+    }
+
+    /// Writes `input` as the cached copy for the day and returns its path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FetchError::Cache`] when the file cannot be written.
+    pub fn store(&self, year: u16, day: u8, input: &str) -> Result<PathBuf, FetchError> {
+        let path = self.path(year, day);
+        write_atomically(&path, input).map_err(|source| FetchError::Cache {
+            path: path.clone(),
+            source,
+        })?;
+        Ok(path)
+    }
+}
+
+/// Writes through a sibling temp file so an interrupted download never
+/// leaves a truncated input behind.
+fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let partial = path.with_extension("txt.partial");
+    std::fs::write(&partial, contents)?;
+    std::fs::rename(&partial, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::input::Puzzle;
+
+    use super::*;
+    use crate::http::UreqTransport;
+    use crate::stub::StubServer;
+
+    fn client(server: &StubServer) -> Client {
+        Client::new(UreqTransport::default(), "session")
+            .with_base_url(server.url())
+            .with_min_interval(Duration::ZERO)
+    }
+
+    fn config(root: &Path) -> InputConfig {
+        InputConfig {
+            input: None,
+            inputs_dir: root.join("inputs"),
+            workspace_dir: root.to_path_buf(),
+        }
+    }
+
+    #[test]
+    fn test_unlock_time() {
+        let secs = |time: SystemTime| {
+            time.duration_since(UNIX_EPOCH)
+                .expect("after the epoch")
+                .as_secs()
+        };
+        // 2025-12-01T05:00:00Z
+        assert_eq!(secs(unlock_time(2025, 1)), 1_764_565_200);
+        // 2015-12-25T05:00:00Z
+        assert_eq!(secs(unlock_time(2015, 25)), 1_451_019_600);
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let server = StubServer::start(vec![(200, "1\n2\n3\n".to_string())]);
+        let input = client(&server).fetch_input(2025, 8).expect("input");
+        assert_eq!(input, "1\n2\n3\n");
+        assert_eq!(server.requests()[0].path, "/2025/day/8/input");
+    }
+
+    #[test]
+    fn test_fetch_input_errors() {
+        let server = StubServer::start(vec![
+            (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.".into(),
+            ),
+            (404, "Not Found".into()),
+            (500, "Internal Server Error".into()),
+        ]);
+        let mut client = client(&server);
+        assert!(matches!(
+            client.fetch_input(2025, 1),
+            Err(FetchError::BadSession { .. })
+        ));
+        assert!(matches!(
+            client.fetch_input(2025, 2),
+            Err(FetchError::Locked { .. })
+        ));
+        assert!(matches!(
+            client.fetch_input(2025, 3),
+            Err(FetchError::Status { status: 500, .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_days_are_not_requested() {
+        let server = StubServer::start(Vec::new());
+        let mut client = client(&server);
+        assert!(matches!(
+            client.fetch_input(2025, 26),
+            Err(FetchError::NoSuchDay { .. })
+        ));
+        assert!(matches!(
+            client.fetch_input(2014, 1),
+            Err(FetchError::NoSuchDay { .. })
+        ));
+        assert!(matches!(
+            client.fetch_input(9999, 1),
+            Err(FetchError::Locked { .. })
+        ));
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn test_cached_input_is_picked_up_by_both_parts() {
+        let root = tempfile::tempdir().expect("temp dir");
+        let cache = InputCache::new(config(root.path()));
+        assert!(!cache.contains(2025, 8));
+
+        let path = cache.store(2025, 8, "cached\n").expect("writable cache");
+        assert_eq!(path, root.path().join("inputs/2025/day-08.txt"));
+        assert!(cache.contains(2025, 8));
+        assert!(!path.with_extension("txt.partial").exists());
+
+        for part in [1, 2] {
+            let loaded = config(root.path())
+                .load(Puzzle::new(2025, 8, part), None)
+                .expect("cached input");
+            assert_eq!(loaded, "cached\n");
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! A small, polite HTTP client for adventofcode.com.
+//!
+//! Requests go through the [`Transport`] trait so tests can point the client
+//! at a local stand-in server instead of the real site.
+
+use std::fmt;
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Holds the value of the `session` cookie from a logged-in browser.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+pub const BASE_URL: &str = "https://adventofcode.com";
+/// Identifies this tool to the site operators, as they ask automated clients to.
+pub const USER_AGENT: &str = concat!(
+    "github.com/xavierforge/advent-of-code runner/",
+    env!("CARGO_PKG_VERSION")
+);
+/// Minimum spacing between two requests from one client.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Status code and body of a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+/// Sends requests. Non-2xx statuses are responses, not errors.
+pub trait Transport {
+    /// Performs a `GET` with the given headers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when no response could be obtained.
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> io::Result<HttpResponse>;
+
+    /// Performs a `POST` of a url-encoded form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when no response could be obtained.
+    fn post_form(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+        form: &[(&str, &str)],
+    ) -> io::Result<HttpResponse>;
+}
+
+/// The real transport, backed by `ureq`.
+#[derive(Debug, Clone)]
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+impl Default for UreqTransport {
+    fn default() -> Self {
+        let agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+        Self { agent }
+    }
+}
+
+impl Transport for UreqTransport {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> io::Result<HttpResponse> {
+        let mut request = self.agent.get(url);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        into_response(request.call())
+    }
+
+    fn post_form(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+        form: &[(&str, &str)],
+    ) -> io::Result<HttpResponse> {
+        let mut request = self.agent.post(url);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        into_response(request.send_form(form.iter().copied()))
+    }
+}
+
+fn into_response(
+    result: Result<ureq::http::Response<ureq::Body>, ureq::Error>,
+) -> io::Result<HttpResponse> {
+    let mut response = result.map_err(io::Error::other)?;
+    Ok(HttpResponse {
+        status: response.status().as_u16(),
+        body: response
+            .body_mut()
+            .read_to_string()
+            .map_err(io::Error::other)?,
+    })
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("{SESSION_ENV} is not set")]
+#[diagnostic(
+    code(runner::http::missing_session),
+    help(
+        "log in to adventofcode.com and copy the value of the `session` cookie \
+         into {SESSION_ENV}"
+    )
+)]
+pub struct MissingSession;
+
+/// An authenticated client that spaces out its requests.
+pub struct Client<T = UreqTransport> {
+    transport: T,
+    base_url: String,
+    session: String,
+    min_interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl<T> fmt::Debug for Client<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // never print the session cookie
+        f.debug_struct("Client")
+            .field("base_url", &self.base_url)
+            .field("min_interval", &self.min_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Client {
+    /// A client for the real site using the session from `AOC_SESSION`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MissingSession`] when the variable is unset or blank.
+    pub fn from_env() -> Result<Self, MissingSession> {
+        let session = std::env::var(SESSION_ENV).map_err(|_| MissingSession)?;
+        if session.trim().is_empty() {
+            return Err(MissingSession);
+        }
+        Ok(Self::new(UreqTransport::default(), session))
+    }
+}
+
+impl<T: Transport> Client<T> {
+    #[must_use]
+    pub fn new(transport: T, session: impl Into<String>) -> Self {
+        Self {
+            transport,
+            base_url: BASE_URL.to_string(),
+            session: session.into().trim().to_string(),
+            min_interval: MIN_INTERVAL,
+            last_request: None,
+        }
+    }
+
+    /// Points the client at another host, e.g. a local test server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    #[must_use]
+    pub const fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// The absolute URL for a site path such as `/2025/day/8/input`.
+    #[must_use]
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    /// Sends an authenticated `GET` for `path`, waiting first if the previous
+    /// request was too recent.
+    ///
+    /// # Errors
+    ///
+    /// Returns the transport's error when no response was received.
+    pub fn get(&mut self, path: &str) -> io::Result<HttpResponse> {
+        self.throttle();
+        let cookie = format!("session={}", self.session);
+        let url = self.url(path);
+        tracing::debug!(%url, "GET");
+        self.transport
+            .get(&url, &[("Cookie", &cookie), ("User-Agent", USER_AGENT)])
+    }
+
+    /// Sends an authenticated form `POST` to `path`, rate limited like
+    /// [`get`](Self::get).
+    ///
+    /// # Errors
+    ///
+    /// Returns the transport's error when no response was received.
+    pub fn post_form(&mut self, path: &str, form: &[(&str, &str)]) -> io::Result<HttpResponse> {
+        self.throttle();
+        let cookie = format!("session={}", self.session);
+        let url = self.url(path);
+        tracing::debug!(%url, "POST");
+        self.transport.post_form(
+            &url,
+            &[("Cookie", &cookie), ("User-Agent", USER_AGENT)],
+            form,
+        )
+    }
+
+    fn throttle(&mut self) {
+        if let Some(last) = self.last_request {
+            let wait = self.min_interval.saturating_sub(last.elapsed());
+            if !wait.is_zero() {
+                tracing::debug!(?wait, "rate limiting");
+                thread::sleep(wait);
+            }
+        }
+        self.last_request = Some(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::StubServer;
+
+    #[test]
+    fn test_get_sends_session_and_user_agent() {
+        let server = StubServer::start(vec![(200, "hello".to_string())]);
+        let mut client =
+            Client::new(UreqTransport::default(), " abc123\n").with_base_url(server.url());
+
+        let response = client.get("/2025/day/8/input").expect("stub responds");
+        assert_eq!(
+            response,
+            HttpResponse {
+                status: 200,
+                body: "hello".to_string()
+            }
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].path, "/2025/day/8/input");
+        assert_eq!(requests[0].header("cookie"), Some("session=abc123"));
+        assert_eq!(requests[0].header("user-agent"), Some(USER_AGENT));
+        assert!(requests[0].body.is_empty());
+        assert!(!format!("{client:?}").contains("abc123"));
+    }
+
+    #[test]
+    fn test_post_form_is_url_encoded() {
+        let server = StubServer::start(vec![(200, "ok".to_string())]);
+        let mut client =
+            Client::new(UreqTransport::default(), "abc123").with_base_url(server.url());
+
+        client
+            .post_form(
+                "/2025/day/8/answer",
+                &[("level", "2"), ("answer", "Merry Xmas!")],
+            )
+            .expect("stub responds");
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2025/day/8/answer");
+        assert_eq!(requests[0].header("cookie"), Some("session=abc123"));
+        assert_eq!(
+            requests[0].header("content-type"),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(requests[0].body, "level=2&answer=Merry+Xmas!");
+    }
+
+    #[test]
+    fn test_error_statuses_are_responses() {
+        let server = StubServer::start(vec![(404, "Not Found".to_string())]);
+        let mut client = Client::new(UreqTransport::default(), "s").with_base_url(server.url());
+        let response = client.get("/2025/day/30/input").expect("stub responds");
+        assert_eq!(response.status, 404);
+    }
+
+    #[test]
+    fn test_requests_are_spaced_out() {
+        let interval = Duration::from_millis(150);
+        let server = StubServer::start(vec![(200, "a".to_string()), (200, "b".to_string())]);
+        let mut client = Client::new(UreqTransport::default(), "s")
+            .with_base_url(server.url())
+            .with_min_interval(interval);
+
+        let start = Instant::now();
+        client.get("/a").expect("first");
+        client.get("/b").expect("second");
+        assert!(start.elapsed() >= interval);
+    }
+
+    #[test]
+    fn test_unreachable_host_is_an_error() {
+        let url = StubServer::unused_url();
+        let mut client = Client::new(UreqTransport::default(), "s").with_base_url(url);
+        assert!(client.get("/").is_err());
+    }
+}
@@ -0,0 +1,16 @@
+//! The `aoc` command-line runner.
+//!
+//! Every solution crate is linked in and listed in [`registry::SOLUTIONS`], so
+//! any year, day and part can be run against any input without rebuilding.
+//! Accepted answers live in `answers.toml` and [`verify`] checks the
+//! registered solutions against them. [`fetch`] downloads missing inputs into
+//! the local cache and [`submit`] posts answers, recording each verdict.
+
+pub mod answers;
+pub mod fetch;
+pub mod http;
+pub mod registry;
+#[cfg(test)]
+mod stub;
+pub mod submit;
+pub mod verify;
@@ -0,0 +1,389 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use clap::{Args, Parser, Subcommand};
+use common::input::{InputConfig, Puzzle, Source};
+use miette::{Context, miette};
+use runner::answers::{Answers, Known};
+use runner::fetch::InputCache;
+use runner::http::Client;
+use runner::registry::{self, Solution};
+use runner::submit::Outcome;
+use runner::verify::{self, Report, Summary};
+use tracing_subscriber::EnvFilter;
+
+/// Run Advent of Code solutions from every year.
+#[derive(Debug, Parser)]
+#[command(name = "aoc", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run one part, one day, or a whole year and print answers with timings
+    Run(RunArgs),
+    /// Check solutions against the stored answers and report pass/fail/missing
+    Verify(VerifyArgs),
+    /// Download puzzle inputs into the local cache (needs `AOC_SESSION`)
+    Fetch(FetchArgs),
+    /// Submit an answer and record the verdict in the answers file (needs `AOC_SESSION`)
+    Submit(SubmitArgs),
+}
+
+#[derive(Debug, Args)]
+struct RunArgs {
+    /// Puzzle year, e.g. 2025
+    year: u16,
+
+    /// Day of the month; omit together with `--all` to run the whole year
+    #[arg(required_unless_present = "all")]
+    day: Option<u8>,
+
+    /// Part 1 or 2; both parts run when omitted
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Run every registered day of the year
+    #[arg(long, conflicts_with_all = ["day", "part", "input"])]
+    all: bool,
+
+    /// Read the puzzle input from this file, or `-` for stdin.
+    /// Defaults to `AOC_INPUT`, then the inputs directory, then the day
+    /// crate's `inputN.txt`.
+    #[arg(short, long, value_name = "PATH")]
+    input: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct VerifyArgs {
+    /// Only check this year; every registered solution is checked when omitted
+    year: Option<u16>,
+
+    /// Only check this day
+    #[arg(requires = "year")]
+    day: Option<u8>,
+
+    /// Only check this part
+    #[arg(requires = "day", value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Answers file to compare against. Defaults to `AOC_ANSWERS`, then
+    /// `answers.toml` at the workspace root.
+    #[arg(long, value_name = "PATH")]
+    answers: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct FetchArgs {
+    /// Puzzle year, e.g. 2025
+    year: u16,
+
+    /// Days to fetch; defaults to every registered day of the year
+    days: Vec<u8>,
+
+    /// Download again even when the input is already cached
+    #[arg(long)]
+    refresh: bool,
+}
+
+#[derive(Debug, Args)]
+struct SubmitArgs {
+    /// Puzzle year, e.g. 2025
+    year: u16,
+
+    /// Day of the month
+    day: u8,
+
+    /// Part 1 or 2
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: u8,
+
+    /// The answer to submit; runs the registered solution when omitted
+    answer: Option<String>,
+
+    /// Input for the solution when no answer is given, or `-` for stdin
+    #[arg(short, long, value_name = "PATH", conflicts_with = "answer")]
+    input: Option<PathBuf>,
+
+    /// Answers file to check and update. Defaults to `AOC_ANSWERS`, then
+    /// `answers.toml` at the workspace root.
+    #[arg(long, value_name = "PATH")]
+    answers: Option<PathBuf>,
+}
+
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    match Cli::parse().command {
+        Command::Run(args) => run(&args),
+        Command::Verify(args) => verify(&args),
+        Command::Fetch(args) => fetch(&args),
+        Command::Submit(args) => submit(&args),
+    }
+}
+
+fn run(args: &RunArgs) -> miette::Result<()> {
+    let selected: Vec<&Solution> = registry::select(Some(args.year), args.day, args.part).collect();
+    if selected.is_empty() {
+        return Err(miette!(
+            "no solution registered for {} day {} part {}",
+            args.year,
+            args.day.map_or("*".to_string(), |d| d.to_string()),
+            args.part.map_or("*".to_string(), |p| p.to_string()),
+        ));
+    }
+
+    let config = InputConfig::from_env();
+    let explicit = args.input.clone().map(Source::from_arg);
+    // stdin can only be drained once, so keep it for every selected part
+    let mut stdin: Option<String> = None;
+
+    for solution in selected {
+        let puzzle = solution.puzzle();
+        let input = match config.resolve(puzzle, explicit.clone())? {
+            Source::Stdin => match &stdin {
+                Some(buffer) => buffer.clone(),
+                None => stdin.insert(Source::Stdin.read()?).clone(),
+            },
+            source @ Source::File(_) => source.read()?,
+        };
+
+        let start = Instant::now();
+        let answer = (solution.process)(&input).wrap_err_with(|| puzzle.to_string())?;
+        let elapsed = start.elapsed();
+
+        println!("{puzzle}: {answer} ({elapsed:.2?})");
+    }
+    Ok(())
+}
+
+fn verify(args: &VerifyArgs) -> miette::Result<()> {
+    let path = args.answers.clone().unwrap_or_else(Answers::default_path);
+    let answers = Answers::load(&path)?;
+    let config = InputConfig::from_env();
+
+    let mut reports: Vec<Report> = Vec::new();
+    for report in verify::verify(
+        registry::select(args.year, args.day, args.part),
+        &config,
+        &answers,
+    ) {
+        println!("{report}");
+        reports.push(report);
+    }
+
+    let summary = Summary::of(&reports);
+    println!("{summary}");
+    if summary.failed > 0 {
+        return Err(miette!(
+            "{} part(s) no longer match {}",
+            summary.failed,
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+fn fetch(args: &FetchArgs) -> miette::Result<()> {
+    let mut days = args.days.clone();
+    if days.is_empty() {
+        days = registry::select(Some(args.year), None, None)
+            .map(|solution| solution.day)
+            .collect();
+    }
+    days.sort_unstable();
+    days.dedup();
+    if days.is_empty() {
+        return Err(miette!("no days registered for {}", args.year));
+    }
+
+    let cache = InputCache::from_env();
+    // only ask for a session once something actually needs downloading
+    let mut client: Option<Client> = None;
+    for day in days {
+        if !args.refresh && cache.contains(args.year, day) {
+            println!(
+                "{} day {day:02}: cached at {}",
+                args.year,
+                cache.path(args.year, day).display()
+            );
+            continue;
+        }
+        let client = match &mut client {
+            Some(client) => client,
+            None => client.insert(Client::from_env()?),
+        };
+        let input = client.fetch_input(args.year, day)?;
+        let path = cache.store(args.year, day, &input)?;
+        println!("{} day {day:02}: saved to {}", args.year, path.display());
+    }
+    Ok(())
+}
+
+fn submit(args: &SubmitArgs) -> miette::Result<()> {
+    let puzzle = Puzzle::new(args.year, args.day, args.part);
+    let answer = if let Some(answer) = &args.answer {
+        answer.trim().to_string()
+    } else {
+        let solution = registry::select(Some(args.year), Some(args.day), Some(args.part))
+            .next()
+            .ok_or_else(|| miette!("no solution registered for {puzzle}"))?;
+        let explicit = args.input.clone().map(Source::from_arg);
+        let input = InputConfig::from_env().load(puzzle, explicit)?;
+        (solution.process)(&input).wrap_err_with(|| puzzle.to_string())?
+    };
+
+    let path = args.answers.clone().unwrap_or_else(Answers::default_path);
+    let mut answers = Answers::load(&path)?;
+    match answers.known(puzzle, &answer) {
+        Some(Known::Accepted) => {
+            println!("{puzzle}: {answer} was already accepted");
+            return Ok(());
+        }
+        Some(Known::Conflicts { accepted }) => {
+            return Err(miette!(
+                "{puzzle} was already solved with {accepted}, so {answer} is wrong"
+            ));
+        }
+        Some(Known::Rejected { verdict, previous }) => {
+            return Err(miette!(
+                "{puzzle}: not submitting {answer}, {previous} was already judged {verdict}"
+            ));
+        }
+        None => {}
+    }
+
+    let outcome = Client::from_env()?.submit(puzzle, &answer)?;
+    if let Some(verdict) = outcome.verdict() {
+        answers.record(puzzle, answer.as_str(), verdict)?;
+        answers.save(&path)?;
+    }
+    match outcome {
+        Outcome::Correct => {
+            println!("{puzzle}: {answer} is correct");
+            Ok(())
+        }
+        outcome => Err(miette!("{puzzle}: {answer} was not accepted: {outcome}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    fn parse_command(args: &[&str]) -> Result<Command, clap::Error> {
+        let cli = Cli::try_parse_from(std::iter::once("aoc").chain(args.iter().copied()))?;
+        Ok(cli.command)
+    }
+
+    fn parse(args: &[&str]) -> Result<RunArgs, clap::Error> {
+        match parse_command(args)? {
+            Command::Run(run) => Ok(run),
+            other => panic!("expected `run`, got {other:?}"),
+        }
+    }
+
+    fn parse_verify(args: &[&str]) -> Result<VerifyArgs, clap::Error> {
+        match parse_command(args)? {
+            Command::Verify(verify) => Ok(verify),
+            other => panic!("expected `verify`, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cli_is_well_formed() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_single_part() {
+        let args = parse(&["run", "2025", "8", "2"]).expect("valid arguments");
+        assert_eq!(args.year, 2025);
+        assert_eq!(args.day, Some(8));
+        assert_eq!(args.part, Some(2));
+        assert!(!args.all);
+    }
+
+    #[test]
+    fn test_parse_whole_year() {
+        let args = parse(&["run", "2025", "--all"]).expect("valid arguments");
+        assert!(args.all);
+        assert_eq!(args.day, None);
+    }
+
+    #[test]
+    fn test_parse_input_path() {
+        let args = parse(&["run", "2024", "1", "--input", "-"]).expect("valid arguments");
+        assert_eq!(args.input, Some(PathBuf::from("-")));
+        assert_eq!(args.part, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_combinations() {
+        assert!(parse(&["run", "2025"]).is_err(), "day or --all is required");
+        assert!(parse(&["run", "2025", "8", "--all"]).is_err());
+        assert!(parse(&["run", "2025", "8", "3"]).is_err(), "only two parts");
+        assert!(parse(&["run", "2025", "--all", "-i", "x.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        let args = parse_verify(&["verify"]).expect("valid arguments");
+        assert_eq!((args.year, args.day, args.part), (None, None, None));
+
+        let args =
+            parse_verify(&["verify", "2025", "8", "--answers", "a.toml"]).expect("valid arguments");
+        assert_eq!((args.year, args.day), (Some(2025), Some(8)));
+        assert_eq!(args.answers, Some(PathBuf::from("a.toml")));
+
+        assert!(parse_verify(&["verify", "2025", "8", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_fetch() {
+        let Command::Fetch(args) =
+            parse_command(&["fetch", "2025", "8", "9", "--refresh"]).expect("valid arguments")
+        else {
+            panic!("expected `fetch`");
+        };
+        assert_eq!(args.year, 2025);
+        assert_eq!(args.days, [8, 9]);
+        assert!(args.refresh);
+    }
+
+    #[test]
+    fn test_parse_submit() {
+        let Command::Submit(args) =
+            parse_command(&["submit", "2025", "8", "2", "59039696"]).expect("valid arguments")
+        else {
+            panic!("expected `submit`");
+        };
+        assert_eq!((args.year, args.day, args.part), (2025, 8, 2));
+        assert_eq!(args.answer.as_deref(), Some("59039696"));
+
+        assert!(
+            parse_command(&["submit", "2025", "8"]).is_err(),
+            "part is required"
+        );
+        assert!(parse_command(&["submit", "2025", "8", "1", "42", "-i", "in.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_every_solution_has_a_checked_in_input() {
+        let config = InputConfig::from_env();
+        for solution in registry::SOLUTIONS {
+            let puzzle = solution.puzzle();
+            assert!(
+                config.candidates(puzzle).iter().any(|path| path.is_file()),
+                "missing input for {puzzle}"
+            );
+        }
+    }
+}
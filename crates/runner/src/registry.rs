@@ -0,0 +1,119 @@
+//! Static table of every solved puzzle part.
+
+use common::input::Puzzle;
+
+/// Signature shared by every `partN::process`.
+pub type Process = fn(&str) -> miette::Result<String>;
+
+/// One runnable puzzle part.
+#[derive(Debug, Clone, Copy)]
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub process: Process,
+}
+
+impl Solution {
+    #[must_use]
+    pub const fn puzzle(&self) -> Puzzle {
+        Puzzle::new(self.year, self.day, self.part)
+    }
+}
+
+macro_rules! part_number {
+    (part1) => {
+        1
+    };
+    (part2) => {
+        2
+    };
+}
+
+/// Expands `year / day => crate [parts]` lines into [`Solution`] entries.
+/// Parts that still `todo!()` are left out of the list.
+macro_rules! registry {
+    ($($year:literal / $day:literal => $krate:ident [$($part:ident),*];)*) => {
+        &[$($(
+            Solution {
+                year: $year,
+                day: $day,
+                part: part_number!($part),
+                process: $krate::$part::process,
+            },
+        )*)*]
+    };
+}
+
+pub static SOLUTIONS: &[Solution] = registry! {
+    2023 / 1 => y2023_day_01 [part1, part2];
+    2023 / 2 => y2023_day_02 [part1, part2];
+    2023 / 3 => y2023_day_03 [part1, part2];
+    2023 / 4 => y2023_day_04 [part1, part2];
+    2023 / 5 => y2023_day_05 [part1, part2];
+
+    2024 / 1 => y2024_day_01 [part1, part2];
+    2024 / 2 => y2024_day_02 [part1, part2];
+    2024 / 3 => y2024_day_03 [part1];
+    2024 / 4 => y2024_day_04 [part1];
+    2024 / 5 => y2024_day_05 [part1, part2];
+
+    2025 / 1 => day_01 [part1, part2];
+    2025 / 2 => day_02 [part1, part2];
+    2025 / 3 => day_03 [part1, part2];
+    2025 / 4 => day_04 [part1, part2];
+    2025 / 5 => day_05 [part1, part2];
+    2025 / 6 => day_06 [part1, part2];
+    2025 / 7 => day_07 [part1, part2];
+    2025 / 8 => day_08 [part1, part2];
+    2025 / 9 => day_09 [part1, part2];
+    2025 / 10 => day_10 [part1, part2];
+    2025 / 11 => day_11 [part1, part2];
+    2025 / 12 => day_12 [part1, part2];
+};
+
+/// Solutions matching `year`, `day` and `part` where given, in registration
+/// order.
+pub fn select(
+    year: Option<u16>,
+    day: Option<u8>,
+    part: Option<u8>,
+) -> impl Iterator<Item = &'static Solution> {
+    SOLUTIONS.iter().filter(move |s| {
+        year.is_none_or(|y| s.year == y)
+            && day.is_none_or(|d| s.day == d)
+            && part.is_none_or(|p| s.part == p)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_registry_keys_are_unique() {
+        let keys: HashSet<_> = SOLUTIONS.iter().map(|s| (s.year, s.day, s.part)).collect();
+        assert_eq!(keys.len(), SOLUTIONS.len());
+    }
+
+    #[test]
+    fn test_registry_covers_2025() {
+        assert_eq!(select(Some(2025), None, None).count(), 24);
+    }
+
+    #[test]
+    fn test_select_single_part() {
+        let found: Vec<_> = select(Some(2025), Some(8), Some(2)).collect();
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].year, found[0].day, found[0].part), (2025, 8, 2));
+    }
+
+    #[test]
+    fn test_select_unknown_day() {
+        assert_eq!(select(Some(2024), Some(25), None).count(), 0);
+        assert_eq!(select(Some(1999), None, None).count(), 0);
+        assert_eq!(select(None, None, None).count(), SOLUTIONS.len());
+    }
+}
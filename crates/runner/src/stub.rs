@@ -0,0 +1,133 @@
+//! A throwaway HTTP/1.1 server on `127.0.0.1` that plays back canned
+//! responses, so the client can be tested without network access.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// One request as the server saw it.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    /// Looks a header up case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub struct StubServer {
+    url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl StubServer {
+    /// Serves `responses` in order, one connection each, then stops.
+    pub fn start(responses: Vec<(u16, String)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind a local port");
+        let url = format!("http://{}", listener.local_addr().expect("bound address"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&requests);
+        let handle = thread::spawn(move || {
+            for (status, body) in responses {
+                let Ok((stream, _)) = listener.accept() else {
+                    return;
+                };
+                serve(stream, status, &body, &recorded);
+            }
+        });
+
+        Self {
+            url,
+            requests,
+            handle: Some(handle),
+        }
+    }
+
+    /// A URL nothing is listening on.
+    pub fn unused_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind a local port");
+        format!("http://{}", listener.local_addr().expect("bound address"))
+    }
+
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Requests received so far.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().expect("not poisoned").clone()
+    }
+}
+
+impl Drop for StubServer {
+    fn drop(&mut self) {
+        // Only join when every response was consumed, otherwise the thread is
+        // still blocked in `accept` and is left to die with the test process.
+        if let Some(handle) = self.handle.take()
+            && handle.is_finished()
+        {
+            handle.join().expect("stub server thread");
+        }
+    }
+}
+
+fn serve(stream: TcpStream, status: u16, body: &str, recorded: &Mutex<Vec<RecordedRequest>>) {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).expect("request line");
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line).expect("header line");
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let mut request_body = vec![0; length];
+    reader.read_exact(&mut request_body).expect("request body");
+
+    recorded
+        .lock()
+        .expect("not poisoned")
+        .push(RecordedRequest {
+            method,
+            path,
+            headers,
+            body: String::from_utf8_lossy(&request_body).into_owned(),
+        });
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {status} Stub\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .expect("write response");
+}
@@ -0,0 +1,247 @@
+//! Posting answers and reading the verdict out of the response page.
+
+use std::fmt;
+use std::time::Duration;
+
+use common::input::Puzzle;
+use miette::Diagnostic;
+use thiserror::Error;
+
+use crate::answers::Verdict;
+use crate::http::{Client, Transport};
+
+/// Assumed when a rate-limit page does not say how long to wait.
+const UNKNOWN_WAIT: Duration = Duration::from_secs(30);
+
+/// What the site said about a submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// Submitted too soon after the previous answer; retry after the wait.
+    RateLimited(Duration),
+    /// The part is already solved, or part 1 is not solved yet.
+    WrongLevel,
+}
+
+impl Outcome {
+    /// The verdict worth recording; `None` when the answer was not judged.
+    #[must_use]
+    pub const fn verdict(self) -> Option<Verdict> {
+        match self {
+            Self::Correct => Some(Verdict::Correct),
+            Self::TooHigh => Some(Verdict::TooHigh),
+            Self::TooLow => Some(Verdict::TooLow),
+            Self::Wrong => Some(Verdict::Wrong),
+            Self::RateLimited(_) | Self::WrongLevel => None,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => f.write_str("correct"),
+            Self::TooHigh => f.write_str("wrong, too high"),
+            Self::TooLow => f.write_str("wrong, too low"),
+            Self::Wrong => f.write_str("wrong"),
+            Self::RateLimited(wait) => write!(f, "rate limited, wait {}s", wait.as_secs()),
+            Self::WrongLevel => f.write_str("not the current level (already solved?)"),
+        }
+    }
+}
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum SubmitError {
+    #[error("request to {url} failed")]
+    #[diagnostic(code(runner::submit::transport))]
+    Transport {
+        url: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("unexpected HTTP {status} from {url}")]
+    #[diagnostic(
+        code(runner::submit::status),
+        help("a 400 or 500 usually means the session cookie has expired")
+    )]
+    Status { url: String, status: u16 },
+
+    #[error("could not understand the response: {message}")]
+    #[diagnostic(code(runner::submit::unrecognized))]
+    Unrecognized { message: String },
+}
+
+impl<T: Transport> Client<T> {
+    /// Posts `answer` for `puzzle` and parses the verdict.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SubmitError`] when the request fails, the status is not 200,
+    /// or the page does not contain a known verdict.
+    pub fn submit(&mut self, puzzle: Puzzle, answer: &str) -> Result<Outcome, SubmitError> {
+        let path = format!("/{}/day/{}/answer", puzzle.year, puzzle.day);
+        let url = self.url(&path);
+        let level = puzzle.part.to_string();
+        let response = self
+            .post_form(&path, &[("level", &level), ("answer", answer)])
+            .map_err(|source| SubmitError::Transport {
+                url: url.clone(),
+                source,
+            })?;
+        if response.status != 200 {
+            return Err(SubmitError::Status {
+                url,
+                status: response.status,
+            });
+        }
+        parse_response(&response.body)
+    }
+}
+
+/// Reads the verdict from the `<article>` of a submission response.
+///
+/// # Errors
+///
+/// Returns [`SubmitError::Unrecognized`] with the article text when no known
+/// message is found.
+pub fn parse_response(html: &str) -> Result<Outcome, SubmitError> {
+    let message = article_text(html);
+    let outcome = if message.contains("That's the right answer") {
+        Outcome::Correct
+    } else if message.contains("your answer is too high") {
+        Outcome::TooHigh
+    } else if message.contains("your answer is too low") {
+        Outcome::TooLow
+    } else if message.contains("That's not the right answer") {
+        Outcome::Wrong
+    } else if message.contains("You gave an answer too recently") {
+        Outcome::RateLimited(parse_wait(&message).unwrap_or(UNKNOWN_WAIT))
+    } else if message.contains("You don't seem to be solving the right level") {
+        Outcome::WrongLevel
+    } else {
+        return Err(SubmitError::Unrecognized { message });
+    };
+    Ok(outcome)
+}
+
+/// The text inside the first `<article>`, tags stripped and whitespace
+/// collapsed. Falls back to the whole page if there is no article.
+fn article_text(html: &str) -> String {
+    let article = html
+        .find("<article")
+        .and_then(|start| {
+            let end = html[start..].find("</article>")?;
+            Some(&html[start..start + end])
+        })
+        .unwrap_or(html);
+
+    let mut text = String::with_capacity(article.len());
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parses the `1m 5s` out of "You have 1m 5s left to wait".
+fn parse_wait(message: &str) -> Option<Duration> {
+    let start = message.find("You have ")? + "You have ".len();
+    let end = start + message[start..].find(" left to wait")?;
+    message[start..end]
+        .split_whitespace()
+        .try_fold(0, |total, token| {
+            let (number, unit) = token.split_at(token.find(|c: char| !c.is_ascii_digit())?);
+            let number: u64 = number.parse().ok()?;
+            let scale = match unit {
+                "h" => 3_600,
+                "m" => 60,
+                "s" => 1,
+                _ => return None,
+            };
+            Some(total + number * scale)
+        })
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::http::UreqTransport;
+    use crate::stub::StubServer;
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            include_str!(concat!("../fixtures/submit/", $name, ".html"))
+        };
+    }
+
+    #[rstest]
+    #[case(fixture!("correct"), Outcome::Correct)]
+    #[case(fixture!("too_high"), Outcome::TooHigh)]
+    #[case(fixture!("too_low"), Outcome::TooLow)]
+    #[case(fixture!("wrong"), Outcome::Wrong)]
+    #[case(fixture!("rate_limited"), Outcome::RateLimited(Duration::from_secs(34)))]
+    #[case(
+        fixture!("rate_limited_minutes"),
+        Outcome::RateLimited(Duration::from_secs(245))
+    )]
+    #[case(fixture!("wrong_level"), Outcome::WrongLevel)]
+    fn test_parse_fixtures(#[case] html: &str, #[case] expected: Outcome) {
+        assert_eq!(parse_response(html).expect("known response"), expected);
+    }
+
+    #[test]
+    fn test_unrecognized_page() {
+        let err = parse_response("<main><article><p>Something new</p></article></main>")
+            .expect_err("unknown message");
+        let SubmitError::Unrecognized { message } = err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(message, "Something new");
+    }
+
+    #[test]
+    fn test_verdicts() {
+        assert_eq!(Outcome::TooLow.verdict(), Some(Verdict::TooLow));
+        assert_eq!(Outcome::RateLimited(Duration::ZERO).verdict(), None);
+        assert_eq!(Outcome::WrongLevel.verdict(), None);
+    }
+
+    #[test]
+    fn test_submit_against_stand_in_server() {
+        let server = StubServer::start(vec![
+            (200, fixture!("too_high").to_string()),
+            (200, fixture!("correct").to_string()),
+            (500, "Internal Server Error".to_string()),
+        ]);
+        let mut client = Client::new(UreqTransport::default(), "session")
+            .with_base_url(server.url())
+            .with_min_interval(Duration::ZERO);
+        let puzzle = Puzzle::new(2025, 8, 2);
+
+        let outcome = client.submit(puzzle, "99999999").expect("judged");
+        assert_eq!(outcome, Outcome::TooHigh);
+        let outcome = client.submit(puzzle, "59039696").expect("judged");
+        assert_eq!(outcome, Outcome::Correct);
+        assert!(matches!(
+            client.submit(puzzle, "1"),
+            Err(SubmitError::Status { status: 500, .. })
+        ));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2025/day/8/answer");
+        assert_eq!(requests[1].body, "level=2&answer=59039696");
+    }
+}
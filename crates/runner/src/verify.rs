@@ -0,0 +1,232 @@
+//! Regression checks of registered solutions against `answers.toml`.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use common::input::{InputConfig, Puzzle};
+
+use crate::answers::Answers;
+use crate::registry::Solution;
+
+/// How one part compared with its stored answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// The answer matches.
+    Pass,
+    /// The answer differs from the stored one.
+    Fail { expected: String, actual: String },
+    /// No answer is stored yet; `actual` is what the solution produced.
+    Missing { actual: String },
+    /// No input could be found, so nothing was run.
+    NoInput(String),
+    /// `process` returned an error.
+    Error(String),
+}
+
+impl Status {
+    /// Whether this status should fail a verification run. Missing answers
+    /// and inputs are reported but tolerated.
+    #[must_use]
+    pub const fn is_failure(&self) -> bool {
+        matches!(self, Self::Fail { .. } | Self::Error(_))
+    }
+
+    #[must_use]
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Fail { .. } => "FAIL",
+            Self::Missing { .. } => "missing",
+            Self::NoInput(_) => "no input",
+            Self::Error(_) => "ERROR",
+        }
+    }
+}
+
+/// The outcome of checking one solution.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub puzzle: Puzzle,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>8}  {}", self.status.label(), self.puzzle)?;
+        match &self.status {
+            Status::Pass => write!(f, " ({:.2?})", self.elapsed),
+            Status::Fail { expected, actual } => {
+                write!(f, ": expected {expected}, got {actual}")
+            }
+            Status::Missing { actual } => write!(f, ": got {actual}"),
+            Status::NoInput(reason) | Status::Error(reason) => write!(f, ": {reason}"),
+        }
+    }
+}
+
+/// Runs `solution` on `input` and compares the result with `answers`.
+#[must_use]
+pub fn check(solution: &Solution, input: &str, answers: &Answers) -> Report {
+    let puzzle = solution.puzzle();
+    let start = Instant::now();
+    let result = (solution.process)(input);
+    let elapsed = start.elapsed();
+
+    let status = match (result, answers.get(puzzle)) {
+        (Err(err), _) => Status::Error(format!("{err:#}")),
+        (Ok(actual), None) => Status::Missing { actual },
+        (Ok(actual), Some(expected)) if actual == expected => Status::Pass,
+        (Ok(actual), Some(expected)) => Status::Fail {
+            expected: expected.to_string(),
+            actual,
+        },
+    };
+    Report {
+        puzzle,
+        status,
+        elapsed,
+    }
+}
+
+/// Lazily checks every solution, loading each input through `config`.
+pub fn verify<'s, 'a>(
+    solutions: impl IntoIterator<Item = &'s Solution>,
+    config: &'a InputConfig,
+    answers: &'a Answers,
+) -> impl Iterator<Item = Report> {
+    solutions
+        .into_iter()
+        .map(|solution| match config.load(solution.puzzle(), None) {
+            Ok(input) => check(solution, &input, answers),
+            Err(err) => Report {
+                puzzle: solution.puzzle(),
+                status: Status::NoInput(err.to_string()),
+                elapsed: Duration::ZERO,
+            },
+        })
+}
+
+/// Per-status totals for a set of reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub passed: usize,
+    pub failed: usize,
+    pub missing: usize,
+}
+
+impl Summary {
+    #[must_use]
+    pub fn of(reports: &[Report]) -> Self {
+        reports.iter().fold(Self::default(), |mut summary, report| {
+            match report.status {
+                Status::Pass => summary.passed += 1,
+                Status::Fail { .. } | Status::Error(_) => summary.failed += 1,
+                Status::Missing { .. } | Status::NoInput(_) => summary.missing += 1,
+            }
+            summary
+        })
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} missing",
+            self.passed, self.failed, self.missing
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn echo(input: &str) -> miette::Result<String> {
+        Ok(input.trim().to_string())
+    }
+
+    fn broken(_: &str) -> miette::Result<String> {
+        Err(miette::miette!("bad input"))
+    }
+
+    const fn solution(day: u8, process: crate::registry::Process) -> Solution {
+        Solution {
+            year: 2025,
+            day,
+            part: 1,
+            process,
+        }
+    }
+
+    fn answers() -> Answers {
+        "[2025.day-01]\npart1 = \"42\"\n"
+            .parse()
+            .expect("valid answers")
+    }
+
+    #[test]
+    fn test_check_statuses() {
+        let answers = answers();
+        assert_eq!(
+            check(&solution(1, echo), "42\n", &answers).status,
+            Status::Pass
+        );
+        assert_eq!(
+            check(&solution(1, echo), "41", &answers).status,
+            Status::Fail {
+                expected: "42".into(),
+                actual: "41".into()
+            }
+        );
+        assert_eq!(
+            check(&solution(2, echo), "7", &answers).status,
+            Status::Missing { actual: "7".into() }
+        );
+        let report = check(&solution(1, broken), "", &answers);
+        assert!(report.status.is_failure());
+        assert_eq!(
+            report.to_string(),
+            "   ERROR  2025 day 01 part 1: bad input"
+        );
+    }
+
+    #[test]
+    fn test_verify_reports_missing_input() {
+        let root = tempfile::tempdir().expect("temp dir");
+        let config = InputConfig {
+            input: None,
+            inputs_dir: root.path().join("inputs"),
+            workspace_dir: root.path().to_path_buf(),
+        };
+        let answers = answers();
+        let reports: Vec<_> = verify(&[solution(1, echo)], &config, &answers).collect();
+        assert_eq!(reports.len(), 1);
+        assert!(matches!(reports[0].status, Status::NoInput(_)));
+        assert!(!reports[0].status.is_failure());
+    }
+
+    #[test]
+    fn test_summary_counts() {
+        let answers = answers();
+        let reports = [
+            check(&solution(1, echo), "42", &answers),
+            check(&solution(1, echo), "0", &answers),
+            check(&solution(2, echo), "0", &answers),
+        ];
+        assert_eq!(
+            Summary::of(&reports),
+            Summary {
+                passed: 1,
+                failed: 1,
+                missing: 1
+            }
+        );
+        assert_eq!(
+            Summary::of(&reports).to_string(),
+            "1 passed, 1 failed, 1 missing"
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Runs every registered solution on its input and compares the result with
+//! `answers.toml`.
+
+use common::input::InputConfig;
+use runner::answers::Answers;
+use runner::registry::{SOLUTIONS, Solution};
+use runner::verify::{self, Summary};
+
+#[test]
+fn test_every_stored_answer_still_matches() {
+    let answers = Answers::load(&Answers::default_path()).expect("answers file");
+    let config = InputConfig::from_env();
+
+    let reports: Vec<_> = verify::verify(SOLUTIONS, &config, &answers).collect();
+    for report in &reports {
+        println!("{report}");
+    }
+
+    let failures: Vec<String> = reports
+        .iter()
+        .filter(|report| report.status.is_failure())
+        .map(ToString::to_string)
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{}\n{}",
+        Summary::of(&reports),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_every_registered_part_has_an_answer() {
+    let answers = Answers::load(&Answers::default_path()).expect("answers file");
+    let unanswered: Vec<String> = SOLUTIONS
+        .iter()
+        .map(Solution::puzzle)
+        .filter(|puzzle| answers.get(*puzzle).is_none())
+        .map(|puzzle| puzzle.to_string())
+        .collect();
+    assert!(
+        unanswered.is_empty(),
+        "no stored answer for: {}",
+        unanswered.join(", ")
+    );
+}
@@ -0,0 +1,52 @@
+use common::parse::{self, ParseError};
+
+/// Combines the first and last of `digits` into a two-digit number. A line
+/// with a single digit uses it twice.
+///
+/// # Errors
+///
+/// Fails, pointing at `line`, when it holds no digit at all.
+pub fn calibration_value(
+    line: &str,
+    mut digits: impl DoubleEndedIterator<Item = u32>,
+) -> Result<u32, ParseError> {
+    let first = digits.next().ok_or_else(|| {
+        ParseError::at(line, line.trim(), "no digit in this line")
+            .with_label("every calibration line needs at least one digit")
+    })?;
+    let last = digits.next_back().unwrap_or(first);
+    Ok(first * 10 + last)
+}
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String> {
+    let output = parse::lines(input, |line| {
+        calibration_value(line, line.chars().filter_map(|char| char.to_digit(10)))
+    })?
+    .into_iter()
+    .sum::<u32>();
+    Ok(output.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+        assert_eq!("142", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_without_digits() {
+        let err = process("1abc2\npqrstu").expect_err("no digit on line 2");
+        let err = err.downcast::<ParseError>().expect("a parse error");
+        assert_eq!(err.message(), "no digit in this line");
+        assert_eq!(err.line_col(), (2, 1));
+    }
+}
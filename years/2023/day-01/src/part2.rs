@@ -0,0 +1,53 @@
+use common::parse;
+
+use crate::part1::calibration_value;
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String> {
+    // Word mappings with tuples (original word, replacement)
+    let word_mapping = [
+        ("one", "one1one"),
+        ("two", "two2two"),
+        ("three", "three3three"),
+        ("four", "four4four"),
+        ("five", "five5five"),
+        ("six", "six6six"),
+        ("seven", "seven7seven"),
+        ("eight", "eight8eight"),
+        ("nine", "nine9nine"),
+        ("zero", "zero0zero"),
+    ];
+
+    let output = parse::lines(input, |line| {
+        let modified_line = word_mapping
+            .iter()
+            .fold(line.to_string(), |acc, (word, replacement)| {
+                acc.replace(word, replacement)
+            });
+        calibration_value(
+            line,
+            modified_line.chars().filter_map(|char| char.to_digit(10)),
+        )
+    })?
+    .into_iter()
+    .sum::<u32>();
+    Ok(output.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+        assert_eq!("281", process(input)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use common::input::Puzzle;
+use divan::Bencher;
+use y2023_day_02::{part1, part2};
+
+fn main() {
+    divan::main();
+}
+
+#[divan::bench]
+fn part1_bench(bencher: Bencher) {
+    let input = Puzzle::new(2023, 2, 1).load().expect("puzzle input");
+    bencher
+        .with_inputs(|| input.as_str())
+        .bench_values(part1::process);
+}
+
+#[divan::bench]
+fn part2_bench(bencher: Bencher) {
+    let input = Puzzle::new(2023, 2, 2).load().expect("puzzle input");
+    bencher
+        .with_inputs(|| input.as_str())
+        .bench_values(part2::process);
+}
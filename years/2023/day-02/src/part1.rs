@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use common::parse::{self, ParseError};
+
+/// The cube colours the bag can hold.
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+#[derive(Debug)]
+pub struct Cube<'a> {
+    pub color: &'a str,
+    pub amount: u32,
+}
+
+#[derive(Debug)]
+pub struct Game<'a> {
+    pub id: u32,
+    pub rounds: Vec<Vec<Cube<'a>>>,
+}
+
+impl Game<'_> {
+    fn possible_set(&self, threshold: &HashMap<&str, u32>) -> Option<u32> {
+        self.rounds
+            .iter()
+            .all(|round| {
+                round.iter().all(|set| {
+                    threshold
+                        .get(set.color)
+                        .is_some_and(|&limit| set.amount <= limit)
+                })
+            })
+            .then_some(self.id)
+    }
+}
+
+// 3 blue
+fn cube(input: &str) -> Result<Cube<'_>, ParseError> {
+    let (amount, color) = parse::pair(input, ' ', parse::int, |color| {
+        if COLORS.contains(&color) {
+            Ok(color)
+        } else {
+            Err(
+                ParseError::at(color, color, format!("unknown colour `{color}`"))
+                    .with_label("expected red, green or blue"),
+            )
+        }
+    })?;
+    Ok(Cube { color, amount })
+}
+
+// 3 blue, 4 red
+fn round(input: &str) -> Result<Vec<Cube<'_>>, ParseError> {
+    parse::comma_list(input, cube)
+}
+
+// Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+fn game(line: &str) -> Result<Game<'_>, ParseError> {
+    let (id, rounds) = parse::key_value(
+        line,
+        |key| {
+            let id = key
+                .strip_prefix("Game ")
+                .ok_or_else(|| ParseError::at(key, key, "expected `Game <id>`"))?;
+            parse::int(id).map_err(|err| err.within(key, id))
+        },
+        |rounds| parse::separated(rounds, ';', round),
+    )?;
+    Ok(Game { id, rounds })
+}
+
+pub fn parse_game(input: &str) -> Result<Vec<Game<'_>>, ParseError> {
+    parse::lines(input, game)
+}
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String> {
+    let threshold = HashMap::from([("red", 12), ("green", 13), ("blue", 14)]);
+    let games = parse_game(input)?;
+    let result = games
+        .iter()
+        .filter_map(|game| game.possible_set(&threshold))
+        .sum::<u32>();
+    Ok(result.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+        assert_eq!("8", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors_point_into_the_input() {
+        let err = parse_game("Game 1: 3 blue\nGame x: 1 red").expect_err("bad id");
+        assert_eq!(err.message(), "invalid integer `x`");
+        assert_eq!(err.line_col(), (2, 6));
+
+        let err = parse_game("Game 1: 3 blue; 2 pink").expect_err("bad colour");
+        assert_eq!(err.message(), "unknown colour `pink`");
+        assert_eq!(err.span(), (18, 4).into());
+
+        let err = parse_game("Round 1: 3 blue").expect_err("bad header");
+        assert_eq!(err.message(), "expected `Game <id>`");
+    }
+}
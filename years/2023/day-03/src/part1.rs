@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use common::parse::{self, ParseError};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Value {
+    Symbol(char),
+    Empty,
+    Number(u32),
+}
+
+/// Every cell, keyed by `(y, x)`.
+pub type Schematic = BTreeMap<(i32, i32), Value>;
+
+/// Digits of one number in reading order, each with its `(x, y)` position.
+pub type Digits = Vec<((i32, i32), u32)>;
+
+/// The number spelled by a run of digits.
+pub fn number(digits: &Digits) -> u32 {
+    digits.iter().fold(0, |acc, (_, digit)| acc * 10 + digit)
+}
+
+/// Parses the schematic into its cells and the runs of digits.
+///
+/// # Errors
+///
+/// Fails when the rows differ in length.
+pub fn parse_schematic(input: &str) -> Result<(Schematic, Vec<Digits>), ParseError> {
+    let grid = parse::char_grid(
+        input,
+        |character| {
+            Some(match character {
+                '.' => Value::Empty,
+                c => c.to_digit(10).map_or(Value::Symbol(c), Value::Number),
+            })
+        },
+        "any character",
+    )?;
+
+    let mut map = BTreeMap::new();
+    let mut numbers: Vec<Digits> = vec![];
+    for (pos, value) in grid.iter() {
+        let (y, x) = (pos.row as i32, pos.col as i32);
+        if let Value::Number(num) = *value {
+            // A digit continues the last number when it sits right after it
+            // on the same row.
+            match numbers.last_mut() {
+                Some(last)
+                    if last
+                        .last()
+                        .is_some_and(|&((lx, ly), _)| lx + 1 == x && ly == y) =>
+                {
+                    last.push(((x, y), num));
+                }
+                _ => numbers.push(vec![((x, y), num)]),
+            }
+        }
+        map.insert((y, x), *value);
+    }
+
+    Ok((map, numbers))
+}
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String> {
+    let (map, numbers) = parse_schematic(input)?;
+
+    // map: entire grid
+    // numbers: sequential numbers
+    let mut total = 0;
+    for num_list in numbers {
+        // (x,y)
+        let positions = [
+            (1, 0),
+            (1, -1),
+            (0, -1),
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+        let num_positions: Vec<(i32, i32)> = num_list.iter().map(|((y, x), _)| (*x, *y)).collect();
+        let pos_to_check: Vec<(i32, i32)> = num_list
+            .iter()
+            .flat_map(|(pos, _)| {
+                positions.iter().map(|outer_pos| {
+                    // outer_pos.x + pos.x, .y + .y
+                    (outer_pos.0 + pos.1, outer_pos.1 + pos.0)
+                })
+            })
+            .filter(|num| !num_positions.contains(num))
+            .collect();
+
+        // dbg!(pos_to_check.len(), pos_to_check);
+        let is_part_number = pos_to_check.iter().any(|pos| {
+            let value = map.get(pos);
+            #[allow(clippy::match_like_matches_macro)]
+            if let Some(Value::Symbol(_)) = value {
+                true
+            } else {
+                false
+            }
+        });
+
+        if is_part_number {
+            total += number(&num_list);
+        }
+    }
+    Ok(total.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+        assert_eq!("4361", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_numbers_do_not_wrap_across_rows() -> miette::Result<()> {
+        let (_, numbers) = parse_schematic("..1\n2..")?;
+        assert_eq!(numbers.iter().map(number).collect::<Vec<_>>(), [1, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ragged_rows_are_reported() {
+        let err = parse_schematic("467..\n...*\n..35.").expect_err("short row");
+        assert_eq!(err.line_col(), (2, 1));
+    }
+}
@@ -0,0 +1,82 @@
+use itertools::Itertools;
+
+use crate::part1::{Value, number, parse_schematic};
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String> {
+    let (map, numbers) = parse_schematic(input)?;
+
+    // map: entire grid
+    // numbers: sequential numbers
+    let mut total = 0;
+    for symbol in map
+        .iter()
+        .filter(|(_, value)| matches!(value, Value::Symbol('*')))
+    {
+        // (x,y)
+        let positions = [
+            (1, 0),
+            (1, -1),
+            (0, -1),
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+        let pos_to_check: Vec<(i32, i32)> = positions
+            .iter()
+            .map(|outer_pos| {
+                // outer_pos.x + pos.x, .y + .y
+                (outer_pos.0 + symbol.0.1, outer_pos.1 + symbol.0.0)
+            })
+            .collect();
+
+        // dbg!(pos_to_check.len(), pos_to_check);
+        let mut indexes_of_numbers = vec![];
+
+        for pos in pos_to_check {
+            for (i, num_list) in numbers.iter().enumerate() {
+                if num_list
+                    .iter()
+                    .find(|(num_pos, _)| num_pos == &pos)
+                    .is_some()
+                {
+                    indexes_of_numbers.push(i);
+                }
+            }
+        }
+
+        let is_gear = indexes_of_numbers.iter().unique().count() == 2;
+
+        if is_gear {
+            total += indexes_of_numbers
+                .iter()
+                .unique()
+                .map(|&index| number(&numbers[index]))
+                .product::<u32>();
+        }
+    }
+    Ok(total.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+        assert_eq!("467835", process(input)?);
+        Ok(())
+    }
+}
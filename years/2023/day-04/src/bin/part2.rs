@@ -0,0 +1,11 @@
+use common::input::Puzzle;
+use miette::Context;
+use y2023_day_04::part2::process;
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    let file = Puzzle::new(2023, 4, 2).load_from_args()?;
+    let daily_result = process(&file).context("Process part2")?;
+    println!("{daily_result}");
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use common::parse::{self, ParseError};
+
+/// How many of the card's winning numbers appear among the numbers it has.
+///
+/// # Errors
+///
+/// Fails on a line that is not `Card <id>: <winning> | <have>`.
+pub fn matching_count(line: &str) -> Result<usize, ParseError> {
+    let (_, (winning, have)) = parse::key_value(
+        line,
+        |card| {
+            let id = card
+                .strip_prefix("Card")
+                .ok_or_else(|| ParseError::at(card, card, "expected `Card <id>`"))?;
+            parse::int::<u32>(id).map_err(|err| err.within(card, id))
+        },
+        |numbers| {
+            parse::pair(
+                numbers,
+                '|',
+                |winning| parse::whitespace_list(winning, parse::int::<u32>),
+                |have| parse::whitespace_list(have, parse::int::<u32>),
+            )
+        },
+    )?;
+
+    // Find the intersection length
+    Ok(winning.iter().filter(|&x| have.contains(x)).count())
+}
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String> {
+    let output = parse::lines(input, matching_count)?
+        .into_iter()
+        .map(|count| match count {
+            0 => 0,
+            intersection_length => 2u32.pow((intersection_length - 1) as u32),
+        })
+        .sum::<u32>();
+    Ok(output.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+        assert_eq!("13", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors_point_into_the_line() {
+        let err = matching_count("Card 1: 41 48 83").expect_err("no bar");
+        assert_eq!(err.message(), "expected two values separated by `|`");
+
+        let err = matching_count("Card 1: 41 4x | 83").expect_err("bad number");
+        assert_eq!(err.message(), "invalid integer `4x`");
+        assert_eq!(err.span(), (11, 2).into());
+    }
+}
@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+
+use common::parse;
+
+use crate::part1::matching_count;
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String> {
+    let mut cards: BTreeMap<i32, i32> = BTreeMap::new();
+    let counts = parse::lines(input, matching_count)?;
+    counts
+        .into_iter()
+        .enumerate()
+        .for_each(|(card_id, intersection_length)| {
+            cards.entry(card_id as i32).or_insert(1);
+
+            for i in card_id + 1..=card_id + intersection_length {
+                *cards.entry(i as i32).or_insert(1) +=
+                    cards.get(&(card_id as i32)).copied().unwrap_or(0);
+            }
+        });
+    Ok(cards.values().sum::<i32>().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+        assert_eq!("30", process(input)?);
+        Ok(())
+    }
+}
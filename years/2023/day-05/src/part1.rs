@@ -0,0 +1,140 @@
+use common::interval::{Interval, RangeMap};
+use common::parse::{self, ParseError};
+use miette::miette;
+
+/// Parses one "x-to-y map:" block into a translation stage.
+fn parse_map(block: &str) -> Result<RangeMap<u64>, ParseError> {
+    let (header, body) = block.split_once('\n').unwrap_or((block, ""));
+    if !header.trim_end().ends_with(" map:") {
+        return Err(
+            ParseError::at(block, header.trim(), "expected a map header")
+                .with_label("like `seed-to-soil map:`"),
+        );
+    }
+
+    let ranges = parse::lines(body, |line| {
+        let numbers = parse::whitespace_list(line, parse::int::<u64>)?;
+        let &[destination, source, length] = numbers.as_slice() else {
+            return Err(ParseError::at(line, line.trim(), "expected three numbers")
+                .with_label("destination start, source start and length"));
+        };
+        let end = source
+            .checked_add(length)
+            .ok_or_else(|| ParseError::at(line, line.trim(), "range runs past the largest id"))?;
+        Ok((Interval::exclusive(source, end), destination))
+    })
+    .map_err(|err| err.within(block, body))?;
+
+    let mut stage = RangeMap::new();
+    for (source, destination) in ranges {
+        if let Some(source) = source {
+            stage.insert(source, destination);
+        }
+    }
+    Ok(stage)
+}
+
+/// Splits the almanac into its seeds and its maps. `seeds` parses the
+/// numbers after `seeds:`, which the two parts read differently.
+///
+/// # Errors
+///
+/// Fails on a missing `seeds:` line, on whatever `seeds` rejects, and on a
+/// malformed map.
+pub fn parse_almanac<'a, T>(
+    input: &'a str,
+    seeds: impl FnOnce(&'a str) -> Result<T, ParseError>,
+) -> Result<(T, Vec<RangeMap<u64>>), ParseError> {
+    let mut blocks = parse::blocks(input);
+    let seed_block = blocks.next().unwrap_or_default();
+    let (_, seeds) = parse::key_value(
+        seed_block,
+        |key| match key {
+            "seeds" => Ok(()),
+            _ => Err(ParseError::at(key, key, "expected `seeds`")),
+        },
+        seeds,
+    )
+    .map_err(|err| err.within(input, seed_block))?;
+
+    let maps = blocks
+        .map(|block| parse_map(block).map_err(|err| err.within(input, block)))
+        .collect::<Result<_, _>>()?;
+    Ok((seeds, maps))
+}
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String> {
+    let (seeds, maps) = parse_almanac(input, |seeds| {
+        parse::whitespace_list(seeds, parse::int::<u64>)
+    })?;
+    let locations = maps.iter().fold(seeds, |seeds, stage| {
+        seeds
+            .into_iter()
+            .map(|seed| stage.map_value(seed))
+            .collect()
+    });
+    let lowest = locations.iter().min().ok_or_else(|| miette!("no seeds"))?;
+    Ok(lowest.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+        assert_eq!("35", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors_point_into_the_almanac() {
+        let seeds = |s| parse::whitespace_list(s, parse::int::<u64>);
+
+        let err = parse_almanac("seeds: 79 x4", seeds).expect_err("bad seed");
+        assert_eq!(err.span(), (10, 2).into());
+
+        let err =
+            parse_almanac("seeds: 79\n\nseed-to-soil map:\n50 98", seeds).expect_err("two numbers");
+        assert_eq!(err.message(), "expected three numbers");
+        assert_eq!(err.line_col(), (4, 1));
+
+        let err = parse_almanac("seeds: 79\n\n50 98 2", seeds).expect_err("no header");
+        assert_eq!(err.message(), "expected a map header");
+        assert_eq!(err.line_col(), (3, 1));
+    }
+}
@@ -0,0 +1,91 @@
+use common::interval::{Interval, IntervalSet, RangeMap};
+use common::parse::{self, ParseError};
+use miette::miette;
+
+use crate::part1::parse_almanac;
+
+/// Reads the seeds as `start length` pairs.
+fn seed_ranges(seeds: &str) -> Result<IntervalSet<u64>, ParseError> {
+    let tokens: Vec<&str> = seeds.split_whitespace().collect();
+    let mut ranges = IntervalSet::new();
+    for pair in tokens.chunks(2) {
+        let &[start, length] = pair else {
+            return Err(ParseError::at(seeds, pair[0], "seed range has no length")
+                .with_label("seeds come in `start length` pairs"));
+        };
+        let first: u64 = parse::int(start).map_err(|err| err.within(seeds, start))?;
+        let count: u64 = parse::int(length).map_err(|err| err.within(seeds, length))?;
+        let end = first
+            .checked_add(count)
+            .ok_or_else(|| ParseError::at(seeds, length, "range runs past the largest id"))?;
+        if let Some(range) = Interval::exclusive(first, end) {
+            ranges.insert(range);
+        }
+    }
+    Ok(ranges)
+}
+
+fn parse(input: &str) -> Result<(IntervalSet<u64>, Vec<RangeMap<u64>>), ParseError> {
+    parse_almanac(input, seed_ranges)
+}
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String> {
+    let (seeds, maps) = parse(input)?;
+    let locations = maps
+        .iter()
+        .fold(seeds, |seeds, stage| stage.map_set(&seeds));
+    let lowest = locations.bounds().ok_or_else(|| miette!("no seeds"))?.start;
+    Ok(lowest.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+        assert_eq!("46", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unpaired_seed() {
+        let err = seed_ranges(" 79 14 55").expect_err("odd seed count");
+        assert_eq!(err.message(), "seed range has no length");
+        assert_eq!(err.span(), (7, 2).into());
+    }
+}
@@ -1,21 +1,21 @@
-use std::str::FromStr;
+use common::parse::{self, ParseError};
 
-fn parse_line_to_pair(line: &str) -> (u32, u32) {
-    let parts = line
-        .split_whitespace()
-        .map(str::trim)
-        .map(|num| u32::from_str(num).expect("Failed to parse number"))
-        .collect::<Vec<u32>>();
-    (parts[0], parts[1])
+fn parse_line_to_pair(line: &str) -> Result<(u32, u32), ParseError> {
+    parse::pair(line, ' ', parse::int, parse::int)
 }
 
-pub fn split_into_lists(input: &str) -> (Vec<u32>, Vec<u32>) {
-    input.lines().map(parse_line_to_pair).unzip()
+/// Splits the input into its left and right columns.
+///
+/// # Errors
+///
+/// Fails on a line that is not two whitespace-separated numbers.
+pub fn split_into_lists(input: &str) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+    Ok(parse::lines(input, parse_line_to_pair)?.into_iter().unzip())
 }
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (mut left_list, mut right_list) = split_into_lists(input);
+    let (mut left_list, mut right_list) = split_into_lists(input)?;
     left_list.sort_unstable();
     right_list.sort_unstable();
 
@@ -41,7 +41,7 @@ mod tests {
     #[test]
     fn parse_one_digit_numbers() {
         let input = "3   4";
-        let (left_number, right_number) = parse_line_to_pair(input);
+        let (left_number, right_number) = parse_line_to_pair(input).expect("two numbers");
         assert_eq!(left_number, 3);
         assert_eq!(right_number, 4);
     }
@@ -49,18 +49,28 @@ mod tests {
     #[test]
     fn parse_multi_digit_numbers() {
         let input = "2468   44";
-        let (left_number, right_number) = parse_line_to_pair(input);
+        let (left_number, right_number) = parse_line_to_pair(input).expect("two numbers");
         assert_eq!(left_number, 2468);
         assert_eq!(right_number, 44);
     }
 
     #[test]
     fn parse_lines_as_two_lists_of_u32() {
-        let (left_list, right_list) = split_into_lists(EXAMPLE);
+        let (left_list, right_list) = split_into_lists(EXAMPLE).expect("example parses");
         assert_eq!(left_list, vec![3, 4, 2, 1, 3, 3]);
         assert_eq!(right_list, vec![4, 3, 5, 3, 9, 3]);
     }
 
+    #[test]
+    fn parse_errors_point_into_the_input() {
+        let err = split_into_lists("3   4\n4   x3").expect_err("bad number");
+        assert_eq!(err.message(), "invalid integer `x3`");
+        assert_eq!(err.line_col(), (2, 5));
+
+        let err = split_into_lists("3   4\n43").expect_err("one column");
+        assert_eq!(err.line_col(), (2, 1));
+    }
+
     #[test]
     fn test_process() -> miette::Result<()> {
         assert_eq!("11", process(EXAMPLE)?);
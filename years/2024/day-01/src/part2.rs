@@ -2,7 +2,7 @@ use crate::part1::split_into_lists;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (left_list, right_list) = split_into_lists(input);
+    let (left_list, right_list) = split_into_lists(input)?;
 
     let similarity: usize = left_list
         .iter()
@@ -4,16 +4,22 @@ use std::cmp::Ordering;
 pub type Report = Vec<u32>;
 
 pub mod parser {
+    use common::parse::{self, ParseError};
+
     use super::Report;
 
-    pub fn parse_line_to_report(line: &str) -> Report {
-        line.split_whitespace()
-            .map(|num| num.parse::<u32>().expect("Each value should parse to u32"))
-            .collect()
+    /// # Errors
+    ///
+    /// Fails on a level that is not a number.
+    pub fn parse_line_to_report(line: &str) -> Result<Report, ParseError> {
+        parse::whitespace_list(line, parse::int)
     }
 
-    pub fn parse_multiline_input(input: &str) -> Vec<Report> {
-        input.lines().map(parse_line_to_report).collect()
+    /// # Errors
+    ///
+    /// Fails on the first report that does not parse.
+    pub fn parse_multiline_input(input: &str) -> Result<Vec<Report>, ParseError> {
+        parse::lines(input, parse_line_to_report)
     }
 }
 
@@ -50,13 +56,14 @@ fn validate_differ_range(report: &Report) -> bool {
         .all(|(a, b)| (1..=3).contains(&a.abs_diff(*b)))
 }
 
+pub fn is_safe(report: &Report) -> bool {
+    validate_differ_range(report) && validate_trending(report)
+}
+
 #[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    let reports = parser::parse_multiline_input(_input);
-    let safe_count = reports
-        .iter()
-        .filter(|report| validate_differ_range(report) && validate_trending(report))
-        .count();
+pub fn process(input: &str) -> miette::Result<String> {
+    let reports = parser::parse_multiline_input(input)?;
+    let safe_count = reports.iter().filter(|report| is_safe(report)).count();
     Ok(safe_count.to_string())
 }
 
@@ -76,13 +83,13 @@ mod tests {
     #[test]
     fn can_parse_line_into_report() {
         let input = "7 6 4 2 1";
-        let report = parse_line_to_report(input);
+        let report = parse_line_to_report(input).expect("valid report");
         assert_eq!(report, vec![7, 6, 4, 2, 1]);
     }
 
     #[test]
     fn can_parse_multiline_input() {
-        let reports = parse_multiline_input(EXAMPLE);
+        let reports = parse_multiline_input(EXAMPLE).expect("example parses");
         assert_eq!(
             reports,
             vec![
@@ -96,11 +103,18 @@ mod tests {
         )
     }
 
+    #[test]
+    fn parse_error_points_at_the_bad_level() {
+        let err = parse_multiline_input("7 6 4\n1 2 x 8").expect_err("bad level");
+        assert_eq!(err.message(), "invalid integer `x`");
+        assert_eq!(err.line_col(), (2, 5));
+    }
+
     #[test]
     fn should_be_monotonic() {
         let is_monotonic = EXAMPLE
             .lines()
-            .map(parse_line_to_report)
+            .map(|line| parse_line_to_report(line).expect("valid report"))
             .map(|line| validate_trending(&line))
             .collect::<Vec<bool>>();
         assert_eq!(is_monotonic, vec![true, true, true, false, false, true])
@@ -110,7 +124,7 @@ mod tests {
     fn should_differ_in_range() {
         let is_within_range = EXAMPLE
             .lines()
-            .map(parse_line_to_report)
+            .map(|line| parse_line_to_report(line).expect("valid report"))
             .map(|line| validate_differ_range(&line))
             .collect::<Vec<bool>>();
         assert_eq!(is_within_range, vec![true, false, false, true, false, true])
@@ -1,70 +1,30 @@
-use itertools::Itertools;
-use std::{cmp::Ordering, ops::RangeBounds};
-
-use crate::part1::{parser::*, Report};
-
-#[derive(Debug)]
-enum LevelTrending {
-    Increasing,
-    Decreasing,
-}
-
-fn validate_trending(report: &Report) -> bool {
-    if report.len() < 2 {
-        return false;
-    }
-
-    let initial_trending = match report[0].cmp(&report[1]) {
-        Ordering::Greater => LevelTrending::Decreasing,
-        Ordering::Less => LevelTrending::Increasing,
-        Ordering::Equal => return false,
-    };
-
-    let mut violations = 0;
-    for (a, b) in report.iter().tuple_windows() {
-        let is_violation = match initial_trending {
-            LevelTrending::Increasing => a > b,
-            LevelTrending::Decreasing => a < b,
-        };
-
-        if is_violation {
-            violations += 1;
-            if violations > 1 {
-                return false;
-            }
-        }
-    }
-    true
-}
-
-fn validate_differ_range(report: &Report) -> bool {
-    let mut violations = 0;
-
-    for (a, b) in report.iter().tuple_windows() {
-        let diff = a.abs_diff(*b);
-        println!("diff: {diff}");
-        if !(1..=3).contains(&diff) {
-            violations += 1;
-            println!("violate");
-            if violations > 1 {
-                return false;
-            }
-        }
-    }
-    true
+use tracing::debug;
+
+use crate::part1::{Report, is_safe, parser::parse_multiline_input};
+
+/// The Problem Dampener tolerates a single bad level: a report counts as safe
+/// if it already is, or becomes safe once any one level is removed.
+fn is_safe_with_dampener(report: &Report) -> bool {
+    is_safe(report)
+        || (0..report.len()).any(|skip| {
+            let mut dampened = report.clone();
+            dampened.remove(skip);
+            is_safe(&dampened)
+        })
 }
 
 #[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    let reports = parse_multiline_input(_input);
-    let is_monotonic = reports.iter().map(validate_trending);
-    let is_within_range = reports.iter().map(validate_differ_range);
-    println!("{:?}", is_monotonic.clone().collect::<Vec<_>>());
-    println!("{:?}", is_within_range.clone().collect::<Vec<_>>());
-    let safe_count = is_monotonic
-        .zip(is_within_range)
-        .filter(|(a, b)| *a && *b)
+pub fn process(input: &str) -> miette::Result<String> {
+    let reports = parse_multiline_input(input)?;
+    let safe_count = reports
+        .iter()
+        .filter(|report| is_safe_with_dampener(report))
         .count();
+    debug!(
+        safe_count,
+        total = reports.len(),
+        "Dampened reports checked"
+    );
     Ok(safe_count.to_string())
 }
 
@@ -80,6 +40,13 @@ mod tests {
 1 3 6 7 9
 ";
 
+    #[test]
+    fn dampener_removes_a_single_bad_level() {
+        assert!(is_safe_with_dampener(&vec![1, 3, 2, 4, 5]));
+        assert!(is_safe_with_dampener(&vec![8, 6, 4, 4, 1]));
+        assert!(!is_safe_with_dampener(&vec![1, 2, 7, 8, 9]));
+    }
+
     #[test]
     fn test_process() -> miette::Result<()> {
         assert_eq!("4", process(EXAMPLE)?);
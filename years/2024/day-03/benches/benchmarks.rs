@@ -0,0 +1,15 @@
+use common::input::Puzzle;
+use divan::Bencher;
+use y2024_day_03::part1;
+
+fn main() {
+    divan::main();
+}
+
+#[divan::bench]
+fn part1_bench(bencher: Bencher) {
+    let input = Puzzle::new(2024, 3, 1).load().expect("puzzle input");
+    bencher
+        .with_inputs(|| input.as_str())
+        .bench_values(part1::process);
+}
@@ -1,16 +1,8 @@
-use miette::miette;
-use nom::{
-    bytes::complete::tag,
-    character::complete::{self, anychar},
-    multi::{many1, many_till},
-    sequence::{delimited, separated_pair},
-    IResult, Parser,
-};
+use common::parse::{self, Delim, ParseError};
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, instructions) = parse(input).map_err(|e| miette!("parse failed {e}"))?;
-    let result: u32 = instructions
+    let result: u32 = parse(input)
         .iter()
         .map(|instruction| match instruction {
             Instruction::Mul(a, b) => a * b,
@@ -24,19 +16,42 @@ enum Instruction {
     Mul(u32, u32),
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Instruction>> {
-    many1(many_till(anychar, parse_mul_instruction).map(|(_, instruction)| instruction))(input)
+/// Every well-formed instruction in the corrupted memory. A `mul(` that
+/// does not close into a valid instruction is noise and is skipped.
+fn parse(input: &str) -> Vec<Instruction> {
+    input
+        .match_indices("mul(")
+        .filter_map(|(start, _)| {
+            let end = start + input[start..].find(')')? + 1;
+            parse_mul_instruction(&input[start..end]).ok()
+        })
+        .collect()
 }
 
-fn parse_mul_instruction(input: &str) -> IResult<&str, Instruction> {
-    let (pair, _) = tag("mul")(input)?;
-    let (remaining, (multiplier, multiplicand)) = delimited(
-        tag("("),
-        separated_pair(complete::u32, tag(","), complete::u32),
-        tag(")"),
-    )(pair)?;
+/// Parses exactly `mul(X,Y)`, where both operands are plain digits.
+fn parse_mul_instruction(s: &str) -> Result<Instruction, ParseError> {
+    if let Some((at, space)) = s.char_indices().find(|(_, c)| c.is_whitespace()) {
+        return Err(ParseError::at(
+            s,
+            &s[at..at + space.len_utf8()],
+            "unexpected whitespace",
+        ));
+    }
+    let args = s
+        .strip_prefix("mul")
+        .ok_or_else(|| ParseError::at(s, s, "expected `mul`"))?;
+    let (multiplier, multiplicand) = parse::delimited(args, Delim::Paren, |pair| {
+        parse::pair(pair, ',', operand, operand)
+    })
+    .map_err(|err| err.within(s, args))?;
+    Ok(Instruction::Mul(multiplier, multiplicand))
+}
 
-    Ok((remaining, Instruction::Mul(multiplier, multiplicand)))
+fn operand(digits: &str) -> Result<u32, ParseError> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseError::at(digits, digits, "expected digits"));
+    }
+    parse::int(digits)
 }
 
 #[cfg(test)]
@@ -48,15 +63,30 @@ mod tests {
     #[test]
     fn can_parse_correct_multiply_instruction() {
         let input = "mul(123,4)";
-        let (_, parsed) = parse_mul_instruction(input).expect("should parse ");
+        let parsed = parse_mul_instruction(input).expect("should parse");
         assert_eq!(parsed, Instruction::Mul(123, 4));
     }
 
     #[test]
     fn should_raise_error_when_contain_space() {
         let input = "mul(123, 4)";
-        let parsing_error = parse_mul_instruction(input);
-        assert!(parsing_error.is_err());
+        let parsing_error = parse_mul_instruction(input).expect_err("space");
+        assert_eq!(parsing_error.span(), (8, 1).into());
+    }
+
+    #[test]
+    fn skips_malformed_instructions() {
+        assert_eq!(
+            parse(EXAMPLE),
+            vec![
+                Instruction::Mul(2, 4),
+                Instruction::Mul(5, 5),
+                Instruction::Mul(11, 8),
+                Instruction::Mul(8, 5),
+            ]
+        );
+        assert!(parse_mul_instruction("mul(+1,2)").is_err());
+        assert!(parse_mul_instruction("mul(1,2").is_err());
     }
 
     #[test]
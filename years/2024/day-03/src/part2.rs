@@ -7,11 +7,13 @@ pub fn process(_input: &str) -> miette::Result<String> {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
     #[test]
+    #[ignore = "part2 is not solved yet"]
     fn test_process() -> miette::Result<()> {
-        todo!("build test");
-        let input = "";
-        assert_eq!("", process(input)?);
+        assert_eq!("48", process(EXAMPLE)?);
         Ok(())
     }
 }
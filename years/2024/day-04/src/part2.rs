@@ -7,11 +7,22 @@ pub fn process(_input: &str) -> miette::Result<String> {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX
+";
+
     #[test]
+    #[ignore = "part2 is not solved yet"]
     fn test_process() -> miette::Result<()> {
-        todo!("build test");
-        let input = "";
-        assert_eq!("", process(input)?);
+        assert_eq!("9", process(EXAMPLE)?);
         Ok(())
     }
 }
@@ -1,12 +1,11 @@
-use day_05::part2::process;
+use common::input::Puzzle;
 use miette::Context;
+use y2024_day_05::part2::process;
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
-    tracing_subscriber::fmt::init();
-
-    let file = include_str!("../../input2.txt");
-    let daily_result = process(file).context("Process part2")?;
+    let file = Puzzle::new(2024, 5, 2).load_from_args()?;
+    let daily_result = process(&file).context("Process part2")?;
     println!("{daily_result}");
     Ok(())
 }
@@ -1,21 +1,93 @@
-fn parse_rule(input: &str) {
-    let a = input
-        .lines()
-        .filter(|line| line.contains('|'))
-        .collect::<Vec<_>>();
-    println!("{:?}", a)
+use std::collections::{HashMap, HashSet};
+
+use common::parse::{self, ParseError};
+use tracing::{info, instrument};
+
+/// The page-ordering rules and the updates to check against them.
+#[derive(Debug)]
+pub struct Manual {
+    /// Pages that must come after each page, when both are in an update.
+    pub rules: HashMap<u32, Vec<u32>>,
+    pub updates: Vec<Vec<u32>>,
+}
+
+impl Manual {
+    #[instrument(skip(input))]
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        let mut blocks = parse::blocks(input);
+        let rule_block = blocks.next().unwrap_or_default();
+        let update_block = blocks.next().unwrap_or_default();
+        if let Some(extra) = blocks.next() {
+            return Err(ParseError::at(input, extra, "unexpected block")
+                .with_help("the manual is a block of `X|Y` rules, then a block of updates"));
+        }
+
+        let mut rules: HashMap<u32, Vec<u32>> = HashMap::new();
+        let pairs = parse::lines(rule_block, |line| {
+            parse::pair(line, '|', parse::int, parse::int)
+        })
+        .map_err(|err| err.within(input, rule_block))?;
+        for (before, after) in pairs {
+            rules.entry(before).or_default().push(after);
+        }
+
+        let updates = parse::lines(update_block, |line| parse::comma_list(line, parse::int))
+            .map_err(|err| err.within(input, update_block))?;
+
+        info!(
+            rule_count = rules.values().map(Vec::len).sum::<usize>(),
+            update_count = updates.len(),
+            "Manual parsed"
+        );
+        Ok(Self { rules, updates })
+    }
+
+    /// The rules between pages of `update`, as each page's successors.
+    pub fn successors<'a>(&'a self, update: &'a [u32]) -> impl Fn(&u32) -> Vec<u32> + 'a {
+        let pages: HashSet<u32> = update.iter().copied().collect();
+        move |page| {
+            self.rules
+                .get(page)
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|after| pages.contains(after))
+                .collect()
+        }
+    }
+
+    /// Whether no rule puts a later page of `update` before an earlier one.
+    pub fn is_ordered(&self, update: &[u32]) -> bool {
+        let successors = self.successors(update);
+        update.iter().enumerate().all(|(i, page)| {
+            successors(page)
+                .iter()
+                .all(|after| !update[..i].contains(after))
+        })
+    }
 }
 
-#[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    todo!("part1");
+pub fn middle_page(update: &[u32]) -> u32 {
+    update.get(update.len() / 2).copied().unwrap_or_default()
+}
+
+#[instrument(skip(input))]
+pub fn process(input: &str) -> miette::Result<String> {
+    let manual = Manual::new(input)?;
+    let result: u32 = manual
+        .updates
+        .iter()
+        .filter(|update| manual.is_ordered(update))
+        .map(|update| middle_page(update))
+        .sum();
+    Ok(result.to_string())
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "47|53
+    pub(crate) const EXAMPLE: &str = "47|53
 97|13
 97|61
 97|47
@@ -46,10 +118,36 @@ mod tests {
 ";
 
     #[test]
-    fn can_parse_rule() {
-        let rule = "97|53";
-        parse_rule(EXAMPLE);
-        assert!(false)
+    fn can_parse_rule() -> miette::Result<()> {
+        let manual = Manual::new(EXAMPLE)?;
+        assert_eq!(manual.rules[&97], [13, 61, 47, 29, 53, 75]);
+        assert_eq!(manual.rules.values().map(Vec::len).sum::<usize>(), 21);
+        assert_eq!(manual.updates.len(), 6);
+        assert_eq!(manual.updates[2], [75, 29, 13]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors_point_into_the_input() {
+        let err = Manual::new("47|53\n97-13\n\n75,47\n").expect_err("bad rule");
+        assert_eq!(err.message(), "expected two values separated by `|`");
+        assert_eq!(err.line_col(), (2, 1));
+
+        let err = Manual::new("47|53\n\n75,x\n").expect_err("bad page");
+        assert_eq!(err.message(), "invalid integer `x`");
+        assert_eq!(err.line_col(), (3, 4));
+    }
+
+    #[test]
+    fn test_is_ordered() -> miette::Result<()> {
+        let manual = Manual::new(EXAMPLE)?;
+        let ordered: Vec<bool> = manual
+            .updates
+            .iter()
+            .map(|update| manual.is_ordered(update))
+            .collect();
+        assert_eq!(ordered, [true, true, true, false, false, false]);
+        Ok(())
     }
 
     #[test]
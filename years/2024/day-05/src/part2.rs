@@ -1,17 +1,55 @@
-#[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    todo!("part2");
+use common::graph::search::toposort;
+use miette::IntoDiagnostic;
+use tracing::instrument;
+
+use crate::part1::{Manual, middle_page};
+
+/// Puts the pages of `update` in the order the rules require.
+fn reorder(manual: &Manual, update: &[u32]) -> miette::Result<Vec<u32>> {
+    toposort(update.iter().copied(), manual.successors(update)).into_diagnostic()
+}
+
+#[instrument(skip(input))]
+pub fn process(input: &str) -> miette::Result<String> {
+    let manual = Manual::new(input)?;
+    let mut result = 0;
+    for update in manual.updates.iter().filter(|u| !manual.is_ordered(u)) {
+        result += middle_page(&reorder(&manual, update)?);
+    }
+    Ok(result.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::part1::tests::EXAMPLE;
+
+    #[test]
+    fn test_reorder() -> miette::Result<()> {
+        let manual = Manual::new(EXAMPLE)?;
+        assert_eq!(
+            reorder(&manual, &[75, 97, 47, 61, 53])?,
+            [97, 75, 47, 61, 53]
+        );
+        assert_eq!(reorder(&manual, &[61, 13, 29])?, [61, 29, 13]);
+        assert_eq!(
+            reorder(&manual, &[97, 13, 75, 29, 47])?,
+            [97, 75, 47, 29, 13]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_reorder_reports_contradicting_rules() -> miette::Result<()> {
+        let manual = Manual::new("1|2\n2|3\n3|1\n\n1,2,3\n")?;
+        let err = reorder(&manual, &[1, 2, 3]).expect_err("rules form a loop");
+        assert_eq!(err.to_string(), "the graph has a cycle through 3 nodes");
+        Ok(())
+    }
 
     #[test]
     fn test_process() -> miette::Result<()> {
-        todo!("build test");
-        let input = "";
-        assert_eq!("", process(input)?);
+        assert_eq!("123", process(EXAMPLE)?);
         Ok(())
     }
 }
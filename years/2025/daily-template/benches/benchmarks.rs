@@ -1,4 +1,5 @@
 use {{crate_name}}::{part1, part2};
+use common::input::Puzzle;
 use divan::Bencher;
 
 fn main() {
@@ -7,14 +8,16 @@ fn main() {
 
 #[divan::bench]
 fn part1_bench(bencher: Bencher) {
+    let input = Puzzle::new(2025, {{ project-name | remove: "day-" | plus: 0 }}, 1).load().expect("puzzle input");
     bencher
-        .with_inputs(|| include_str!("../input1.txt"))
-        .bench_values(|s| part1::process(s));
+        .with_inputs(|| input.as_str())
+        .bench_values(part1::process);
 }
 
 #[divan::bench]
 fn part2_bench(bencher: Bencher) {
+    let input = Puzzle::new(2025, {{ project-name | remove: "day-" | plus: 0 }}, 2).load().expect("puzzle input");
     bencher
-        .with_inputs(|| include_str!("../input2.txt"))
-        .bench_values(|s| part2::process(s));
+        .with_inputs(|| input.as_str())
+        .bench_values(part2::process);
 }
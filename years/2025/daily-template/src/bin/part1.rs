@@ -1,10 +1,11 @@
+use common::input::Puzzle;
 use {{crate_name}}::part1::process;
 use miette::Context;
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
-    let file = include_str!("../../input1.txt");
-    let daily_result = process(file).context("Process part1")?;
+    let file = Puzzle::new(2025, {{ project-name | remove: "day-" | plus: 0 }}, 1).load_from_args()?;
+    let daily_result = process(&file).context("Process part1")?;
     println!("{daily_result}");
     Ok(())
 }
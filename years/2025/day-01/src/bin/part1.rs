@@ -1,9 +1,10 @@
+use common::input::Puzzle;
 use day_01::part1::process;
 use miette::Context;
 
 fn main() -> miette::Result<()> {
-    let file = include_str!("../../input1.txt");
-    let daily_result = process(file).context("Process part1")?;
+    let file = Puzzle::new(2025, 1, 1).load_from_args()?;
+    let daily_result = process(&file).context("Process part1")?;
     println!("{daily_result}");
     Ok(())
 }
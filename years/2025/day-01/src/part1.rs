@@ -1,23 +1,34 @@
+use common::parse::{self, ParseError};
+
 // 定義常數以避免 Magic Numbers
 pub const DIAL_SIZE: i32 = 100;
 pub const START_POSITION: i32 = 50;
 pub const TARGET_POSITION: i32 = 0;
 
-pub fn parse_line_to_rotation(line: &str) -> i32 {
+pub fn parse_line_to_rotation(line: &str) -> Result<i32, ParseError> {
     let mut chars = line.chars();
     let direction = chars.next();
+    let sign = match direction {
+        Some('R') => 1,
+        Some('L') => -1,
+        _ => {
+            let end = direction.map_or(0, char::len_utf8);
+            return Err(ParseError::at(line, &line[..end], "unknown direction")
+                .with_label("expected `L` or `R`"));
+        }
+    };
 
-    let value: i32 = chars.as_str().parse().expect("Should be a number");
+    let distance = chars.as_str();
+    let value: i32 = parse::int(distance).map_err(|err| err.within(line, distance))?;
+    Ok(sign * value)
+}
 
-    match direction {
-        Some('R') => value,
-        Some('L') => -value,
-        _ => panic!("Unknown direction"),
-    }
+pub fn parse_rotations(input: &str) -> Result<Vec<i32>, ParseError> {
+    parse::lines(input, parse_line_to_rotation)
 }
 
 pub fn process(input: &str) -> miette::Result<String> {
-    let final_state = input.lines().map(parse_line_to_rotation).fold(
+    let final_state = parse_rotations(input)?.into_iter().fold(
         (START_POSITION, 0),
         |(current_pos, count), rotation| {
             let new_pos = (current_pos + rotation).rem_euclid(DIAL_SIZE);
@@ -51,9 +62,21 @@ R14
 L82";
 
     #[test]
-    fn test_parse_one_line() {
-        assert_eq!(parse_line_to_rotation("R76"), 76);
-        assert_eq!(parse_line_to_rotation("L30"), -30);
+    fn test_parse_one_line() -> miette::Result<()> {
+        assert_eq!(parse_line_to_rotation("R76")?, 76);
+        assert_eq!(parse_line_to_rotation("L30")?, -30);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_token() {
+        let err = parse_rotations("L68\nU30\n").expect_err("unknown direction");
+        assert_eq!(err.message(), "unknown direction");
+        assert_eq!(err.line_col(), (2, 1));
+
+        let err = parse_rotations("L68\nR3x\n").expect_err("not a number");
+        assert_eq!(err.message(), "invalid integer `3x`");
+        assert_eq!(err.span(), (5, 2).into());
     }
 
     #[test]
@@ -1,7 +1,7 @@
-use crate::part1::{DIAL_SIZE, START_POSITION, TARGET_POSITION, parse_line_to_rotation};
+use crate::part1::{DIAL_SIZE, START_POSITION, TARGET_POSITION, parse_rotations};
 
 pub fn process(input: &str) -> miette::Result<String> {
-    let final_state = input.lines().map(parse_line_to_rotation).fold(
+    let final_state = parse_rotations(input)?.into_iter().fold(
         (START_POSITION, 0),
         |(current_pos, total_hits), rotation| {
             let distance = rotation.abs();
@@ -1,30 +1,15 @@
-#[derive(PartialEq, Debug)]
-pub struct IdRange {
-    pub start: u64,
-    pub end: u64,
-}
-
-impl IdRange {
-    pub fn contains(&self, id: u64) -> bool {
-        id >= self.start && id <= self.end
-    }
-}
-
-pub fn parse_range(input: &str) -> Vec<IdRange> {
-    input
-        .replace('\n', "")
-        .split(',')
-        .filter_map(|s| {
-            let parts: Vec<&str> = s.trim().split('-').collect();
-            if parts.len() == 2 {
-                let start = parts[0].parse::<u64>().ok()?;
-                let end = parts[1].parse::<u64>().ok()?;
-                Some(IdRange { start, end })
-            } else {
-                None
-            }
+use common::interval::{Interval, IntervalSet};
+use common::parse::{self, ParseError};
+
+/// Parses comma-separated `start-end` ranges, which may wrap across lines.
+pub fn parse_range(input: &str) -> Result<Vec<Interval<u64>>, ParseError> {
+    parse::comma_list(input, |range| {
+        let (start, end) = parse::pair(range, '-', parse::int, parse::int)?;
+        Interval::inclusive(start, end).ok_or_else(|| {
+            ParseError::at(range, range.trim(), "range ends before it starts")
+                .with_label(format!("{end} is less than {start}"))
         })
-        .collect()
+    })
 }
 
 fn generate_mirrored_id(seed: u64) -> Option<u64> {
@@ -34,13 +19,12 @@ fn generate_mirrored_id(seed: u64) -> Option<u64> {
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let ranges = parse_range(input);
+    let ranges: IntervalSet<u64> = parse_range(input)?.into_iter().collect();
 
-    if ranges.is_empty() {
+    let Some(bounds) = ranges.bounds() else {
         return Ok("0".to_string());
-    }
-
-    let max_bound = ranges.iter().map(|r| r.end).max().unwrap_or(0);
+    };
+    let max_bound = bounds.end;
     let mut total_sum: u64 = 0;
     let mut seed: u64 = 1;
 
@@ -51,7 +35,7 @@ pub fn process(input: &str) -> miette::Result<String> {
             break;
         }
 
-        if ranges.iter().any(|range| range.contains(candidate)) {
+        if ranges.contains(candidate) {
             total_sum += candidate;
         }
 
@@ -66,24 +50,34 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_single_range() {
+    fn test_parse_single_range() -> miette::Result<()> {
         let input = "11-22";
-        assert_eq!(vec![IdRange { start: 11, end: 22 }], parse_range(input));
+        assert_eq!(vec![Interval::new(11, 22)], parse_range(input)?);
+        Ok(())
     }
 
     #[test]
-    fn test_parse_multiple_range() {
+    fn test_parse_multiple_range() -> miette::Result<()> {
         let input = "11-22,95-115";
         assert_eq!(
-            vec![
-                IdRange { start: 11, end: 22 },
-                IdRange {
-                    start: 95,
-                    end: 115
-                }
-            ],
-            parse_range(input)
+            vec![Interval::new(11, 22), Interval::new(95, 115)],
+            parse_range(input)?
         );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_range("11-22,\n95+115").expect_err("missing dash");
+        assert_eq!(err.message(), "expected two values separated by `-`");
+        assert_eq!(err.line_col(), (2, 1));
+
+        let err = parse_range("11-22,95-1x5").expect_err("bad end");
+        assert_eq!(err.span(), (9, 3).into());
+
+        let err = parse_range("11-22,95-15").expect_err("inverted range");
+        assert_eq!(err.message(), "range ends before it starts");
+        assert_eq!(err.span(), (6, 5).into());
     }
 
     #[test]
@@ -127,12 +121,16 @@ mod tests {
     }
 
     #[test]
-    fn test_id_range_contains() {
-        let range = IdRange { start: 10, end: 20 };
-        assert!(range.contains(10));
-        assert!(range.contains(15));
-        assert!(range.contains(20));
-        assert!(!range.contains(9));
-        assert!(!range.contains(21));
+    fn test_id_ranges_contains() {
+        let ranges: IntervalSet<u64> = parse_range("10-20,15-25,40-41")
+            .expect("valid ranges")
+            .into_iter()
+            .collect();
+        assert!(ranges.contains(10));
+        assert!(ranges.contains(15));
+        assert!(ranges.contains(25));
+        assert!(ranges.contains(41));
+        assert!(!ranges.contains(9));
+        assert!(!ranges.contains(26));
     }
 }
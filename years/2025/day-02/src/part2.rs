@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+use common::interval::IntervalSet;
+
 use crate::part1::parse_range;
 
 fn generate_repeated_id(seed_str: &str, times: usize) -> Option<u64> {
@@ -9,12 +11,11 @@ fn generate_repeated_id(seed_str: &str, times: usize) -> Option<u64> {
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let ranges = parse_range(input);
-    if ranges.is_empty() {
+    let ranges: IntervalSet<u64> = parse_range(input)?.into_iter().collect();
+    let Some(bounds) = ranges.bounds() else {
         return Ok("0".to_string());
-    }
-
-    let max_bound = ranges.iter().map(|r| r.end).max().unwrap_or(0);
+    };
+    let max_bound = bounds.end;
     let mut unique_invalid_ids: HashSet<u64> = HashSet::new();
     let mut seed: u64 = 1;
 
@@ -35,7 +36,7 @@ pub fn process(input: &str) -> miette::Result<String> {
                 break;
             }
 
-            if ranges.iter().any(|range| range.contains(candidate)) {
+            if ranges.contains(candidate) {
                 unique_invalid_ids.insert(candidate);
             }
             k += 1;
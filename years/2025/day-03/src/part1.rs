@@ -1,5 +1,23 @@
+use common::parse::{self, ParseError};
 use tracing::{debug, info};
 
+/// Checks that a bank line holds only battery digits.
+pub fn parse_bank_line(line: &str) -> Result<&str, ParseError> {
+    let line = line.trim_end();
+    match line.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, c)) => Err(
+            ParseError::at(line, &line[i..i + c.len_utf8()], "invalid battery")
+                .with_label("expected a digit"),
+        ),
+        None => Ok(line),
+    }
+}
+
+/// Every bank in the input, validated.
+pub fn parse_banks(input: &str) -> Result<Vec<&str>, ParseError> {
+    parse::lines(input, parse_bank_line)
+}
+
 /// Calculates the maximum possible joltage for a single battery bank.
 ///
 /// Logic:
@@ -43,7 +61,7 @@ fn parse_bank(input: &str) -> u32 {
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let total_joltage: u32 = input.lines().map(parse_bank).sum();
+    let total_joltage: u32 = parse_banks(input)?.into_iter().map(parse_bank).sum();
     Ok(total_joltage.to_string())
 }
 
@@ -66,6 +84,13 @@ mod tests {
         assert_eq!(result, expected, "Failed for input: {}", input);
     }
 
+    #[test]
+    fn test_parse_banks_rejects_non_digits() {
+        let err = parse_banks("987\n81x1\n").expect_err("x is not a digit");
+        assert_eq!(err.message(), "invalid battery");
+        assert_eq!(err.line_col(), (2, 3));
+    }
+
     #[test]
     fn test_process() -> miette::Result<()> {
         let input = "987654321111111
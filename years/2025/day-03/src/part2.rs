@@ -1,5 +1,7 @@
 use tracing::debug;
 
+use crate::part1::parse_banks;
+
 fn parse_bank_twelve(input: &str) -> u64 {
     let bytes = input.as_bytes();
     let n = bytes.len();
@@ -37,7 +39,7 @@ fn parse_bank_twelve(input: &str) -> u64 {
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let total_joltage: u64 = input.lines().map(parse_bank_twelve).sum();
+    let total_joltage: u64 = parse_banks(input)?.into_iter().map(parse_bank_twelve).sum();
     Ok(total_joltage.to_string())
 }
 
@@ -1,105 +1,59 @@
-use std::cmp::max;
-
+use common::interval::{Interval, IntervalSet};
+use common::parse::{self, ParseError};
 use tracing::{info, instrument};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Range {
-    pub start: u64,
-    pub end: u64,
-}
-
-impl Range {
-    fn new(start: u64, end: u64) -> Self {
-        Range { start, end }
-    }
-
-    fn contains(&self, value: u64) -> bool {
-        value >= self.start && value <= self.end
-    }
-}
-
 pub struct InventorySystem {
-    pub merged_ranges: Vec<Range>,
+    pub fresh_ranges: IntervalSet<u64>,
     pub candidate_ids: Vec<u64>,
 }
 
 impl InventorySystem {
     #[instrument(skip(input))]
-    pub fn new(input: &str) -> Self {
-        let (range_block, id_block) = match input.split_once("\n\n") {
-            Some((r, i)) => (r, i),
-            None => (input, ""), // Handle edge case: only ranges, no IDs
-        };
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        let mut blocks = parse::blocks(input);
+        // Handle edge case: only ranges, no IDs
+        let range_block = blocks.next().unwrap_or_default();
+        let id_block = blocks.next().unwrap_or_default();
+        if let Some(extra) = blocks.next() {
+            return Err(ParseError::at(input, extra, "unexpected block")
+                .with_help("the database is a block of fresh ranges, then a block of IDs"));
+        }
 
-        let raw_ranges = Self::parse_ranges(range_block);
-        let merged_ranges = Self::merge_intervals(raw_ranges);
+        let fresh_ranges: IntervalSet<u64> = Self::parse_ranges(range_block)
+            .map_err(|err| err.within(input, range_block))?
+            .into_iter()
+            .collect();
 
-        let candidate_ids = Self::parse_ids(id_block);
+        let candidate_ids = Self::parse_ids(id_block).map_err(|err| err.within(input, id_block))?;
 
         info!(
-            range_count = merged_ranges.len(),
+            range_count = fresh_ranges.len(),
             id_count = candidate_ids.len(),
             "System initialized"
         );
 
-        Self {
-            merged_ranges,
+        Ok(Self {
+            fresh_ranges,
             candidate_ids,
-        }
+        })
     }
 
-    fn parse_ranges(input: &str) -> Vec<Range> {
-        input
-            .lines()
-            .filter_map(|line| {
-                let (start, end) = line.split_once('-')?;
-                Some(Range::new(start.parse().ok()?, end.parse().ok()?))
+    fn parse_ranges(input: &str) -> Result<Vec<Interval<u64>>, ParseError> {
+        parse::lines(input, |line| {
+            let (start, end) = parse::pair(line, '-', parse::int, parse::int)?;
+            Interval::inclusive(start, end).ok_or_else(|| {
+                ParseError::at(line, line.trim(), "range ends before it starts")
+                    .with_label(format!("{end} is less than {start}"))
             })
-            .collect()
+        })
     }
 
-    fn parse_ids(input: &str) -> Vec<u64> {
-        input
-            .lines()
-            .filter_map(|line| line.parse::<u64>().ok())
-            .collect()
+    fn parse_ids(input: &str) -> Result<Vec<u64>, ParseError> {
+        parse::lines(input, parse::int)
     }
 
-    fn merge_intervals(mut ranges: Vec<Range>) -> Vec<Range> {
-        if ranges.is_empty() {
-            return vec![];
-        }
-
-        ranges.sort_by_key(|r| r.start);
-
-        let mut merged = Vec::new();
-        let mut current = ranges[0];
-
-        for next in ranges.into_iter().skip(1) {
-            if next.start <= current.end {
-                current.end = max(current.end, next.end);
-            } else {
-                merged.push(current);
-                current = next;
-            }
-        }
-        merged.push(current);
-
-        merged
-    }
-
-    /// Logic: Binary search to check if an ID exists in the merged ranges.
     fn is_fresh(&self, id: u64) -> bool {
-        // partition_point returns the index of the first element NOT satisfying the predicate.
-        // We want ranges that start <= id.
-        let idx = self.merged_ranges.partition_point(|r| r.start <= id);
-
-        if idx == 0 {
-            return false;
-        }
-
-        // Check the candidate range (the one immediately before the partition point)
-        self.merged_ranges[idx - 1].contains(id)
+        self.fresh_ranges.contains(id)
     }
 
     fn count_fresh_ids(&self) -> usize {
@@ -112,7 +66,7 @@ impl InventorySystem {
 
 #[instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let system: InventorySystem = InventorySystem::new(input);
+    let system: InventorySystem = InventorySystem::new(input)?;
     let result = system.count_fresh_ids();
     Ok(result.to_string())
 }
@@ -134,58 +88,82 @@ mod tests {
         #[case] val: u64,
         #[case] expected: bool,
     ) {
-        let range = Range::new(start, end);
+        let range = Interval::new(start, end);
         assert_eq!(range.contains(val), expected)
     }
 
     #[test_log::test]
-    fn test_parse_range_logic() {
-        let input = "3-5\n10-14\ninvalid\n16-20";
-        let result = InventorySystem::parse_ranges(input);
+    fn test_parse_range_logic() -> miette::Result<()> {
+        let input = "3-5\n10-14\n16-20";
+        let result = InventorySystem::parse_ranges(input)?;
 
         assert_eq!(result.len(), 3);
-        assert_eq!(result[0], Range::new(3, 5));
-        assert_eq!(result[1], Range::new(10, 14));
-        assert_eq!(result[2], Range::new(16, 20));
+        assert_eq!(result[0], Interval::new(3, 5));
+        assert_eq!(result[1], Interval::new(10, 14));
+        assert_eq!(result[2], Interval::new(16, 20));
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_parse_range_rejects_bad_line() {
+        let input = "3-5\n10-14\ninvalid\n16-20";
+        let err = InventorySystem::parse_ranges(input).expect_err("invalid line");
+        assert_eq!(err.message(), "expected two values separated by `-`");
+        assert_eq!(err.line_col(), (3, 1));
+
+        let err = InventorySystem::parse_ranges("4-4\n10-2").expect_err("inverted range");
+        assert_eq!(err.message(), "range ends before it starts");
+        assert_eq!(err.line_col(), (2, 1));
     }
 
     #[test_log::test]
-    fn test_parse_ids_logic() {
-        let input = "1\n5\nnot_a_number\n11";
-        let result = InventorySystem::parse_ids(input);
+    fn test_parse_ids_logic() -> miette::Result<()> {
+        let input = "1\n5\n11";
+        let result = InventorySystem::parse_ids(input)?;
 
-        assert_eq!(result, vec![1, 5, 11])
+        assert_eq!(result, vec![1, 5, 11]);
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_bad_id_points_into_whole_input() {
+        let input = "3-5\n\n1\nnot_a_number\n11";
+        let err = InventorySystem::new(input).err().expect("bad id");
+        assert_eq!(err.message(), "invalid integer `not_a_number`");
+        assert_eq!(err.line_col(), (4, 1));
     }
 
     #[test_log::test]
     fn test_merge_intervals_complex() {
         let input = vec![
-            Range::new(1, 5),
-            Range::new(2, 6),
-            Range::new(8, 10),
-            Range::new(15, 20),
-            Range::new(16, 18),
+            Interval::new(1, 5),
+            Interval::new(2, 6),
+            Interval::new(8, 10),
+            Interval::new(15, 20),
+            Interval::new(16, 18),
         ];
 
-        let merged = InventorySystem::merge_intervals(input);
+        let merged: IntervalSet<u64> = input.into_iter().collect();
+        let merged = merged.as_slice();
 
         assert_eq!(merged.len(), 3);
-        assert_eq!(merged[0], Range::new(1, 6));
-        assert_eq!(merged[1], Range::new(8, 10));
-        assert_eq!(merged[2], Range::new(15, 20));
+        assert_eq!(merged[0], Interval::new(1, 6));
+        assert_eq!(merged[1], Interval::new(8, 10));
+        assert_eq!(merged[2], Interval::new(15, 20));
     }
 
     #[test_log::test]
     fn test_merge_intervals_unordered() {
         let input = vec![
-            Range::new(10, 15),
-            Range::new(1, 5), // Should be sorted to first
+            Interval::new(10, 15),
+            Interval::new(1, 5), // Should be sorted to first
         ];
-        let merged = InventorySystem::merge_intervals(input);
+        let merged: IntervalSet<u64> = input.into_iter().collect();
+        let merged = merged.as_slice();
 
         assert_eq!(merged.len(), 2);
-        assert_eq!(merged[0], Range::new(1, 5));
-        assert_eq!(merged[1], Range::new(10, 15));
+        assert_eq!(merged[0], Interval::new(1, 5));
+        assert_eq!(merged[1], Interval::new(10, 15));
     }
 
     #[test_log::test(rstest)]
@@ -198,7 +176,13 @@ mod tests {
     #[case(32, false)]
     fn test_is_fresh_logic(#[case] id: u64, #[case] expected: bool) {
         let system = InventorySystem {
-            merged_ranges: vec![Range::new(3, 5), Range::new(10, 14), Range::new(16, 20)],
+            fresh_ranges: [
+                Interval::new(3, 5),
+                Interval::new(10, 14),
+                Interval::new(16, 20),
+            ]
+            .into_iter()
+            .collect(),
             candidate_ids: Vec::new(),
         };
 
@@ -1,25 +1,11 @@
 use tracing::instrument;
 
-use crate::part1::{InventorySystem, Range};
-
-trait RangeCounter {
-    fn count_ids_in_range(&self) -> u64;
-}
-
-impl RangeCounter for Range {
-    fn count_ids_in_range(&self) -> u64 {
-        self.end - self.start + 1
-    }
-}
+use crate::part1::InventorySystem;
 
 #[instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let system = InventorySystem::new(input);
-    let merged_range = system.merged_ranges;
-    let total_count = merged_range
-        .iter()
-        .map(|&r| r.count_ids_in_range())
-        .sum::<u64>();
+    let system = InventorySystem::new(input)?;
+    let total_count = system.fresh_ranges.total_len();
     Ok(total_count.to_string())
 }
 
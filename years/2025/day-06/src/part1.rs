@@ -1,3 +1,7 @@
+use std::ops::Range;
+
+use common::grid::Grid;
+use common::parse::{self, ParseError};
 use tracing::{info, instrument};
 
 #[derive(Debug)]
@@ -24,98 +28,150 @@ impl Problem {
     }
 }
 
-pub struct WorksheetParser;
+/// A worksheet read into a character grid: rows of numbers above one row
+/// of operators, with problems separated by blank columns.
+///
+/// Every accepted character is ASCII, so grid columns are also byte
+/// offsets into the source lines, which is how errors point back into the
+/// input.
+pub struct Worksheet<'a> {
+    pub grid: Grid<char>,
+    input: &'a str,
+    lines: Vec<&'a str>,
+}
 
-impl WorksheetParser {
-    pub fn to_grid(input: &str) -> Vec<Vec<char>> {
-        let lines: Vec<&str> = input.lines().collect();
-        let max_width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+impl<'a> Worksheet<'a> {
+    /// Reads the grid and checks that digits only appear above the last row
+    /// and operators only on it.
+    pub fn parse(input: &'a str) -> Result<Self, ParseError> {
+        let sheet = input.trim_end_matches('\n');
+        let grid = parse::char_grid(
+            sheet,
+            |c| matches!(c, '0'..='9' | '+' | '*' | ' ').then_some(c),
+            "expected a digit, `+`, `*` or space",
+        )?;
+        let worksheet = Self {
+            grid,
+            input,
+            lines: sheet.lines().collect(),
+        };
+
+        if worksheet.grid.height() == 1 {
+            return Err(
+                ParseError::at(input, worksheet.lines[0], "missing number rows")
+                    .with_help("a worksheet has rows of numbers above a row of operators"),
+            );
+        }
+        for (pos, &c) in worksheet.grid.iter() {
+            let on_operator_row = pos.row == worksheet.operator_row();
+            let cell = worksheet.text(pos.row, pos.col..pos.col + 1);
+            if c.is_ascii_digit() && on_operator_row {
+                return Err(ParseError::at(input, cell, "digit in the operator row")
+                    .with_label("expected `+` or `*`"));
+            }
+            if matches!(c, '+' | '*') && !on_operator_row {
+                return Err(ParseError::at(input, cell, "operator above the last row")
+                    .with_label("expected a digit or space"));
+            }
+        }
+        Ok(worksheet)
+    }
 
-        lines
-            .iter()
-            .map(|line| {
-                let mut chars: Vec<char> = line.chars().collect();
-                chars.resize(max_width, ' ');
-                chars
-            })
-            .collect()
+    /// The index of the last row, which holds the operators.
+    pub fn operator_row(&self) -> usize {
+        self.grid.height().saturating_sub(1)
     }
 
-    pub fn is_column_empty(grid: &[Vec<char>], col_idx: usize) -> bool {
-        grid.iter()
-            .all(|row| row.get(col_idx).unwrap_or(&' ') == &' ')
+    /// The text the worksheet was parsed from, for error spans.
+    pub fn input(&self) -> &'a str {
+        self.input
     }
 
-    fn parse_slice(grid: &[Vec<char>], start_col: usize, end_col: usize) -> Option<Problem> {
-        let height = grid.len();
+    /// The source text of `cols` on `row`.
+    pub fn text(&self, row: usize, cols: Range<usize>) -> &'a str {
+        &self.lines[row][cols]
+    }
 
-        if height < 2 {
-            return None; // Need at least one number row and one operator row
-        }
+    pub fn is_column_empty(&self, col: usize) -> bool {
+        self.grid.column(col).all(|&c| c == ' ')
+    }
 
-        // Find Operator
-        let last_row = &grid[height - 1];
-        let operator = last_row[start_col..end_col]
-            .iter()
-            .find(|&&c| c != ' ')
-            .copied()?;
+    /// The column ranges of the problems, left to right.
+    pub fn problem_columns(&self) -> Vec<Range<usize>> {
+        let width = self.grid.width();
+        let mut problems = Vec::new();
+        let mut start_col = 0;
 
-        // Parse Numbers
-        let mut numbers = Vec::new();
-        for row in grid.iter().take(height - 1) {
-            let row_slice = &row[start_col..end_col];
-            let row_str: String = row_slice.iter().collect();
-            if let Ok(num) = row_str.trim().parse::<u64>() {
-                numbers.push(num);
+        for col in 0..=width {
+            // A blank column, or the right edge, closes the current problem.
+            if col == width || self.is_column_empty(col) {
+                if col > start_col {
+                    problems.push(start_col..col);
+                }
+                start_col = col + 1;
             }
         }
+        problems
+    }
 
-        if numbers.is_empty() {
-            return None;
+    /// The single operator under the problem in `cols`.
+    pub fn operator(&self, cols: Range<usize>) -> Result<char, ParseError> {
+        let text = self.text(self.operator_row(), cols);
+        let mut operators = text.char_indices().filter(|&(_, c)| c != ' ');
+        match (operators.next(), operators.next()) {
+            (Some((_, operator)), None) => Ok(operator),
+            (None, _) => Err(ParseError::at(self.input, text, "problem has no operator")
+                .with_label("expected `+` or `*` under the numbers")),
+            (Some(_), Some((i, _))) => Err(ParseError::at(
+                self.input,
+                &text[i..=i],
+                "problem has more than one operator",
+            )),
         }
-
-        Some(Problem { numbers, operator })
     }
 
-    fn parse_all(input: &str) -> Vec<Problem> {
-        let grid = Self::to_grid(input);
-        if grid.is_empty() {
-            return vec![];
+    /// Pairs the numbers read from `cols` with its operator, rejecting a
+    /// problem without numbers.
+    pub fn problem(
+        &self,
+        cols: Range<usize>,
+        numbers: Vec<u64>,
+        operator: char,
+    ) -> Result<Problem, ParseError> {
+        if numbers.is_empty() {
+            return Err(ParseError::at(
+                self.input,
+                self.text(self.operator_row(), cols),
+                "problem has no numbers",
+            ));
         }
+        Ok(Problem { numbers, operator })
+    }
 
-        let width = grid[0].len();
-        let mut problems = Vec::new();
-        let mut start_col = 0;
-
-        for col in 0..width {
-            // If we hit an empty column, it's a separator.
-            if Self::is_column_empty(&grid, col) {
-                // If we have accumulated width, parse the slice before this separator
-                if col > start_col
-                    && let Some(p) = Self::parse_slice(&grid, start_col, col)
-                {
-                    problems.push(p);
-                }
-
-                // The next problem starts after this empty column
-                start_col = col + 1;
+    /// Reads the problem in `cols` row by row.
+    fn parse_slice(&self, cols: Range<usize>) -> Result<Problem, ParseError> {
+        let operator = self.operator(cols.clone())?;
+        let mut numbers = Vec::new();
+        for row in 0..self.operator_row() {
+            let text = self.text(row, cols.clone());
+            if !text.trim().is_empty() {
+                numbers.push(parse::int(text).map_err(|err| err.within(self.input, text))?);
             }
         }
+        self.problem(cols, numbers, operator)
+    }
 
-        // Handle the last block if it wasn't followed by a space
-        if start_col < width
-            && let Some(p) = Self::parse_slice(&grid, start_col, width)
-        {
-            problems.push(p);
-        }
-
-        problems
+    fn parse_all(&self) -> Result<Vec<Problem>, ParseError> {
+        self.problem_columns()
+            .into_iter()
+            .map(|cols| self.parse_slice(cols))
+            .collect()
     }
 }
 
 #[instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let problems = WorksheetParser::parse_all(input);
+    let problems = Worksheet::parse(input)?.parse_all()?;
 
     let grand_total: u64 = problems.iter().map(|p| p.solve()).sum();
 
@@ -132,36 +188,35 @@ mod tests {
     use super::*;
     use rstest::rstest;
 
-    #[test_log::test]
-    fn test_to_grid() {
-        let input = "12\n3";
-        let grid = WorksheetParser::to_grid(input);
+    fn parse_error(input: &str) -> ParseError {
+        Worksheet::parse(input)
+            .and_then(|sheet| sheet.parse_all())
+            .expect_err("invalid worksheet")
+    }
 
-        assert_eq!(grid.len(), 2);
-        assert_eq!(grid[0], vec!['1', '2']);
-        assert_eq!(grid[1], vec!['3', ' ']); // should be padded
+    #[test_log::test]
+    fn test_ragged_rows_are_rejected() {
+        let err = parse_error("12\n3\n+ ");
+        assert_eq!(err.line_col(), (2, 1));
     }
 
     #[test_log::test]
     fn test_is_column_empty() {
-        let grid = vec![vec!['1', ' ', '3'], vec![' ', ' ', '4']];
+        let sheet = Worksheet::parse("1 3\n  4\n+ *").unwrap();
 
-        // Col 0: '1', ' ' -> Not empty
-        assert!(!WorksheetParser::is_column_empty(&grid, 0));
-        // Col 1: ' ', ' ' -> Empty
-        assert!(WorksheetParser::is_column_empty(&grid, 1));
+        // Col 0: '1', ' ', '+' -> Not empty
+        assert!(!sheet.is_column_empty(0));
+        // Col 1: ' ', ' ', ' ' -> Empty
+        assert!(sheet.is_column_empty(1));
+        assert_eq!(sheet.problem_columns(), vec![0..1, 2..3]);
     }
 
     #[test_log::test]
     fn test_parse_slice() {
-        let grid = vec![
-            vec!['1', '2', ' '], // "12"
-            vec![' ', '5', ' '], // " 5"
-            vec!['+', ' ', ' '], // Operator +
-        ];
+        let sheet = Worksheet::parse("12 \n 5 \n+  ").unwrap();
 
         // Slice the whole width (0..3)
-        let problem = WorksheetParser::parse_slice(&grid, 0, 3).unwrap();
+        let problem = sheet.parse_slice(0..3).unwrap();
 
         assert_eq!(problem.operator, '+');
         assert_eq!(problem.numbers, vec![12, 5]);
@@ -180,10 +235,48 @@ mod tests {
         assert_eq!(p.solve(), expected)
     }
 
+    #[test_log::test]
+    fn test_parse_points_at_bad_cells() {
+        let err = parse_error("123 4\n 5x 6\n*   +");
+        assert_eq!(err.message(), "unexpected `x`");
+        assert_eq!(err.line_col(), (2, 3));
+
+        let err = parse_error("123 4\n 45 6\n*   -");
+        assert_eq!(err.message(), "unexpected `-`");
+        assert_eq!(err.line_col(), (3, 5));
+
+        let err = parse_error("123 4\n 4+ 6\n*   +");
+        assert_eq!(err.message(), "operator above the last row");
+        assert_eq!(err.line_col(), (2, 3));
+
+        assert_eq!(parse_error("*   +").message(), "missing number rows");
+        assert!(Worksheet::parse("").is_ok());
+    }
+
+    #[test_log::test]
+    fn test_problems_need_one_operator_and_valid_numbers() {
+        let err = parse_error("12345\n5 6 7\n*    ");
+        assert_eq!(err.message(), "invalid integer `5 6 7`");
+        assert_eq!(err.line_col(), (2, 1));
+
+        let err = parse_error("12 34\n 5  6\n*    ");
+        assert_eq!(err.message(), "problem has no operator");
+        assert_eq!(err.line_col(), (3, 4));
+
+        let err = parse_error("123\n 45\n*+ ");
+        assert_eq!(err.message(), "problem has more than one operator");
+        assert_eq!(err.line_col(), (3, 2));
+
+        let err = parse_error("99999999999999999999\n+                   ");
+        assert!(err.message().contains("out of range"));
+    }
+
     #[test_log::test]
     fn test_parse_all_intergration() {
-        let input = "123 328\n 45  64\n  6  98\n*   +";
-        let problems = WorksheetParser::parse_all(input);
+        let input = "123 328\n 45  64\n  6  98\n*   +  ";
+        let problems = Worksheet::parse(input)
+            .and_then(|sheet| sheet.parse_all())
+            .unwrap();
 
         assert_eq!(problems.len(), 2);
 
@@ -1,84 +1,65 @@
+use std::ops::Range;
+
+use common::parse::ParseError;
 use tracing::{info, instrument};
 
-use crate::part1::{Problem, WorksheetParser};
+use crate::part1::{Problem, Worksheet};
 
 trait VerticalScanner {
-    fn parse_all_vertical(input: &str) -> Vec<Problem>;
+    fn parse_all_vertical(&self) -> Result<Vec<Problem>, ParseError>;
 }
 
-impl VerticalScanner for WorksheetParser {
-    #[instrument(skip(input))]
-    fn parse_all_vertical(input: &str) -> Vec<Problem> {
-        let grid = Self::to_grid(input);
-        if grid.is_empty() {
-            return vec![];
-        }
-
-        let width = grid[0].len();
-        let mut problems = Vec::new();
-        let mut start_col = 0;
-
-        for col in 0..width {
-            if Self::is_column_empty(&grid, col) {
-                if col > start_col
-                    && let Some(p) = parse_slice_vertical(&grid, start_col, col)
-                {
-                    problems.push(p);
-                }
-                start_col = col + 1;
-            }
-        }
-
-        if start_col < width
-            && let Some(p) = parse_slice_vertical(&grid, start_col, width)
-        {
-            problems.push(p);
-        }
-
-        problems
+impl VerticalScanner for Worksheet<'_> {
+    #[instrument(skip(self))]
+    fn parse_all_vertical(&self) -> Result<Vec<Problem>, ParseError> {
+        self.problem_columns()
+            .into_iter()
+            .map(|cols| parse_slice_vertical(self, cols))
+            .collect()
     }
 }
 
-fn parse_slice_vertical(grid: &[Vec<char>], start_col: usize, end_col: usize) -> Option<Problem> {
-    let height = grid.len();
-    if height < 2 {
-        return None;
-    }
-
-    let operator = grid[height - 1][start_col..end_col]
-        .iter()
-        .find(|&&c| c != ' ')
-        .copied()?;
-
+/// Reads the problem in `cols` column by column, right to left, each column
+/// being one number written top to bottom.
+fn parse_slice_vertical(sheet: &Worksheet, cols: Range<usize>) -> Result<Problem, ParseError> {
+    let operator = sheet.operator(cols.clone())?;
     let mut numbers = Vec::new();
 
-    for c in (start_col..end_col).rev() {
-        let mut digit_str = String::new();
-
-        for row in grid.iter().take(height - 1) {
-            let char_at = row[c];
-            if char_at.is_ascii_digit() {
-                digit_str.push(char_at);
-            }
-        }
-
-        if !digit_str.is_empty()
-            && let Ok(num) = digit_str.parse::<u64>()
+    for col in cols.clone().rev() {
+        let mut number: Option<u64> = None;
+        for (row, &c) in sheet
+            .grid
+            .column(col)
+            .take(sheet.operator_row())
+            .enumerate()
         {
-            numbers.push(num);
+            let Some(digit) = c.to_digit(10) else {
+                continue;
+            };
+            number = Some(
+                number
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|n| n.checked_add(u64::from(digit)))
+                    .ok_or_else(|| {
+                        ParseError::at(
+                            sheet.input(),
+                            sheet.text(row, col..col + 1),
+                            "number out of range",
+                        )
+                        .with_label("this digit overflows u64")
+                    })?,
+            );
         }
+        numbers.extend(number);
     }
 
-    if numbers.is_empty() {
-        None
-    } else {
-        Some(Problem { numbers, operator })
-    }
+    sheet.problem(cols, numbers, operator)
 }
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let problems = WorksheetParser::parse_all_vertical(input);
+    let problems = Worksheet::parse(input)?.parse_all_vertical()?;
 
     let total: u64 = problems.iter().map(|p| p.solve()).sum();
 
@@ -92,14 +73,9 @@ mod tests {
 
     #[test_log::test]
     fn test_parse_slice_vertical() {
-        let grid = vec![
-            vec!['1', '2', '3'],
-            vec![' ', '4', '5'],
-            vec![' ', ' ', '6'],
-            vec!['*', ' ', ' '],
-        ];
+        let sheet = Worksheet::parse("123\n 45\n  6\n*  ").unwrap();
 
-        let problem = parse_slice_vertical(&grid, 0, 3).unwrap();
+        let problem = parse_slice_vertical(&sheet, 0..3).unwrap();
 
         assert_eq!(problem.operator, '*');
         // Remember: Right-to-Left
@@ -107,6 +83,16 @@ mod tests {
         assert_eq!(problem.solve(), 8544);
     }
 
+    #[test_log::test]
+    fn test_overflowing_column_points_at_the_digit() {
+        let input = format!("{}+", "9\n".repeat(21));
+        let sheet = Worksheet::parse(&input).unwrap();
+
+        let err = parse_slice_vertical(&sheet, 0..1).expect_err("21 digits");
+        assert_eq!(err.message(), "number out of range");
+        assert_eq!(err.line_col(), (20, 1));
+    }
+
     #[test_log::test]
     fn test_process() -> miette::Result<()> {
         let input = "123 328  51 64 
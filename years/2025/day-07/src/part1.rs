@@ -1,81 +1,57 @@
 use std::collections::HashSet;
 
+use common::grid::{Dir, Grid, Pos};
+use common::parse::{self, ParseError};
 use tracing::{info, instrument};
 
-pub type Position = (usize, usize);
-
 pub struct TachyonLab {
-    pub grid: Vec<Vec<char>>,
-    pub width: usize,
-    pub height: usize,
-    pub start: Position,
+    pub grid: Grid<char>,
+    pub start: Pos,
 }
 
 impl TachyonLab {
-    pub fn new(input: &str) -> Self {
-        let grid = Self::parse_grid(input);
-        let height = grid.len();
-        let width = grid.first().map(|l| l.len()).unwrap_or(0);
-        let start = Self::find_start(&grid).unwrap_or((0, 0));
-
-        info!(width, height, ?start, "Lab initialized");
-
-        Self {
-            grid,
-            width,
-            height,
-            start,
-        }
-    }
-
-    fn parse_grid(input: &str) -> Vec<Vec<char>> {
-        input.lines().map(|l| l.chars().collect()).collect()
-    }
-
-    fn find_start(grid: &[Vec<char>]) -> Option<Position> {
-        for (r, row) in grid.iter().enumerate() {
-            for (c, &ch) in row.iter().enumerate() {
-                if ch == 'S' {
-                    return Some((r, c));
-                }
-            }
-        }
-        None
-    }
-
-    fn is_in_bounds(&self, pos: Position) -> bool {
-        pos.0 < self.height && pos.1 < self.width
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        let grid = parse::char_grid(
+            input,
+            |c| matches!(c, '.' | '^' | 'S').then_some(c),
+            "expected `.`, `^` or `S`",
+        )?;
+        let start = grid.find(&'S').ok_or_else(|| {
+            ParseError::new(input, (0, 0), "the manifold has no entry point")
+                .with_help("mark the beam's starting cell with `S`")
+        })?;
+
+        info!(
+            width = grid.width(),
+            height = grid.height(),
+            ?start,
+            "Lab initialized"
+        );
+
+        Ok(Self { grid, start })
     }
 
     /// Core Physics Logic: Determines the next positions based on current state.
     /// Returns: (Next Positions to visit, Was a splitter activated?)
     /// This function is PURE regarding the simulation state (visited set).
-    pub fn calculate_next_moves(&self, current: Position) -> (Vec<Position>, bool) {
-        let (r, c) = current;
-        let next_r = r + 1;
-
-        if !self.is_in_bounds((next_r, c)) {
+    pub fn calculate_next_moves(&self, current: Pos) -> (Vec<Pos>, bool) {
+        let Some(below) = self.grid.step(current, Dir::S) else {
             return (vec![], false);
-        }
+        };
 
-        let tile = self.grid[next_r][c];
         let mut next_positions = Vec::new();
         let mut splitter_hit = false;
 
-        match tile {
+        match self.grid[below] {
             '.' | 'S' => {
-                next_positions.push((next_r, c));
+                next_positions.push(below);
             }
             '^' => {
                 splitter_hit = true;
 
-                // Try split left
-                if c > 0 {
-                    next_positions.push((next_r, c - 1));
-                }
-                if c + 1 < self.width {
-                    next_positions.push((next_r, c + 1));
-                }
+                // Try split left, then right
+                next_positions.extend(self.grid.step(below, Dir::W));
+                next_positions.extend(self.grid.step(below, Dir::E));
             }
             _ => {
                 // Obstacles or unknown chars: beam stops
@@ -98,9 +74,9 @@ impl TachyonLab {
 
             // Record splitter activation logic
             // Note: If hit_splitter is true, it means the *tile below* is a splitter.
-            // The splitter's coordinate is (current.0 + 1, current.1)
+            // The splitter's coordinate is (current.row + 1, current.col)
             if hit_splitter {
-                activated_splitters.insert((current.0 + 1, current.1));
+                activated_splitters.insert(Pos::new(current.row + 1, current.col));
             }
 
             // Process next moves
@@ -119,7 +95,7 @@ impl TachyonLab {
 
 #[instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let lab = TachyonLab::new(input);
+    let lab = TachyonLab::new(input)?;
     let result = lab.count_splits();
     Ok(result.to_string())
 }
@@ -128,19 +104,27 @@ pub fn process(input: &str) -> miette::Result<String> {
 mod tests {
     use super::*;
 
+    fn lab(input: &str) -> TachyonLab {
+        TachyonLab::new(input).expect("rectangular lab")
+    }
+
     #[test_log::test]
     fn test_parse_grid() {
-        let input = "S.\n.^";
-        let grid = TachyonLab::parse_grid(input);
-        assert_eq!(grid.len(), 2);
-        assert_eq!(grid[0], vec!['S', '.']);
-        assert_eq!(grid[1], vec!['.', '^']);
+        let lab = lab("S.\n.^");
+        assert_eq!(lab.grid.height(), 2);
+        assert_eq!(lab.grid.row(0), &['S', '.']);
+        assert_eq!(lab.grid.row(1), &['.', '^']);
+    }
+
+    #[test_log::test]
+    fn test_missing_start_is_an_error() {
+        let err = TachyonLab::new("..\n.^").err().expect("no S");
+        assert_eq!(err.message(), "the manifold has no entry point");
     }
 
     #[test_log::test]
     fn test_find_start() {
-        let grid = vec![vec!['.', '.'], vec!['.', 'S']];
-        assert_eq!(TachyonLab::find_start(&grid), Some((1, 1)))
+        assert_eq!(lab("..\n.S").start, Pos::new(1, 1))
     }
 
     #[test_log::test]
@@ -149,40 +133,28 @@ mod tests {
         // S.
         // .^  <-- (1, 1) is a splitter
         // ..
-        let grid = vec![vec!['S', '.'], vec!['.', '^'], vec!['.', '.']];
-        let lab = TachyonLab {
-            grid,
-            width: 2,
-            height: 3,
-            start: (0, 0),
-        };
+        let lab = lab("S.\n.^\n..");
 
         // Scenario 1: Beam at (0, 0) (The 'S'). Moving down to (1, 0) which is '.'.
         // Expectation: Beam moves to (1, 0). No splitter.
-        let (moves, hit) = lab.calculate_next_moves((0, 0));
-        assert_eq!(moves, vec![(1, 0)]);
-        assert_eq!(hit, false);
+        let (moves, hit) = lab.calculate_next_moves(Pos::new(0, 0));
+        assert_eq!(moves, vec![Pos::new(1, 0)]);
+        assert!(!hit);
 
         // Scenario 2: Beam at (0, 1) (The '.'). Moving down to (1, 1) which is '^'.
         // Expectation: Beam stops, splits to (1, 0) [Left] and (1, 2) [Right - Out of bounds].
         // Wait, width is 2. (1, 2) is out of bounds. So only (1, 0).
-        let (moves, hit) = lab.calculate_next_moves((0, 1));
-        assert_eq!(moves, vec![(1, 0)]); // Only left is valid
-        assert_eq!(hit, true);
+        let (moves, hit) = lab.calculate_next_moves(Pos::new(0, 1));
+        assert_eq!(moves, vec![Pos::new(1, 0)]); // Only left is valid
+        assert!(hit);
     }
 
     #[test_log::test]
     fn test_physics_out_of_bounds() {
-        let grid = vec![vec!['.']]; // 1x1 grid
-        let lab = TachyonLab {
-            grid,
-            width: 1,
-            height: 1,
-            start: (0, 0),
-        };
+        let lab = lab("S"); // 1x1 grid
 
         // Beam at (0,0), tries to go to (1,0). (1,0) is out of bounds.
-        let (moves, hit) = lab.calculate_next_moves((0, 0));
+        let (moves, hit) = lab.calculate_next_moves(Pos::new(0, 0));
         assert!(moves.is_empty());
         assert!(!hit);
     }
@@ -1,8 +1,9 @@
 use std::collections::HashMap;
 
+use common::grid::Pos;
 use tracing::{info, instrument};
 
-use crate::part1::{Position, TachyonLab};
+use crate::part1::TachyonLab;
 
 trait QuantumManifold {
     fn count_timelines(&self) -> usize;
@@ -21,8 +22,8 @@ impl QuantumManifold for TachyonLab {
 }
 
 impl TachyonLab {
-    fn solve_recursive(&self, current: Position, memo: &mut HashMap<Position, usize>) -> usize {
-        if current.0 + 1 == self.height {
+    fn solve_recursive(&self, current: Pos, memo: &mut HashMap<Pos, usize>) -> usize {
+        if current.row + 1 == self.grid.height() {
             return 1;
         }
 
@@ -49,7 +50,7 @@ impl TachyonLab {
 
 #[instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let lab = TachyonLab::new(input);
+    let lab = TachyonLab::new(input)?;
     let result = lab.count_timelines();
     Ok(result.to_string())
 }
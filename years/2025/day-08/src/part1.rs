@@ -1,154 +1,63 @@
-use tracing::{debug, info, instrument};
-
-pub struct Dsu {
-    parent: Vec<usize>,
-    size: Vec<usize>,
+use common::dsu::Dsu;
+use common::geometry::Point3;
+use common::parse::{self, ParseError};
+use common::spatial::KdTree;
+use tracing::{debug, instrument};
+
+fn parse_point(line: &str) -> Result<Point3, ParseError> {
+    let coords = parse::comma_list(line, parse::int)?;
+    let [x, y, z] = coords[..] else {
+        return Err(ParseError::at(
+            line,
+            line.trim(),
+            format!("expected 3 coordinates, found {}", coords.len()),
+        )
+        .with_label("like `162,817,812`"));
+    };
+    Ok(Point3::new(x, y, z))
 }
 
-impl Dsu {
-    pub fn new(n: usize) -> Self {
-        Self {
-            parent: (0..n).collect(),
-            size: vec![1; n],
-        }
-    }
-
-    pub fn find(&mut self, i: usize) -> usize {
-        if self.parent[i] == i {
-            return i;
-        }
-        let root = self.find(self.parent[i]);
-        self.parent[i] = root; // Path compression: point directly to root
-        root
-    }
-
-    pub fn union(&mut self, i: usize, j: usize) -> bool {
-        let root_i = self.find(i);
-        let root_j = self.find(j);
-
-        if root_i == root_j {
-            return false;
-        }
-
-        if self.size[root_i] > self.size[root_j] {
-            self.parent[root_j] = root_i;
-            self.size[root_i] += self.size[root_j];
-        } else {
-            self.parent[root_i] = root_j;
-            self.size[root_j] += self.size[root_i];
-        }
-        true
-    }
-
-    fn get_all_circuit_sizes(&mut self) -> Vec<usize> {
-        let mut sizes = Vec::new();
-        // We must iterate through all indices to find the roots
-        // Note: We need to use 0..len because self is borrowed mutably inside
-        let len = self.parent.len();
-
-        for i in 0..len {
-            // Check if i is a root
-            if self.parent[i] == i {
-                sizes.push(self.size[i]);
-            }
-        }
-
-        // Sort descending
-        sizes.sort_by(|a, b| b.cmp(a));
-        sizes
-    }
-}
-
-pub struct Point3D {
-    pub x: i64,
-    pub y: i64,
-    pub z: i64,
-}
-
-impl Point3D {
-    fn dist_sq(&self, other: &Point3D) -> i64 {
-        let dx = self.x - other.x;
-        let dy = self.y - other.y;
-        let dz = self.z - other.z;
-        dx * dx + dy * dy + dz * dz
-    }
-}
-
-pub struct Edge {
-    pub u: usize,
-    pub v: usize,
-    pub dist_sq: i64,
-}
-
-pub fn generate_sorted_edges(points: &[Point3D]) -> Vec<Edge> {
-    let n = points.len();
-    let mut edges = Vec::with_capacity(n * (n - 1) / 2);
-
-    for i in 0..n {
-        for j in (i + 1)..n {
-            let d = points[i].dist_sq(&points[j]);
-            edges.push(Edge {
-                u: i,
-                v: j,
-                dist_sq: d,
-            });
-        }
-    }
-
-    edges.sort_by_key(|e| e.dist_sq);
-
-    info!(total_edges = edges.len(), "Generated and sorted edges");
-    edges
+pub fn parse_points(input: &str) -> Result<Vec<Point3>, ParseError> {
+    parse::lines(input, parse_point)
 }
 
-fn solve(input: &str, limit: usize) -> Option<usize> {
-    let points: Vec<Point3D> = input
-        .lines()
-        .filter_map(|line| {
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() < 3 {
-                return None;
-            }
-            Some(Point3D {
-                x: parts[0].trim().parse().ok()?,
-                y: parts[1].trim().parse().ok()?,
-                z: parts[2].trim().parse().ok()?,
-            })
-        })
-        .collect();
+fn solve(input: &str, limit: usize) -> Result<usize, ParseError> {
+    let points = parse_points(input)?;
 
     let n = points.len();
     if n == 0 {
-        return Some(0);
+        return Ok(0);
     }
 
-    let edges = generate_sorted_edges(&points);
+    // Only the `limit` closest pairs are ever generated.
+    let tree = KdTree::new(&points);
 
     let mut dsu = Dsu::new(n);
 
-    let actual_limit = limit.min(edges.len());
-
+    let mut connections = 0;
     let mut actual_merges = 0;
-    for edge in edges.iter().take(actual_limit) {
-        if dsu.union(edge.u, edge.v) {
+    for pair in tree.closest_pairs().take(limit) {
+        connections += 1;
+        if dsu.union(pair.a, pair.b) {
             actual_merges += 1;
         }
     }
 
     debug!(
-        limit = actual_limit,
+        limit = connections,
         merges = actual_merges,
         "DSU pass complete"
     );
 
-    let sizes = dsu.get_all_circuit_sizes();
+    let mut sizes = dsu.component_sizes();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
 
-    Some(sizes.iter().take(3).product())
+    Ok(sizes.iter().take(3).product())
 }
 
 #[instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let result = solve(input, 1000).unwrap_or(0);
+    let result = solve(input, 1000)?;
     Ok(result.to_string())
 }
 
@@ -157,99 +66,25 @@ mod tests {
     use super::*;
 
     #[test_log::test]
-    fn test_dsu_initialization() {
-        let dsu = Dsu::new(3);
-
-        assert_eq!(dsu.parent, vec![0, 1, 2]);
-        assert_eq!(dsu.size, vec![1, 1, 1]);
-    }
-
-    #[test_log::test]
-    fn test_dsu_find() {
-        // 0 -> 1 -> 2
-        let mut dsu = Dsu::new(3);
-        dsu.parent[0] = 1;
-        dsu.parent[1] = 2;
-
-        assert_eq!(dsu.find(0), 2);
-    }
-
-    #[test_log::test]
-    fn test_dsu_simple_union() {
-        let mut dsu = Dsu::new(2);
-
-        assert!(dsu.union(0, 1), "Should be success for the first time");
-        assert!(!dsu.union(0, 1), "Should fail when duplicate");
-        assert!(!dsu.union(1, 0), "Should fail when duplicate");
-    }
-
-    #[test_log::test]
-    fn test_dsu_union_by_size_logic() {
-        let mut dsu = Dsu::new(10);
-
-        dsu.union(0, 1);
-        dsu.union(0, 2);
-        let root_a = dsu.find(0);
-        assert_eq!(dsu.size[root_a], 3);
-
-        let root_b = dsu.find(3);
-        assert_eq!(dsu.size[root_b], 1);
-
-        dsu.union(3, 0);
-        assert_eq!(
-            dsu.parent[root_b], root_a,
-            "Small root should point to big root"
-        );
-        assert_eq!(dsu.parent[root_a], root_a, "Big root should remain root");
-        assert_eq!(dsu.size[root_a], 4);
-    }
-
-    #[test_log::test]
-    fn test_dsu_path_compression() {
-        let mut dsu = Dsu::new(5);
-        // 0 -> 1 -> 2 -> 3 -> 4 (Root)
-        dsu.parent = vec![1, 2, 3, 4, 4];
-        assert_eq!(dsu.parent[0], 1);
-        let root = dsu.find(0);
-        assert_eq!(root, 4);
-
-        assert_eq!(
-            dsu.parent[0], 4,
-            "Path should be compressed directly to root"
-        );
-        assert_eq!(
-            dsu.parent[1], 4,
-            "Intermediate nodes should also be compressed"
-        );
-        assert_eq!(dsu.parent[2], 4);
-        assert_eq!(dsu.parent[3], 4);
-    }
-
-    #[test_log::test]
-    fn test_get_all_circuit_sizes() {
-        let mut dsu = Dsu::new(5);
-        assert_eq!(dsu.get_all_circuit_sizes(), vec![1; 5]);
-    }
-
-    #[test_log::test]
-    fn test_sorted_edges_logic() {
+    fn test_closest_pairs_logic() {
         let points = vec![
-            Point3D { x: 0, y: 0, z: 0 },
-            Point3D { x: 10, y: 0, z: 0 }, // dist_sq = 100
-            Point3D { x: 2, y: 0, z: 0 },  // dist_sq = 4
+            Point3::new(0, 0, 0),
+            Point3::new(10, 0, 0), // dist_sq = 100
+            Point3::new(2, 0, 0),  // dist_sq = 4
         ];
 
-        let edges = generate_sorted_edges(&points);
+        let tree = KdTree::new(&points);
+        let pairs: Vec<_> = tree.closest_pairs().collect();
 
-        assert_eq!(edges.len(), 3);
+        assert_eq!(pairs.len(), 3);
 
         // Expected order：
         // 1. (0, 2) dist 4
-        // 2. (2, 1) dist 64 (10-2=8, 8^2=64)
+        // 2. (1, 2) dist 64 (10-2=8, 8^2=64)
         // 3. (0, 1) dist 100
-        assert_eq!(edges[0].dist_sq, 4);
-        assert_eq!(edges[1].dist_sq, 64);
-        assert_eq!(edges[2].dist_sq, 100);
+        assert_eq!((pairs[0].a, pairs[0].b, pairs[0].dist_sq), (0, 2, 4));
+        assert_eq!((pairs[1].a, pairs[1].b, pairs[1].dist_sq), (1, 2, 64));
+        assert_eq!((pairs[2].a, pairs[2].b, pairs[2].dist_sq), (0, 1, 100));
     }
 
     #[test_log::test]
@@ -274,7 +109,17 @@ mod tests {
 862,61,35
 984,92,344
 425,690,689";
-        assert_eq!(solve(input, 10), Some(40));
+        assert_eq!(solve(input, 10)?, 40);
         Ok(())
     }
+
+    #[test_log::test]
+    fn test_malformed_point_is_reported() {
+        let err = parse_points("1,2,3\n4,5\n").expect_err("two coordinates");
+        assert_eq!(err.message(), "expected 3 coordinates, found 2");
+        assert_eq!(err.line_col(), (2, 1));
+
+        let err = parse_points("1,2,3\n4,x,6\n").expect_err("not a number");
+        assert_eq!(err.line_col(), (2, 3));
+    }
 }
@@ -1,41 +1,25 @@
-use crate::part1::{Dsu, Point3D, generate_sorted_edges};
+use crate::part1::parse_points;
+use common::dsu::Dsu;
+use common::spatial::KdTree;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let points: Vec<Point3D> = input
-        .lines()
-        .filter_map(|line| {
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() < 3 {
-                return None;
-            }
-            Some(Point3D {
-                x: parts[0].trim().parse().ok()?,
-                y: parts[1].trim().parse().ok()?,
-                z: parts[2].trim().parse().ok()?,
-            })
-        })
-        .collect();
+    let points = parse_points(input)?;
 
     let n = points.len();
 
-    let edges = generate_sorted_edges(&points);
+    let tree = KdTree::new(&points);
 
     let mut dsu = Dsu::new(n);
 
-    let target_merges = n - 1;
-    let mut current_merges = 0;
+    // Pairs are generated lazily, so this stops at the last connection needed.
+    for pair in tree.closest_pairs() {
+        if dsu.union(pair.a, pair.b) && dsu.component_count() == 1 {
+            let p1 = &points[pair.a];
+            let p2 = &points[pair.b];
 
-    for edge in edges {
-        if dsu.union(edge.u, edge.v) {
-            current_merges += 1;
-            if current_merges == target_merges {
-                let p1 = &points[edge.u];
-                let p2 = &points[edge.v];
-
-                let result = p1.x * p2.x;
-                return Ok(result.to_string());
-            }
+            let result = p1.x * p2.x;
+            return Ok(result.to_string());
         }
     }
 
@@ -1,38 +1,23 @@
+use common::geometry::Point2;
+use common::parse::{self, ParseError};
 use tracing::{info, instrument};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Point {
-    pub x: i64,
-    pub y: i64,
+/// Tiles covered by the rectangle with opposite corners `a` and `b`.
+pub fn tile_area(a: &Point2, b: &Point2) -> i64 {
+    let width = (a.x - b.x).abs() + 1;
+    let height = (a.y - b.y).abs() + 1;
+    width * height
 }
 
-impl Point {
-    pub fn new(x: i64, y: i64) -> Self {
-        Self { x, y }
-    }
-
-    pub fn area_with(&self, other: &Point) -> i64 {
-        let width = (self.x - other.x).abs() + 1;
-        let height = (self.y - other.y).abs() + 1;
-        width * height
-    }
-}
-
-pub fn parse_input(input: &str) -> Vec<Point> {
-    input
-        .lines()
-        .filter_map(|line| {
-            let (x_str, y_str) = line.split_once(',')?;
-            Some(Point::new(
-                x_str.trim().parse().ok()?,
-                y_str.trim().parse().ok()?,
-            ))
-        })
-        .collect()
+pub fn parse_input(input: &str) -> Result<Vec<Point2>, ParseError> {
+    parse::lines(input, |line| {
+        let (x, y) = parse::pair(line, ',', parse::int, parse::int)?;
+        Ok(Point2::new(x, y))
+    })
 }
 
 #[instrument(skip(points))]
-fn solve_largest_area(points: &[Point]) -> i64 {
+fn solve_largest_area(points: &[Point2]) -> i64 {
     let n = points.len();
     if n < 2 {
         return 0;
@@ -42,7 +27,7 @@ fn solve_largest_area(points: &[Point]) -> i64 {
 
     for i in 0..n {
         for j in (i + 1)..n {
-            let area = points[i].area_with(&points[j]);
+            let area = tile_area(&points[i], &points[j]);
             if area > max_area {
                 max_area = area
             }
@@ -54,7 +39,7 @@ fn solve_largest_area(points: &[Point]) -> i64 {
 
 #[instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let points = parse_input(input);
+    let points = parse_input(input)?;
     let result = solve_largest_area(&points);
 
     info!(
@@ -71,18 +56,18 @@ mod tests {
 
     #[test_log::test]
     fn test_area_calculation() {
-        let p1 = Point::new(2, 5);
-        let p2 = Point::new(9, 7);
+        let p1 = Point2::new(2, 5);
+        let p2 = Point2::new(9, 7);
 
-        assert_eq!(p1.area_with(&p2), 24);
+        assert_eq!(tile_area(&p1, &p2), 24);
     }
 
     #[test_log::test]
     fn test_solve_largest_area_basic() {
         let points = vec![
-            Point::new(0, 0),
-            Point::new(1, 1),   // Area with (0,0) = 2*2 = 4
-            Point::new(10, 10), // Area with (0,0) = 11*11 = 121
+            Point2::new(0, 0),
+            Point2::new(1, 1),   // Area with (0,0) = 2*2 = 4
+            Point2::new(10, 10), // Area with (0,0) = 11*11 = 121
         ];
 
         assert_eq!(solve_largest_area(&points), 121);
@@ -91,17 +76,17 @@ mod tests {
     #[test_log::test]
     fn test_solve_largest_area_insufficient_points() {
         // Case 1: Empty
-        let points: Vec<Point> = vec![];
+        let points: Vec<Point2> = vec![];
         assert_eq!(solve_largest_area(&points), 0);
 
         // Case 2: Single point
-        let points = vec![Point::new(5, 5)];
+        let points = vec![Point2::new(5, 5)];
         assert_eq!(solve_largest_area(&points), 0);
     }
 
     #[test_log::test]
     fn test_solve_largest_area_negative_coords() {
-        let points = vec![Point::new(-2, -2), Point::new(2, 2)];
+        let points = vec![Point2::new(-2, -2), Point2::new(2, 2)];
 
         // width = |-2 - 2| + 1 = 5
         // height = |-2 - 2| + 1 = 5
@@ -109,6 +94,13 @@ mod tests {
         assert_eq!(solve_largest_area(&points), 25);
     }
 
+    #[test_log::test]
+    fn test_parse_input_reports_bad_tile() {
+        let err = parse_input("7,1\n11;1\n").expect_err("missing comma");
+        assert_eq!(err.message(), "expected two values separated by `,`");
+        assert_eq!(err.line_col(), (2, 1));
+    }
+
     #[test]
     fn test_process() -> miette::Result<()> {
         let input = "7,1
@@ -1,139 +1,144 @@
-use std::cmp::{max, min};
-
+use common::geometry::PolygonRaster;
+use miette::IntoDiagnostic;
 use tracing::{info, instrument};
 
-use crate::part1::{Point, parse_input};
+use crate::part1::{parse_input, tile_area};
 
-struct Rect {
-    min_x: i64,
-    max_x: i64,
-    min_y: i64,
-    max_y: i64,
-}
+#[instrument(skip(input))]
+pub fn process(input: &str) -> miette::Result<String> {
+    let points = parse_input(input)?;
+    let raster = PolygonRaster::new(&points).into_diagnostic()?;
+    let located: Vec<_> = points.iter().map(|&p| raster.locate(p)).collect();
+    let mut max_area = 0;
+
+    for (i, (p1, g1)) in points.iter().zip(&located).enumerate() {
+        for (p2, g2) in points.iter().zip(&located).skip(i + 1) {
+            let area = tile_area(p1, p2);
+            if area <= max_area {
+                continue;
+            }
 
-impl Rect {
-    fn from_points(p1: Point, p2: Point) -> Self {
-        Self {
-            min_x: min(p1.x, p2.x),
-            max_x: max(p1.x, p2.x),
-            min_y: min(p1.y, p2.y),
-            max_y: max(p1.y, p2.y),
+            if let (Some(g1), Some(g2)) = (g1, g2)
+                && raster.contains_between(*g1, *g2)
+            {
+                max_area = area;
+            }
         }
     }
 
-    fn is_cut_by_segment(&self, start: Point, end: Point) -> bool {
-        let is_vertical = start.x == end.x;
-
-        if is_vertical {
-            let x = start.x;
-            let x_inside = x > self.min_x && x < self.max_x;
+    info!(max_area, "Calculation complete");
+    Ok(max_area.to_string())
+}
 
-            let seg_min = min(start.y, end.y);
-            let seg_max = max(start.y, end.y);
-            let y_overlap = seg_min < self.max_y && seg_max > self.min_y;
+/// Reference implementation, kept to check [`PolygonRaster`] against.
+#[cfg(test)]
+mod oracle {
+    use std::cmp::{max, min};
 
-            x_inside && y_overlap
-        } else {
-            let y = start.y;
-            let y_inside = y > self.min_y && y < self.max_y;
+    use common::geometry::Point2;
 
-            let seg_min = min(start.x, end.x);
-            let seg_max = max(start.x, end.x);
-            let x_overlap = seg_min < self.max_x && seg_max > self.min_x;
+    pub struct Rect {
+        pub min_x: i64,
+        pub max_x: i64,
+        pub min_y: i64,
+        pub max_y: i64,
+    }
 
-            y_inside && x_overlap
+    impl Rect {
+        pub fn from_points(p1: Point2, p2: Point2) -> Self {
+            Self {
+                min_x: min(p1.x, p2.x),
+                max_x: max(p1.x, p2.x),
+                min_y: min(p1.y, p2.y),
+                max_y: max(p1.y, p2.y),
+            }
         }
-    }
-}
 
-struct Polygon {
-    vertices: Vec<Point>,
-}
+        pub fn is_cut_by_segment(&self, start: Point2, end: Point2) -> bool {
+            let is_vertical = start.x == end.x;
 
-impl Polygon {
-    fn new(vertices: Vec<Point>) -> Self {
-        Self { vertices }
-    }
+            if is_vertical {
+                let x = start.x;
+                let x_inside = x > self.min_x && x < self.max_x;
 
-    fn contains_center_of(&self, rect: &Rect) -> bool {
-        let mid_x = (rect.min_x as f64 + rect.max_x as f64) / 2.0;
-        let mid_y = (rect.min_y as f64 + rect.max_y as f64) / 2.0;
-
-        let mut inside = false;
-        let n = self.vertices.len();
-
-        for i in 0..n {
-            let p1 = self.vertices[i];
-            let p2 = self.vertices[(i + 1) % n]; // Make sure the list is wrapped
-
-            let p1y = p1.y as f64;
-            let p2y = p2.y as f64;
-            let p1x = p1.x as f64;
-            let p2x = p2.x as f64;
-
-            // Ray casting logic:
-            // 1. Check if the edge's Y-range spans the center point's Y (must straddle the line).
-            // 2. Check if there is an intersection to the "right" of the center point.
-            if (p1y > mid_y) != (p2y > mid_y) {
-                let intersect_x = (p2x - p1x) * (mid_y - p1y) / (p2y - p1y) + p1x;
-                if mid_x < intersect_x {
-                    inside = !inside;
-                }
-            }
-        }
-        inside
-    }
+                let seg_min = min(start.y, end.y);
+                let seg_max = max(start.y, end.y);
+                let y_overlap = seg_min < self.max_y && seg_max > self.min_y;
+
+                x_inside && y_overlap
+            } else {
+                let y = start.y;
+                let y_inside = y > self.min_y && y < self.max_y;
 
-    fn boundaries_cut_through(&self, rect: &Rect) -> bool {
-        let n = self.vertices.len();
-        for i in 0..n {
-            let p1 = self.vertices[i];
-            let p2 = self.vertices[(i + 1) % n];
+                let seg_min = min(start.x, end.x);
+                let seg_max = max(start.x, end.x);
+                let x_overlap = seg_min < self.max_x && seg_max > self.min_x;
 
-            if rect.is_cut_by_segment(p1, p2) {
-                return true;
+                y_inside && x_overlap
             }
         }
-        false
     }
 
-    fn fully_contains_rect(&self, rect: &Rect) -> bool {
-        self.contains_center_of(rect) && !self.boundaries_cut_through(rect)
+    pub struct Polygon {
+        vertices: Vec<Point2>,
     }
-}
 
-#[instrument(skip(input))]
-pub fn process(input: &str) -> miette::Result<String> {
-    let points = parse_input(input);
-    let n = points.len();
-
-    let polygon = Polygon::new(points.clone());
-    let mut max_area = 0;
+    impl Polygon {
+        pub fn new(vertices: Vec<Point2>) -> Self {
+            Self { vertices }
+        }
 
-    for i in 0..n {
-        for j in (i + 1)..n {
-            let p1 = points[i];
-            let p2 = points[j];
+        /// Whether the rectangle's centre lies strictly inside, checked on a
+        /// copy scaled by two so the centre lands on the lattice.
+        pub fn contains_center_of(&self, rect: &Rect) -> bool {
+            let (cx, cy) = (rect.min_x + rect.max_x, rect.min_y + rect.max_y);
+            let mut inside = false;
+            let n = self.vertices.len();
+
+            for i in 0..n {
+                let p1 = self.vertices[i] + self.vertices[i];
+                let p2 = self.vertices[(i + 1) % n] + self.vertices[(i + 1) % n];
+
+                // Ray casting logic:
+                // 1. The edge must straddle the centre's Y.
+                // 2. The crossing must lie to the right of the centre, which is
+                //    `cx < p1.x + (p2.x - p1.x) * (cy - p1.y) / (p2.y - p1.y)`
+                //    multiplied out so it stays in integers.
+                if (p1.y > cy) != (p2.y > cy) {
+                    let left = (cx - p1.x) * (p2.y - p1.y);
+                    let right = (p2.x - p1.x) * (cy - p1.y);
+                    if (left < right) == (p2.y > p1.y) {
+                        inside = !inside;
+                    }
+                }
+            }
+            inside
+        }
 
-            let area = p1.area_with(&p2);
+        fn boundaries_cut_through(&self, rect: &Rect) -> bool {
+            let n = self.vertices.len();
+            for i in 0..n {
+                let p1 = self.vertices[i];
+                let p2 = self.vertices[(i + 1) % n];
 
-            if area <= max_area {
-                continue;
+                if rect.is_cut_by_segment(p1, p2) {
+                    return true;
+                }
             }
+            false
+        }
 
-            let rect = Rect::from_points(p1, p2);
-            if polygon.fully_contains_rect(&rect) {
-                max_area = area;
-            }
+        pub fn fully_contains_rect(&self, rect: &Rect) -> bool {
+            self.contains_center_of(rect) && !self.boundaries_cut_through(rect)
         }
     }
-
-    info!(max_area, "Calculation complete");
-    Ok(max_area.to_string())
 }
 
 #[cfg(test)]
 mod tests {
+    use common::geometry::Point2;
+
+    use super::oracle::{Polygon, Rect};
     use super::*;
 
     #[test_log::test]
@@ -146,11 +151,11 @@ mod tests {
         };
 
         // Wall cut through
-        assert!(rect.is_cut_by_segment(Point::new(5, -5), Point::new(5, 15)));
+        assert!(rect.is_cut_by_segment(Point2::new(5, -5), Point2::new(5, 15)));
         // Wall on boundary
-        assert!(!rect.is_cut_by_segment(Point::new(0, -5), Point::new(0, 15)));
+        assert!(!rect.is_cut_by_segment(Point2::new(0, -5), Point2::new(0, 15)));
         // Wall completely outside
-        assert!(!rect.is_cut_by_segment(Point::new(20, -5), Point::new(20, 15)));
+        assert!(!rect.is_cut_by_segment(Point2::new(20, -5), Point2::new(20, 15)));
     }
 
     #[test_log::test]
@@ -158,14 +163,14 @@ mod tests {
         // Create a C shape
         // (0,0) -> (4,0) -> (4,1) -> (1,1) -> (1,3) -> (4,3) -> (4,4) -> (0,4)
         let vertices = vec![
-            Point::new(0, 0),
-            Point::new(4, 0),
-            Point::new(4, 1),
-            Point::new(1, 1),
-            Point::new(1, 3),
-            Point::new(4, 3),
-            Point::new(4, 4),
-            Point::new(0, 4),
+            Point2::new(0, 0),
+            Point2::new(4, 0),
+            Point2::new(4, 1),
+            Point2::new(1, 1),
+            Point2::new(1, 3),
+            Point2::new(4, 3),
+            Point2::new(4, 4),
+            Point2::new(0, 4),
         ];
 
         let poly = Polygon::new(vertices);
@@ -180,6 +185,51 @@ mod tests {
         assert!(!poly.contains_center_of(&rect));
     }
 
+    fn oracle_agrees(vertices: &[Point2]) {
+        let polygon = Polygon::new(vertices.to_vec());
+        let raster = PolygonRaster::new(vertices).expect("rectilinear");
+        for (i, &p1) in vertices.iter().enumerate() {
+            for &p2 in &vertices[i + 1..] {
+                // The oracle can't judge zero-width rectangles, whose centre
+                // lies on the boundary.
+                if p1.x == p2.x || p1.y == p2.y {
+                    continue;
+                }
+                let rect = Rect::from_points(p1, p2);
+                assert_eq!(
+                    raster.contains_rect(p1, p2),
+                    polygon.fully_contains_rect(&rect),
+                    "{p1:?} {p2:?}"
+                );
+            }
+        }
+    }
+
+    #[test_log::test]
+    fn test_raster_matches_polygon() -> miette::Result<()> {
+        let example = parse_input("7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3")?;
+        oracle_agrees(&example);
+
+        // A spiral, so most rectangles cross an edge somewhere.
+        let spiral = [
+            (0, 0),
+            (10, 0),
+            (10, 10),
+            (2, 10),
+            (2, 4),
+            (6, 4),
+            (6, 6),
+            (4, 6),
+            (4, 8),
+            (8, 8),
+            (8, 2),
+            (0, 2),
+        ]
+        .map(|(x, y)| Point2::new(x, y));
+        oracle_agrees(&spiral);
+        Ok(())
+    }
+
     #[test_log::test]
     fn test_process() -> miette::Result<()> {
         let input = "7,1
@@ -1,10 +1,16 @@
-use day_10::part1::process;
+use common::input::Puzzle;
+use day_10::part1::{breakdown, process};
 use miette::Context;
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
-    let file = include_str!("../../input1.txt");
-    let daily_result = process(file).context("Process part1")?;
-    println!("{daily_result}");
+    let ([verbose], file) = Puzzle::new(2025, 10, 1).load_with_flags(["--verbose"])?;
+    if verbose {
+        let lines = breakdown(&file).context("Break down part1")?;
+        print!("{lines}");
+    } else {
+        let daily_result = process(&file).context("Process part1")?;
+        println!("{daily_result}");
+    }
     Ok(())
 }
@@ -1,10 +1,16 @@
-use day_10::part2::process;
+use common::input::Puzzle;
+use day_10::part2::{breakdown, process};
 use miette::Context;
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
-    let file = include_str!("../../input2.txt");
-    let daily_result = process(file).context("Process part2")?;
-    println!("{daily_result}");
+    let ([verbose], file) = Puzzle::new(2025, 10, 2).load_with_flags(["--verbose"])?;
+    if verbose {
+        let lines = breakdown(&file).context("Break down part2")?;
+        print!("{lines}");
+    } else {
+        let daily_result = process(&file).context("Process part2")?;
+        println!("{daily_result}");
+    }
     Ok(())
 }
@@ -1,5 +1,9 @@
-use std::collections::{HashSet, VecDeque};
-
+use common::gf2::{self, BitMatrix, BitVec, NullityTooLarge};
+#[cfg(test)]
+use common::graph::search::bfs;
+use common::parse::{self, Delim, ParseError};
+use miette::IntoDiagnostic;
+use thiserror::Error;
 use tracing::{info, instrument};
 
 #[derive(Debug)]
@@ -8,86 +12,209 @@ struct Machine {
     buttons: Vec<u32>,
 }
 
-fn parse_light_pattern(input: &str) -> u32 {
-    let content = input
-        .strip_prefix('[')
-        .and_then(|s| s.strip_suffix(']'))
-        .expect("Invalid light pattern format! Missing brackets.");
-
-    content
-        .chars()
-        .enumerate()
-        .filter(|(_, c)| *c == '#')
-        .fold(0, |acc, (i, _)| acc | (1 << i))
+/// The presses found for one machine. In part 1 `presses` lists the buttons
+/// pressed, each once; in part 2 it counts the presses of every button.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Solution {
+    pub presses: Vec<usize>,
+    pub total: usize,
 }
 
-fn parse_buttons(input: &str) -> Vec<u32> {
-    input
-        .split_whitespace()
-        .map(|token| {
-            let inner = token
-                .strip_prefix('(')
-                .and_then(|s| s.strip_suffix(')'))
-                .expect("Invalid button format!");
-            inner.split(',').map(|n| n.trim()).fold(0u32, |acc, n_str| {
-                let bit = n_str.parse::<u32>().expect("Invalid bit index");
-                acc | (1 << bit)
-            })
+#[derive(Debug, Error, PartialEq, Eq)]
+pub(crate) enum SimulateError {
+    #[error("the machine has no button {0}")]
+    UnknownButton(usize),
+    #[error("the presses reach {actual} instead of {expected}")]
+    Mismatch { expected: String, actual: String },
+}
+
+/// Splits a machine line into its light pattern, button list and joltages.
+pub(crate) fn split_line(line: &str) -> Result<(&str, &str, &str), ParseError> {
+    let missing = || {
+        ParseError::at(
+            line,
+            line.trim(),
+            "expected lights, buttons and joltage requirements",
+        )
+        .with_label("like `[.##.] (3) (1,3) {3,5,4,7}`")
+    };
+    let lights = line.split_whitespace().next().ok_or_else(missing)?;
+    let start = parse::offset_in(line, lights).unwrap_or(0) + lights.len();
+    // The joltage group runs from the last `{`; without one, the last token
+    // is reported as the malformed group.
+    let end = match line.rfind('{') {
+        Some(i) if i >= start => i,
+        _ => {
+            let last = line[start..].split_whitespace().next_back();
+            last.and_then(|token| parse::offset_in(line, token))
+                .ok_or_else(missing)?
+        }
+    };
+
+    Ok((lights, line[start..end].trim(), line[end..].trim()))
+}
+
+fn parse_light_pattern(input: &str) -> Result<u32, ParseError> {
+    parse::delimited(input, Delim::Square, |content| {
+        if content.len() > 32 {
+            return Err(
+                ParseError::at(content, content, "too many lights").with_label("at most 32")
+            );
+        }
+
+        content.char_indices().try_fold(0, |acc, (i, c)| match c {
+            '#' => Ok(acc | (1 << i)),
+            '.' => Ok(acc),
+            _ => Err(
+                ParseError::at(content, &content[i..i + c.len_utf8()], "unexpected light")
+                    .with_label("expected `#` or `.`"),
+            ),
         })
-        .collect()
+    })
 }
 
-fn parse_line(line: &str) -> Option<Machine> {
-    let target_end = line.find(']')?;
-    let joltage_start = line.find('{')?;
+fn parse_button(token: &str) -> Result<u32, ParseError> {
+    let bits = parse::delimited(token, Delim::Paren, |inner| {
+        parse::comma_list(inner, |n| {
+            let bit: u32 = parse::int(n)?;
+            if bit >= u32::BITS {
+                return Err(
+                    ParseError::at(n, n, "light index out of range").with_label("at most 31")
+                );
+            }
+            Ok(bit)
+        })
+    })?;
+    Ok(bits.into_iter().fold(0, |acc, bit| acc | (1 << bit)))
+}
 
-    if target_end >= joltage_start {
-        return None;
-    }
+fn parse_buttons(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse::whitespace_list(input, parse_button)
+}
 
-    let target_str = &line[..=target_end];
-    let buttons_chunk = line[(target_end + 1)..joltage_start].trim();
+fn parse_line(line: &str) -> Result<Machine, ParseError> {
+    let (target_str, buttons_chunk, joltage_str) = split_line(line)?;
+    parse::delimited(joltage_str, Delim::Brace, Ok).map_err(|e| e.within(line, joltage_str))?;
 
-    Some(Machine {
-        target_state: parse_light_pattern(target_str),
-        buttons: parse_buttons(buttons_chunk),
+    Ok(Machine {
+        target_state: parse_light_pattern(target_str).map_err(|e| e.within(line, target_str))?,
+        buttons: parse_buttons(buttons_chunk).map_err(|e| e.within(line, buttons_chunk))?,
     })
 }
 
-fn solve_machine(machine: &Machine) -> Option<usize> {
-    if machine.target_state == 0 {
-        return Some(0);
-    }
+/// Fewest presses to light the target pattern.
+///
+/// Pressing a button twice undoes it, so each is pressed at most once and
+/// the presses are the lightest solution of `buttons x = target` over GF(2),
+/// one equation per light. Fails if too many buttons are redundant for
+/// every combination of them to be tried.
+fn solve_machine(machine: &Machine) -> Result<Option<Solution>, NullityTooLarge> {
+    let columns: Vec<u64> = machine.buttons.iter().map(|&b| u64::from(b)).collect();
+    // Lights no button or target touches would only add `0 = 0` rows.
+    let used = machine
+        .buttons
+        .iter()
+        .fold(machine.target_state, |acc, b| acc | b);
+    let lights = (u32::BITS - used.leading_zeros()) as usize;
+    let a = BitMatrix::from_column_masks(&columns, lights);
+    let b = BitVec::from_mask(u64::from(machine.target_state), lights);
+    let Some(x) = gf2::min_weight_solution(&a, &b)? else {
+        return Ok(None);
+    };
+    Ok(Some(Solution {
+        presses: x.ones().collect(),
+        total: x.count_ones(),
+    }))
+}
 
-    let mut queue = VecDeque::from([(0u32, 0u32)]);
-    let mut visited = HashSet::from([0u32]);
+/// Lit lights in the input's button notation, like `(1,2)`.
+fn lit(state: u32) -> String {
+    let lights: Vec<_> = (0..u32::BITS)
+        .filter(|i| state >> i & 1 == 1)
+        .map(|i| i.to_string())
+        .collect();
+    format!("({})", lights.join(","))
+}
 
-    while let Some((state, dist)) = queue.pop_front() {
-        for button in &machine.buttons {
-            let new_state = state ^ button;
-            if new_state == machine.target_state {
-                return Some((dist + 1) as usize);
-            }
+/// Presses `presses` once each from all lights off and checks that the
+/// machine ends up showing its target.
+fn simulate(machine: &Machine, presses: &[usize]) -> Result<(), SimulateError> {
+    let state = presses.iter().try_fold(0, |state, &button| {
+        let mask = machine
+            .buttons
+            .get(button)
+            .ok_or(SimulateError::UnknownButton(button))?;
+        Ok(state ^ mask)
+    })?;
+    if state == machine.target_state {
+        Ok(())
+    } else {
+        Err(SimulateError::Mismatch {
+            expected: lit(machine.target_state),
+            actual: lit(state),
+        })
+    }
+}
 
-            if visited.insert(new_state) {
-                queue.push_back((new_state, dist + 1));
-            }
-        }
+/// One line per machine listing the buttons pressed, for the binary's
+/// `--verbose` flag.
+///
+/// # Errors
+///
+/// Fails on malformed input, on a machine [`solve_machine`] gives up on,
+/// and on a solution that does not pass [`simulate`].
+pub fn breakdown(input: &str) -> miette::Result<String> {
+    let mut out = String::new();
+    let mut total_presses = 0;
+
+    for (i, machine) in parse::lines(input, parse_line)?.iter().enumerate() {
+        let Some(solution) = solve_machine(machine).into_diagnostic()? else {
+            out.push_str(&format!("machine {}: no solution\n", i + 1));
+            continue;
+        };
+        simulate(machine, &solution.presses).into_diagnostic()?;
+        let buttons: Vec<_> = solution
+            .presses
+            .iter()
+            .map(|&b| lit(machine.buttons[b]))
+            .collect();
+        out.push_str(&format!(
+            "machine {}: {} presses: {}\n",
+            i + 1,
+            solution.total,
+            buttons.join(" ")
+        ));
+        total_presses += solution.total;
     }
-    None
+
+    out.push_str(&format!("total: {total_presses}\n"));
+    Ok(out)
+}
+
+/// Breadth-first search over the light states, kept to check
+/// [`solve_machine`] against.
+#[cfg(test)]
+fn solve_machine_bfs(machine: &Machine) -> Option<usize> {
+    let path = bfs(
+        0u32,
+        |&state| machine.buttons.iter().map(move |button| state ^ button),
+        |&state| state == machine.target_state,
+    )?;
+    Some(path.cost)
 }
 
 #[instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
     let mut total_presses = 0;
 
-    for line in input.lines() {
-        if let Some(machine) = parse_line(line) {
-            match solve_machine(&machine) {
-                Some(presses) => total_presses += presses,
-                None => {
-                    info!("No solution found for machine: {:?}", machine);
-                }
+    for machine in parse::lines(input, parse_line)? {
+        match solve_machine(&machine).into_diagnostic()? {
+            Some(solution) => {
+                simulate(&machine, &solution.presses).into_diagnostic()?;
+                total_presses += solution.total;
+            }
+            None => {
+                info!("No solution found for machine: {:?}", machine);
             }
         }
     }
@@ -98,31 +225,34 @@ pub fn process(input: &str) -> miette::Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test_log::test]
-    fn test_parse_light_pattern() {
+    fn test_parse_light_pattern() -> miette::Result<()> {
         // Index 0: ., Index 1: #, Index 2: #, Index 3: .
         // Value = 2^1 + 2^2 = 6
-        assert_eq!(parse_light_pattern("[.##.]"), 6);
+        assert_eq!(parse_light_pattern("[.##.]")?, 6);
 
         // Index 0: #
         // Value = 2^0 = 1
-        assert_eq!(parse_light_pattern("[#....]"), 1);
+        assert_eq!(parse_light_pattern("[#....]")?, 1);
 
         // All off
-        assert_eq!(parse_light_pattern("[....]"), 0);
+        assert_eq!(parse_light_pattern("[....]")?, 0);
+        Ok(())
     }
 
     #[test_log::test]
-    fn test_parse_buttons() {
+    fn test_parse_buttons() -> miette::Result<()> {
         let input = "(1,3) (2)";
-        let buttons = parse_buttons(input);
+        let buttons = parse_buttons(input)?;
 
         assert_eq!(buttons.len(), 2);
         // bits 1 and 3 -> 2^1 + 2^3 = 10
         assert_eq!(buttons[0], 10);
         // bits 2 -> 2^2 = 4
         assert_eq!(buttons[1], 4);
+        Ok(())
     }
 
     #[test_log::test]
@@ -135,6 +265,25 @@ mod tests {
         assert_eq!(machine.buttons[1], 10); // (1,3) -> 10
     }
 
+    #[test_log::test]
+    fn test_parse_line_errors_point_into_the_line() {
+        let err = parse_line("[.##.] (3) 1,3) {3}").expect_err("unopened button");
+        assert_eq!(err.message(), "expected `(`");
+        assert_eq!(err.span(), (11, 1).into());
+
+        let err = parse_line("[.#x.] (3) {3}").expect_err("bad light");
+        assert_eq!(err.message(), "unexpected light");
+        assert_eq!(err.span(), (3, 1).into());
+
+        let err = parse_line("[.##.] (3) (1,x) {3}").expect_err("bad index");
+        assert_eq!(err.message(), "invalid integer `x`");
+        assert_eq!(err.span(), (14, 1).into());
+
+        let err = parse_line("[.##.] (3) (1,3)").expect_err("no joltages");
+        assert_eq!(err.message(), "expected `{`");
+        assert_eq!(err.span(), (11, 1).into());
+    }
+
     #[test_log::test]
     fn test_solve_simple_one_step() {
         // Target: 4 (binary 100, index 2 on)
@@ -144,7 +293,7 @@ mod tests {
             target_state: 4,
             buttons: vec![4],
         };
-        assert_eq!(solve_machine(&machine), Some(1));
+        assert_eq!(solve_machine(&machine).unwrap().map(|s| s.total), Some(1));
     }
 
     #[test_log::test]
@@ -157,7 +306,7 @@ mod tests {
             target_state: 6,
             buttons: vec![2, 4],
         };
-        assert_eq!(solve_machine(&machine), Some(2));
+        assert_eq!(solve_machine(&machine).unwrap().map(|s| s.total), Some(2));
     }
 
     #[test_log::test]
@@ -170,7 +319,7 @@ mod tests {
             target_state: 1,
             buttons: vec![3, 2],
         };
-        assert_eq!(solve_machine(&machine), Some(2));
+        assert_eq!(solve_machine(&machine).unwrap().map(|s| s.total), Some(2));
     }
 
     #[test_log::test]
@@ -181,7 +330,7 @@ mod tests {
             target_state: 0,
             buttons: vec![1, 2, 4],
         };
-        assert_eq!(solve_machine(&machine), Some(0));
+        assert_eq!(solve_machine(&machine).unwrap().map(|s| s.total), Some(0));
     }
 
     #[test_log::test]
@@ -193,7 +342,36 @@ mod tests {
             target_state: 1,
             buttons: vec![2],
         };
-        assert_eq!(solve_machine(&machine), None);
+        assert_eq!(solve_machine(&machine), Ok(None));
+    }
+
+    #[test_log::test]
+    fn test_simulate() {
+        let machine = parse_line("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3}").expect("valid");
+        assert_eq!(simulate(&machine, &[4, 5]), Ok(()));
+        assert_eq!(simulate(&machine, &[1, 3, 5, 5]), Ok(()));
+        assert_eq!(
+            simulate(&machine, &[0]),
+            Err(SimulateError::Mismatch {
+                expected: "(1,2)".into(),
+                actual: "(3)".into(),
+            })
+        );
+        assert_eq!(
+            simulate(&machine, &[6]),
+            Err(SimulateError::UnknownButton(6))
+        );
+    }
+
+    #[test_log::test]
+    fn test_breakdown() -> miette::Result<()> {
+        let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[#.] (1) {1}";
+        assert_eq!(
+            breakdown(input)?,
+            "machine 1: 2 presses: (1,3) (2,3)\nmachine 2: no solution\ntotal: 2\n"
+        );
+        Ok(())
     }
 
     #[test_log::test]
@@ -204,4 +382,27 @@ mod tests {
         assert_eq!("7", process(input)?);
         Ok(())
     }
+
+    fn machine() -> impl Strategy<Value = Machine> {
+        (1..=10u32).prop_flat_map(|lights| {
+            let mask = (1_u32 << lights) - 1;
+            (0..=mask, prop::collection::vec(0..=mask, 0..8)).prop_map(|(target_state, buttons)| {
+                Machine {
+                    target_state,
+                    buttons,
+                }
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_solve_matches_bfs(machine in machine()) {
+            let solution = solve_machine(&machine).expect("small nullity");
+            prop_assert_eq!(solution.as_ref().map(|s| s.total), solve_machine_bfs(&machine));
+            if let Some(solution) = solution {
+                prop_assert_eq!(simulate(&machine, &solution.presses), Ok(()));
+            }
+        }
+    }
 }
@@ -1,266 +1,169 @@
+use common::ilp::{IlpError, Problem, Relation};
+use common::parse::{self, Delim, ParseError};
+use miette::IntoDiagnostic;
 use tracing::instrument;
 
+use crate::part1::{SimulateError, Solution, split_line};
+
 #[derive(Debug)]
 struct MachinePart2 {
     target_state: Vec<u32>,
     buttons: Vec<Vec<usize>>,
 }
 
-fn parse_joltage(input: &str) -> Vec<u32> {
-    let content = input
-        .trim()
-        .strip_prefix('{')
-        .and_then(|s| s.strip_suffix('}'))
-        .expect("Invalid joltage format! Missing curly braces.");
-    content
-        .split(',')
-        .map(|num_str| {
-            num_str
-                .trim()
-                .parse::<u32>()
-                .expect("Should be a valid number")
-        })
-        .collect()
+fn parse_joltage(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse::delimited(input, Delim::Brace, |inner| {
+        parse::comma_list(inner, parse::int)
+    })
 }
 
-fn parse_buttons_p2(input: &str) -> Vec<Vec<usize>> {
-    input
-        .split_whitespace()
-        .map(|token| {
-            let inner = token
-                .strip_prefix('(')
-                .and_then(|s| s.strip_suffix(')'))
-                .expect("Invalid button format!");
-            inner
-                .split(',')
-                .map(|num_str| {
-                    num_str
-                        .trim()
-                        .parse::<usize>()
-                        .expect("Should be a valid number")
-                })
-                .collect()
+fn parse_buttons_p2(input: &str) -> Result<Vec<Vec<usize>>, ParseError> {
+    parse::whitespace_list(input, |token| {
+        parse::delimited(token, Delim::Paren, |inner| {
+            parse::comma_list(inner, parse::int)
         })
-        .collect()
+    })
 }
 
-fn parse_line_p2(line: &str) -> Option<MachinePart2> {
-    let target_end = line.find(']')?;
-    let joltage_start = line.find('{')?;
-
-    if target_end >= joltage_start {
-        return None;
+fn parse_line_p2(line: &str) -> Result<MachinePart2, ParseError> {
+    let (_, buttons_chunk, joltage_str) = split_line(line)?;
+    let target_state = parse_joltage(joltage_str).map_err(|e| e.within(line, joltage_str))?;
+    let buttons = parse_buttons_p2(buttons_chunk).map_err(|e| e.within(line, buttons_chunk))?;
+
+    if let Some((token, index)) =
+        buttons_chunk
+            .split_whitespace()
+            .zip(&buttons)
+            .find_map(|(token, button)| {
+                let index = button.iter().find(|&&i| i >= target_state.len())?;
+                Some((token, index))
+            })
+    {
+        return Err(
+            ParseError::at(line, token, format!("button moves counter {index}"))
+                .with_label(format!("only {} counters", target_state.len())),
+        );
     }
 
-    let buttons_chunk = line[(target_end + 1)..joltage_start].trim();
-    let joltage_str = line[joltage_start..].trim();
-
-    Some(MachinePart2 {
-        target_state: parse_joltage(joltage_str),
-        buttons: parse_buttons_p2(buttons_chunk),
+    Ok(MachinePart2 {
+        target_state,
+        buttons,
     })
 }
 
-// Struct to hold static context for the recursive solver
-struct SolverContext<'a> {
-    matrix: &'a [Vec<f64>],
-    col_to_pivot: &'a [Option<usize>],
-    bounds: &'a [usize],
-    free_vars: &'a [usize],
-}
-
-fn solve_machine_p2(machine: &MachinePart2) -> Option<usize> {
-    let num_eq = machine.target_state.len(); // Number of equations (dimensions)
-    let num_vars = machine.buttons.len(); // Number of variables (buttons)
-
-    // 1. Build Augmented Matrix [A | b]
-    // Use f64 for Gaussian elimination
-    let mut matrix = vec![vec![0.0; num_vars + 1]; num_eq];
-
-    // Fill coefficient matrix A
-    for (col, btn_indices) in machine.buttons.iter().enumerate() {
-        for &row in btn_indices {
-            if row < num_eq {
-                matrix[row][col] = 1.0;
-            }
-        }
+/// Minimise the total presses, with one equation per counter: the presses
+/// of every button that moves it must add up to its target. `None` means no
+/// combination of presses reaches the targets.
+fn solve_machine_p2(machine: &MachinePart2) -> Result<Option<Solution>, IlpError> {
+    let num_vars = machine.buttons.len();
+    let mut problem = Problem::minimize(vec![1; num_vars]);
+    for (counter, &target) in machine.target_state.iter().enumerate() {
+        let coeffs = machine
+            .buttons
+            .iter()
+            .map(|btn_indices| i64::from(btn_indices.contains(&counter)))
+            .collect();
+        problem = problem.subject_to(coeffs, Relation::Eq, target.into());
     }
-
-    // Fill constant vector b (Target state)
-    for (row, &val) in machine.target_state.iter().enumerate() {
-        matrix[row][num_vars] = val as f64;
+    // A button can't be pressed more often than the smallest target among
+    // the counters it moves, which keeps branch and bound finite.
+    for (i, button) in machine.buttons.iter().enumerate() {
+        let bound = button
+            .iter()
+            .map(|&counter| machine.target_state[counter])
+            .min()
+            .unwrap_or(0);
+        problem = problem.bounded(i, 0, Some(bound.into()));
     }
 
-    // 2. Gaussian Elimination (Convert to RREF)
-    let mut pivot_row = 0;
-    let mut col_to_pivot = vec![None; num_vars]; // Maps variable column to its pivot row
-    let mut free_vars = Vec::new(); // Variables without pivots (free variables)
-
-    for col in 0..num_vars {
-        if pivot_row >= num_eq {
-            free_vars.push(col);
-            continue;
-        }
-
-        // Find Pivot (Partial Pivoting)
-        let mut max_row = pivot_row;
-        for r in (pivot_row + 1)..num_eq {
-            if matrix[r][col].abs() > matrix[max_row][col].abs() {
-                max_row = r;
-            }
-        }
-
-        // If the column is effectively zero, it's a free variable
-        if matrix[max_row][col].abs() < 1e-9 {
-            free_vars.push(col);
-            continue;
-        }
-
-        // Swap rows
-        matrix.swap(pivot_row, max_row);
-
-        // Normalize Pivot Row
-        let pivot_val = matrix[pivot_row][col];
-        for val in &mut matrix[pivot_row][col..=num_vars] {
-            *val /= pivot_val;
-        }
-
-        // --- FIXED: Use a clone of the pivot row to satisfy borrow checker and Clippy ---
-        // This allows us to use zip() for clean iteration without range loops.
-        // Cloning a small vector of f64s is very cheap.
-        let pivot_row_vals = matrix[pivot_row].clone();
-
-        // Eliminate other rows
-        for (i, row_i) in matrix.iter_mut().enumerate() {
-            if i != pivot_row {
-                let factor = row_i[col];
-                if factor.abs() > 1e-9 {
-                    // Optimized subtraction using zip
-                    // Iterating over the relevant slice of both rows
-                    let target_slice = &mut row_i[col..=num_vars];
-                    let pivot_slice = &pivot_row_vals[col..=num_vars];
+    let solution = match problem.solve() {
+        Ok(solution) => solution,
+        Err(IlpError::Infeasible) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let count = |n: i64| usize::try_from(n).expect("presses are at least zero");
+    Ok(Some(Solution {
+        presses: solution.values.into_iter().map(count).collect(),
+        total: count(solution.objective),
+    }))
+}
 
-                    for (target, &pivot) in target_slice.iter_mut().zip(pivot_slice) {
-                        *target -= factor * pivot;
-                    }
-                }
-            }
-        }
+/// Counters in the input's joltage notation, like `{3,5,4,7}`.
+fn counters(values: impl IntoIterator<Item = impl ToString>) -> String {
+    let values: Vec<_> = values.into_iter().map(|v| v.to_string()).collect();
+    format!("{{{}}}", values.join(","))
+}
 
-        col_to_pivot[col] = Some(pivot_row);
-        pivot_row += 1;
+/// Presses each button the given number of times from all counters at zero
+/// and checks that every counter ends on its target.
+fn simulate(machine: &MachinePart2, presses: &[usize]) -> Result<(), SimulateError> {
+    if let Some(button) = (machine.buttons.len()..presses.len()).find(|&b| presses[b] > 0) {
+        return Err(SimulateError::UnknownButton(button));
     }
-
-    // 3. Check for inconsistency (0 = non-zero)
-    for row in matrix.iter().skip(pivot_row) {
-        if row[num_vars].abs() > 1e-4 {
-            return None; // System has no solution
+    let mut state = vec![0; machine.target_state.len()];
+    for (button, &times) in machine.buttons.iter().zip(presses) {
+        for &counter in button {
+            state[counter] += times;
         }
     }
-
-    // 4. Calculate Upper Bounds for each button
-    let mut bounds = vec![usize::MAX; num_vars];
-    for (col, btn_indices) in machine.buttons.iter().enumerate() {
-        for &row in btn_indices {
-            if row < num_eq {
-                let limit = machine.target_state[row] as usize;
-                if limit < bounds[col] {
-                    bounds[col] = limit;
-                }
-            }
-        }
+    let expected = machine.target_state.iter().map(|&t| t as usize);
+    if state.iter().copied().eq(expected) {
+        Ok(())
+    } else {
+        Err(SimulateError::Mismatch {
+            expected: counters(&machine.target_state),
+            actual: counters(&state),
+        })
     }
-
-    // 5. Recursively find the best integer solution
-    let mut min_total_presses = None;
-    let mut free_vals = vec![0; free_vars.len()];
-
-    let ctx = SolverContext {
-        matrix: &matrix,
-        col_to_pivot: &col_to_pivot,
-        bounds: &bounds,
-        free_vars: &free_vars,
-    };
-
-    solve_recursive(0, &mut free_vals, &ctx, &mut min_total_presses);
-
-    min_total_presses
 }
 
-fn solve_recursive(
-    idx: usize,
-    free_vals: &mut Vec<usize>,
-    ctx: &SolverContext,
-    min_total: &mut Option<usize>,
-) {
-    // Base case: All free variables are assigned
-    if idx == ctx.free_vars.len() {
-        let mut current_total = 0;
-
-        // Sum up free variables
-        for &v in free_vals.iter() {
-            current_total += v;
-        }
-
-        // Calculate Pivot Variables (Dependent variables)
-        // x_pivot = Constant - sum(coeff * x_free)
-        // FIXED: Removed unused 'col' variable by only iterating over values
-        for &maybe_row in ctx.col_to_pivot.iter() {
-            if let Some(row) = maybe_row {
-                let mut val = ctx.matrix[row][ctx.matrix[0].len() - 1]; // Constant term
-
-                for (i, &fv_idx) in ctx.free_vars.iter().enumerate() {
-                    val -= ctx.matrix[row][fv_idx] * (free_vals[i] as f64);
-                }
-
-                // Check if the result is a valid non-negative integer
-                if val < -1e-4 {
-                    return;
-                } // Cannot be negative
-                let rounded = val.round();
-                if (val - rounded).abs() > 1e-4 {
-                    return;
-                } // Must be an integer
-
-                current_total += rounded as usize;
-            }
-        }
-
-        // Update minimum total
-        if min_total.is_none_or(|min| current_total < min) {
-            *min_total = Some(current_total);
-        }
-        return;
-    }
-
-    // Recursive step: Try values for the current free variable
-    let fv_col = ctx.free_vars[idx];
-    let limit = ctx.bounds[fv_col];
-
-    // Pruning: Check if current sum already exceeds known minimum
-    let current_free_sum: usize = free_vals.iter().take(idx).sum();
+/// One line per machine listing how often each button is pressed, for the
+/// binary's `--verbose` flag.
+///
+/// # Errors
+///
+/// Fails on malformed input and on a solution that does not pass
+/// [`simulate`].
+pub fn breakdown(input: &str) -> miette::Result<String> {
+    let mut out = String::new();
+    let mut total_presses = 0;
 
-    if min_total.is_some_and(|min| current_free_sum >= min) {
-        return;
+    for (i, machine) in parse::lines(input, parse_line_p2)?.iter().enumerate() {
+        let Some(solution) = solve_machine_p2(machine).into_diagnostic()? else {
+            out.push_str(&format!("machine {}: no solution\n", i + 1));
+            continue;
+        };
+        simulate(machine, &solution.presses).into_diagnostic()?;
+        let buttons: Vec<_> = machine
+            .buttons
+            .iter()
+            .zip(&solution.presses)
+            .filter(|&(_, &times)| times > 0)
+            .map(|(button, times)| {
+                let lights: Vec<_> = button.iter().map(ToString::to_string).collect();
+                format!("({})x{times}", lights.join(","))
+            })
+            .collect();
+        out.push_str(&format!(
+            "machine {}: {} presses: {}\n",
+            i + 1,
+            solution.total,
+            buttons.join(" ")
+        ));
+        total_presses += solution.total;
     }
 
-    for val in 0..=limit {
-        free_vals[idx] = val;
-        solve_recursive(idx + 1, free_vals, ctx, min_total);
-    }
+    out.push_str(&format!("total: {total_presses}\n"));
+    Ok(out)
 }
 
 #[instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
     let mut total_presses = 0;
-    for line in input.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-        if let Some(machine) = parse_line_p2(line)
-            && let Some(p) = solve_machine_p2(&machine)
-        {
-            total_presses += p;
+    for machine in parse::lines(input, parse_line_p2)? {
+        if let Some(solution) = solve_machine_p2(&machine).into_diagnostic()? {
+            simulate(&machine, &solution.presses).into_diagnostic()?;
+            total_presses += solution.total;
         }
     }
     Ok(total_presses.to_string())
@@ -269,20 +172,35 @@ pub fn process(input: &str) -> miette::Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::linalg;
+    use proptest::prelude::*;
 
     #[test_log::test]
-    fn test_parse_joltage() {
-        assert_eq!(parse_joltage("{3,5,4,7}"), vec![3, 5, 4, 7]);
-        assert_eq!(parse_joltage("{10,11}"), vec![10, 11]);
+    fn test_parse_joltage() -> miette::Result<()> {
+        assert_eq!(parse_joltage("{3,5,4,7}")?, vec![3, 5, 4, 7]);
+        assert_eq!(parse_joltage("{10,11}")?, vec![10, 11]);
+        Ok(())
     }
 
     #[test_log::test]
-    fn test_parse_buttons_p2() {
+    fn test_parse_buttons_p2() -> miette::Result<()> {
         // (1,3) (2)
-        let res = parse_buttons_p2("(1,3) (2)");
+        let res = parse_buttons_p2("(1,3) (2)")?;
         assert_eq!(res.len(), 2);
         assert_eq!(res[0], vec![1, 3]);
         assert_eq!(res[1], vec![2]);
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_parse_line_p2_errors() {
+        let err = parse_line_p2("[.#] (0) {3,4").expect_err("unclosed joltages");
+        assert_eq!(err.message(), "unclosed `{`");
+        assert_eq!(err.span(), (9, 1).into());
+
+        let err = parse_line_p2("[.#] (0) (2) {3,4}").expect_err("no counter 2");
+        assert_eq!(err.message(), "button moves counter 2");
+        assert_eq!(err.span(), (9, 3).into());
     }
 
     #[test_log::test]
@@ -302,7 +220,10 @@ mod tests {
                 vec![0, 1],
             ],
         };
-        assert_eq!(solve_machine_p2(&machine), Some(10));
+        let solution = solve_machine_p2(&machine).unwrap().expect("solvable");
+        assert_eq!(solution.total, 10);
+        assert_eq!(solution.presses.iter().sum::<usize>(), 10);
+        assert_eq!(simulate(&machine, &solution.presses), Ok(()));
     }
 
     #[test_log::test]
@@ -321,7 +242,44 @@ mod tests {
                 vec![1, 2, 3, 4],
             ],
         };
-        assert_eq!(solve_machine_p2(&machine), Some(12));
+        let solution = solve_machine_p2(&machine).unwrap().expect("solvable");
+        assert_eq!(solution.total, 12);
+        assert_eq!(simulate(&machine, &solution.presses), Ok(()));
+    }
+
+    #[test_log::test]
+    fn test_unreachable_targets_have_no_solution() {
+        // Both counters move together, so they can't end on different targets.
+        let machine = parse_line_p2("[..] (0,1) {1,2}").expect("valid");
+        assert_eq!(solve_machine_p2(&machine), Ok(None));
+    }
+
+    #[test_log::test]
+    fn test_simulate() {
+        let machine = parse_line_p2("[.#] (0) (0,1) {3,2}").expect("valid");
+        assert_eq!(simulate(&machine, &[1, 2]), Ok(()));
+        assert_eq!(
+            simulate(&machine, &[2, 1]),
+            Err(SimulateError::Mismatch {
+                expected: "{3,2}".into(),
+                actual: "{3,1}".into(),
+            })
+        );
+        assert_eq!(
+            simulate(&machine, &[1, 2, 1]),
+            Err(SimulateError::UnknownButton(2))
+        );
+    }
+
+    #[test_log::test]
+    fn test_breakdown() -> miette::Result<()> {
+        let input = "[.#] (0) (0,1) {3,2}
+[.#] (0) {0,1}";
+        assert_eq!(
+            breakdown(input)?,
+            "machine 1: 3 presses: (0)x1 (0,1)x2\nmachine 2: no solution\ntotal: 3\n"
+        );
+        Ok(())
     }
 
     #[test_log::test]
@@ -332,4 +290,47 @@ mod tests {
         assert_eq!("33", process(input)?);
         Ok(())
     }
+
+    fn machine() -> impl Strategy<Value = MachinePart2> {
+        (1..=5usize).prop_flat_map(|counters| {
+            let button = prop::collection::vec(any::<bool>(), counters)
+                .prop_map(|picks| (0..picks.len()).filter(|&counter| picks[counter]).collect());
+            (
+                prop::collection::vec(0..12u32, counters),
+                prop::collection::vec(button, 1..=6),
+            )
+                .prop_map(|(target_state, buttons)| MachinePart2 {
+                    target_state,
+                    buttons,
+                })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_solve_matches_linalg_oracle(machine in machine()) {
+            let a: Vec<Vec<i64>> = (0..machine.target_state.len())
+                .map(|counter| {
+                    machine
+                        .buttons
+                        .iter()
+                        .map(|button| i64::from(button.contains(&counter)))
+                        .collect()
+                })
+                .collect();
+            let b: Vec<i64> = machine.target_state.iter().map(|&t| t.into()).collect();
+            let upper: Vec<i64> = machine
+                .buttons
+                .iter()
+                .map(|button| button.iter().map(|&counter| b[counter]).min().unwrap_or(0))
+                .collect();
+            let expected = linalg::min_nonnegative(&a, &b, &upper).map(|m| m.total as usize);
+
+            let solution = solve_machine_p2(&machine).expect("search finishes");
+            prop_assert_eq!(solution.as_ref().map(|s| s.total), expected);
+            if let Some(solution) = solution {
+                prop_assert_eq!(simulate(&machine, &solution.presses), Ok(()));
+            }
+        }
+    }
 }
@@ -1,3 +1,4 @@
+use common::input::Puzzle;
 use day_11::{part1, part2};
 use divan::Bencher;
 
@@ -7,14 +8,16 @@ fn main() {
 
 #[divan::bench]
 fn part1_bench(bencher: Bencher) {
+    let input = Puzzle::new(2025, 11, 1).load().expect("puzzle input");
     bencher
-        .with_inputs(|| include_str!("../input1.txt"))
-        .bench_values(|s| part1::process(s));
+        .with_inputs(|| input.as_str())
+        .bench_values(part1::process);
 }
 
 #[divan::bench]
 fn part2_bench(bencher: Bencher) {
+    let input = Puzzle::new(2025, 11, 2).load().expect("puzzle input");
     bencher
-        .with_inputs(|| include_str!("../input2.txt"))
-        .bench_values(|s| part2::process(s));
+        .with_inputs(|| input.as_str())
+        .bench_values(part2::process);
 }
@@ -1,52 +1,61 @@
-use std::collections::HashMap;
-
+use common::graph::{Digraph, GraphError};
+use common::parse::{self, ParseError};
+use miette::IntoDiagnostic;
+use thiserror::Error;
 use tracing::instrument;
 
-pub type Graph = HashMap<String, Vec<String>>;
+/// Devices and the outputs each one feeds.
+pub type Graph = Digraph;
 
-pub fn parse_input(input: &str) -> Graph {
-    input
-        .lines()
-        .filter_map(|line| {
-            let (source, dest) = line.split_once(": ")?;
-            let destinations: Vec<String> =
-                dest.split_whitespace().map(|s| s.to_string()).collect();
-            Some((source.to_string(), destinations))
+pub fn parse_input(input: &str) -> Result<Graph, ParseError> {
+    let edges = parse::lines(input, |line| {
+        parse::key_value(line, Ok, Ok).map_err(|err| {
+            err.with_help("each line names a device and its outputs, like `aaa: bbb ccc`")
         })
-        .collect()
+    })?;
+    Ok(Digraph::from_adjacency(
+        edges
+            .into_iter()
+            .map(|(source, dests)| (source, dests.split_whitespace())),
+    ))
 }
 
-fn count_paths<'a>(
-    current: &'a str,
-    graph: &'a Graph,
-    memo: &mut HashMap<&'a str, usize>,
-) -> usize {
-    if current == "out" {
-        return 1;
-    }
-
-    if let Some(&count) = memo.get(current) {
-        return count;
-    }
-
-    let mut total_paths = 0;
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PathError {
+    #[error("the graph has a cycle through `{0}`, so paths can't be counted")]
+    Cycle(String),
+    #[error("`{0}` is listed as a waypoint more than once")]
+    DuplicateWaypoint(String),
+    #[error(transparent)]
+    Graph(GraphError),
+}
 
-    if let Some(children) = graph.get(current) {
-        for child in children {
-            total_paths += count_paths(child.as_str(), graph, memo)
+impl PathError {
+    /// Names the devices a [`GraphError`] refers to by their labels.
+    pub fn new(graph: &Graph, err: GraphError) -> Self {
+        let label = |node| graph.names().label(node).to_string();
+        match err {
+            GraphError::Cycle(node) => Self::Cycle(label(node)),
+            GraphError::DuplicateWaypoint(node) => Self::DuplicateWaypoint(label(node)),
+            err => Self::Graph(err),
         }
     }
+}
 
-    memo.insert(current, total_paths);
-
-    total_paths
+/// Counts the paths from `from` to `out`.
+fn count_paths(graph: &Graph, from: &str) -> Result<u128, PathError> {
+    match (graph.id(from), graph.id("out")) {
+        (Some(from), Some(out)) => graph
+            .count_paths(from, out)
+            .map_err(|err| PathError::new(graph, err)),
+        _ => Ok(0),
+    }
 }
 
 #[instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let graph = parse_input(input);
-    let mut memo = HashMap::new();
-    let result = count_paths("you", &graph, &mut memo);
+    let graph = parse_input(input)?;
+    let result = count_paths(&graph, "you").into_diagnostic()?;
     Ok(result.to_string())
 }
 
@@ -54,47 +63,67 @@ pub fn process(input: &str) -> miette::Result<String> {
 mod tests {
     use super::*;
 
+    fn outputs<'a>(graph: &'a Graph, device: &str) -> Option<Vec<&'a str>> {
+        let id = graph.id(device)?;
+        Some(
+            graph
+                .successors(id)
+                .iter()
+                .map(|&n| graph.names().label(n))
+                .collect(),
+        )
+    }
+
     #[test_log::test]
-    fn test_parse_simple() {
+    fn test_parse_simple() -> miette::Result<()> {
         let input = "aaa: bbb ccc\nbbb: out";
-        let graph = parse_input(input);
-
-        assert_eq!(
-            graph.get("aaa"),
-            Some(&vec!["bbb".to_string(), "ccc".to_string()])
-        );
-        assert_eq!(graph.get("bbb"), Some(&vec!["out".to_string()]));
-        assert!(graph.get("ccc").is_none()); // ccc 沒有定義輸出，應該是 None 或不包含在 Key 中
+        let graph = parse_input(input)?;
+
+        assert_eq!(outputs(&graph, "aaa"), Some(vec!["bbb", "ccc"]));
+        assert_eq!(outputs(&graph, "bbb"), Some(vec!["out"]));
+        assert_eq!(outputs(&graph, "ccc"), Some(vec![])); // ccc 沒有定義輸出
+        assert_eq!(outputs(&graph, "ddd"), None);
+        Ok(())
     }
 
     #[test_log::test]
-    fn test_count_direct_path() {
-        let mut graph = Graph::new();
-        graph.insert("you".to_string(), vec!["out".to_string()]);
+    fn test_parse_rejects_line_without_colon() {
+        let err = parse_input("aaa: bbb\nbbb out").expect_err("no colon");
+        assert_eq!(err.message(), "expected `key: value`");
+        assert_eq!(err.line_col(), (2, 1));
+    }
 
-        let mut memo = HashMap::new();
-        assert_eq!(count_paths("you", &graph, &mut memo), 1);
+    #[test_log::test]
+    fn test_count_direct_path() -> miette::Result<()> {
+        let graph = parse_input("you: out")?;
+        assert_eq!(count_paths(&graph, "you"), Ok(1));
+        Ok(())
     }
 
     #[test_log::test]
-    fn test_count_dead_end() {
-        let mut graph = Graph::new();
-        graph.insert("you".to_string(), vec!["dead".to_string()]);
-        graph.insert("dead".to_string(), vec![]);
+    fn test_count_dead_end() -> miette::Result<()> {
+        let graph = parse_input("you: dead\ndead:\nelse: out")?;
+        assert_eq!(count_paths(&graph, "you"), Ok(0));
+        assert_eq!(count_paths(&graph, "nobody"), Ok(0));
+        Ok(())
+    }
 
-        let mut memo = HashMap::new();
-        assert_eq!(count_paths("you", &graph, &mut memo), 0);
+    #[test_log::test]
+    fn test_diamond_structure() -> miette::Result<()> {
+        let graph = parse_input("you: A B\nA: out\nB: out")?;
+        assert_eq!(count_paths(&graph, "you"), Ok(2));
+        Ok(())
     }
 
     #[test_log::test]
-    fn test_diamond_structure() {
-        let mut graph = Graph::new();
-        graph.insert("you".to_string(), vec!["A".to_string(), "B".to_string()]);
-        graph.insert("A".to_string(), vec!["out".to_string()]);
-        graph.insert("B".to_string(), vec!["out".to_string()]);
-
-        let mut memo = HashMap::new();
-        assert_eq!(count_paths("you", &graph, &mut memo), 2);
+    fn test_cycle_is_named_by_label() -> miette::Result<()> {
+        let graph = parse_input(
+            "you: loop
+loop: you out",
+        )?;
+        let err = count_paths(&graph, "you").expect_err("cycle");
+        assert!(matches!(&err, PathError::Cycle(node) if node == "you" || node == "loop"));
+        Ok(())
     }
 
     #[test_log::test]
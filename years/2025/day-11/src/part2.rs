@@ -1,76 +1,96 @@
-use std::collections::HashMap;
-
+use miette::IntoDiagnostic;
 use tracing::instrument;
 
-use crate::part1::{Graph, parse_input};
-
-fn count_paths_between<'a>(
-    current: &'a str,
-    target: &'a str,
-    graph: &'a Graph,
-    memo: &mut HashMap<&'a str, usize>,
-) -> usize {
-    if current == target {
-        return 1;
-    }
-    if current == "out" && target != "out" {
-        return 0;
-    }
-    if let Some(&count) = memo.get(current) {
-        return count;
-    }
-
-    let mut total_paths = 0;
-
-    if let Some(children) = graph.get(current) {
-        for child in children {
-            total_paths += count_paths_between(child, target, graph, memo)
-        }
-    }
-
-    memo.insert(current, total_paths);
-    total_paths
+use crate::part1::{Graph, PathError, parse_input};
+
+/// Counts the paths from `start` to `end` that pass through every one of
+/// `waypoints`, in the given order if `ordered` is set. Devices missing
+/// from the graph have no paths through them.
+pub fn count_paths_via(
+    graph: &Graph,
+    start: &str,
+    end: &str,
+    waypoints: &[&str],
+    ordered: bool,
+) -> Result<u128, PathError> {
+    let (Some(start), Some(end)) = (graph.id(start), graph.id(end)) else {
+        return Ok(0);
+    };
+    let Some(waypoints) = waypoints
+        .iter()
+        .map(|w| graph.id(w))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(0);
+    };
+    graph
+        .count_paths_via(start, end, &waypoints, ordered)
+        .map_err(|err| PathError::new(graph, err))
 }
 
 #[instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let graph = parse_input(input);
-    let count = |start, end| {
-        let mut memo = HashMap::new();
-        count_paths_between(start, end, &graph, &mut memo)
-    };
-
-    let p_svr_dac = count("svr", "dac");
-    let p_dac_fft = count("dac", "fft");
-    let p_fft_out = count("fft", "out");
-    let p_svr_fft = count("svr", "fft");
-    let p_fft_dac = count("fft", "dac");
-    let p_dac_out = count("dac", "out");
-
-    let total = (p_svr_dac * p_dac_fft * p_fft_out) + (p_svr_fft * p_fft_dac * p_dac_out);
-
+    let graph = parse_input(input)?;
+    let total = count_paths_via(&graph, "svr", "out", &["dac", "fft"], false).into_diagnostic()?;
     Ok(total.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::graph::{GraphError, MAX_WAYPOINTS};
 
     #[test]
     fn test_count_paths_between_nodes() {
         // A -> B -> C
-        let mut graph = Graph::new();
-        graph.insert("A".to_string(), vec!["B".to_string()]);
-        graph.insert("B".to_string(), vec!["C".to_string()]);
+        let graph = parse_input("A: B\nB: C").expect("valid graph");
 
-        let mut memo = HashMap::new();
-        assert_eq!(count_paths_between("A", "C", &graph, &mut memo), 1);
+        assert_eq!(count_paths_via(&graph, "A", "C", &[], false), Ok(1));
+        assert_eq!(count_paths_via(&graph, "A", "B", &[], false), Ok(1));
+        assert_eq!(count_paths_via(&graph, "A", "Z", &[], false), Ok(0)); // 不存在
+    }
 
-        memo.clear();
-        assert_eq!(count_paths_between("A", "B", &graph, &mut memo), 1);
+    #[test_log::test]
+    fn test_ordered_and_unordered_waypoints() -> miette::Result<()> {
+        // s-a-b-t, s-a-t, s-b-t and s-c-t.
+        let graph = parse_input("s: a b c\na: b t\nb: t\nc: t")?;
+
+        assert_eq!(count_paths_via(&graph, "s", "t", &[], false), Ok(4));
+        assert_eq!(count_paths_via(&graph, "s", "t", &["b"], false), Ok(2));
+        assert_eq!(count_paths_via(&graph, "s", "t", &["b", "a"], false), Ok(1));
+        assert_eq!(count_paths_via(&graph, "s", "t", &["a", "b"], true), Ok(1));
+        assert_eq!(count_paths_via(&graph, "s", "t", &["b", "a"], true), Ok(0));
+        // The endpoints count as visited too.
+        assert_eq!(count_paths_via(&graph, "s", "t", &["s", "t"], true), Ok(4));
+        assert_eq!(count_paths_via(&graph, "s", "t", &["t", "s"], true), Ok(0));
+        assert_eq!(
+            count_paths_via(&graph, "s", "t", &["a", "b", "a"], false),
+            Err(PathError::DuplicateWaypoint("a".into()))
+        );
+        Ok(())
+    }
 
-        memo.clear();
-        assert_eq!(count_paths_between("A", "Z", &graph, &mut memo), 0); // 不存在
+    #[test_log::test]
+    fn test_cycles_are_reported() -> miette::Result<()> {
+        let graph = parse_input("svr: aaa\naaa: bbb\nbbb: aaa out")?;
+        assert!(matches!(
+            count_paths_via(&graph, "svr", "out", &[], false),
+            Err(PathError::Cycle(node)) if node == "aaa" || node == "bbb"
+        ));
+
+        let waypoints = ["aaa"; MAX_WAYPOINTS + 1];
+        assert_eq!(
+            count_paths_via(&graph, "svr", "aaa", &waypoints, false),
+            Err(PathError::Graph(GraphError::TooManyWaypoints(
+                MAX_WAYPOINTS + 1
+            )))
+        );
+        // A waypoint that isn't a device can't be visited.
+        assert_eq!(
+            count_paths_via(&graph, "aaa", "out", &["zzz"], false),
+            Ok(0)
+        );
+        Ok(())
     }
 
     #[test_log::test]
@@ -1,10 +1,18 @@
-use day_12::part1::process;
+use std::io::IsTerminal;
+
+use common::input::Puzzle;
+use day_12::part1::{process, show};
 use miette::Context;
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
-    let file = include_str!("../../input1.txt");
-    let daily_result = process(file).context("Process part1")?;
-    println!("{daily_result}");
+    let ([draw], file) = Puzzle::new(2025, 12, 1).load_with_flags(["--show"])?;
+    if draw {
+        let drawn = show(&file, std::io::stdout().is_terminal()).context("Show part1")?;
+        print!("{drawn}");
+    } else {
+        let daily_result = process(&file).context("Process part1")?;
+        println!("{daily_result}");
+    }
     Ok(())
 }
@@ -1,85 +1,13 @@
-use std::collections::HashSet;
-
+use common::exact_cover::{BudgetExceeded, ExactCover};
+use common::grid::{Grid, Pos};
+use common::parse::{self, ParseError};
+use common::polyomino::{self, Shape};
+use miette::IntoDiagnostic;
+use thiserror::Error;
 use tracing::instrument;
 
 // --- Data Structures ---
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct Shape {
-    id: usize,
-    // (row, col) offsets relative to top-left (0,0)
-    cells: Vec<(usize, usize)>,
-    height: usize,
-    width: usize,
-}
-
-impl Shape {
-    /// Normalizes coordinates to (0,0) and sorts cells for consistent hashing.
-    fn normalize(id: usize, cells: Vec<(usize, usize)>) -> Self {
-        if cells.is_empty() {
-            return Self {
-                id,
-                cells,
-                height: 0,
-                width: 0,
-            };
-        }
-
-        // 1. Shift to top-left (0,0)
-        let min_r = cells.iter().map(|&(r, _)| r).min().unwrap_or(0);
-        let min_c = cells.iter().map(|&(_, c)| c).min().unwrap_or(0);
-        let mut normalized_cells: Vec<(usize, usize)> =
-            cells.iter().map(|&(r, c)| (r - min_r, c - min_c)).collect();
-
-        // 2. Sort for deduplication
-        normalized_cells.sort_unstable();
-
-        let height = normalized_cells.iter().map(|&(r, _)| r).max().unwrap_or(0) + 1;
-        let width = normalized_cells.iter().map(|&(_, c)| c).max().unwrap_or(0) + 1;
-
-        Self {
-            id,
-            cells: normalized_cells,
-            height,
-            width,
-        }
-    }
-
-    fn rotate(&self) -> Self {
-        let new_cells = self
-            .cells
-            .iter()
-            .map(|&(r, c)| (c, self.height - 1 - r))
-            .collect();
-        Self::normalize(self.id, new_cells)
-    }
-
-    fn horizontal_flip(&self) -> Self {
-        let new_cells = self
-            .cells
-            .iter()
-            .map(|&(r, c)| (r, self.width - 1 - c))
-            .collect();
-        Self::normalize(self.id, new_cells)
-    }
-
-    /// Generates all unique orientations (rotations + flips).
-    fn generate_unique_variations(&self) -> Vec<Shape> {
-        let mut unique = HashSet::new();
-        let mut curr = self.clone();
-
-        for _ in 0..4 {
-            unique.insert(curr.clone());
-            unique.insert(curr.horizontal_flip());
-            curr = curr.rotate();
-        }
-
-        let mut result: Vec<_> = unique.into_iter().collect();
-        result.sort(); // Deterministic order
-        result
-    }
-}
-
 #[derive(Debug)]
 struct RegionTask {
     width: usize,
@@ -90,221 +18,321 @@ struct RegionTask {
 
 // --- Parsing ---
 
-fn parse_shapes(input: &str) -> Vec<Shape> {
-    let mut shapes = Vec::new();
-    let mut current_id = None;
-    let mut current_cells = Vec::new();
-    let mut current_row = 0;
+/// Region lines look like `12x5: 1 0 1 0 2 2`.
+fn is_task_line(line: &str) -> bool {
+    line.split_once(':')
+        .is_some_and(|(size, _)| size.contains('x'))
+}
 
-    for line in input.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+/// The shape blocks come before the first region line.
+fn parse_shapes(input: &str) -> Result<Vec<Shape>, ParseError> {
+    let end = input
+        .lines()
+        .find(|line| is_task_line(line))
+        .and_then(|line| parse::offset_in(input, line))
+        .unwrap_or(input.len());
+    let section = &input[..end];
+    polyomino::parse_shapes(section).map_err(|err| err.within(input, section))
+}
 
-        // Start of a shape block (e.g., "0:")
-        if line.ends_with(':') && !line.contains('x') {
-            if let Some(id) = current_id {
-                shapes.push(Shape::normalize(id, current_cells));
-                current_cells = Vec::new();
-                current_row = 0;
-            }
-            if let Ok(id) = line.trim_end_matches(':').trim().parse::<usize>() {
-                current_id = Some(id);
-            }
-        // Shape grid lines (e.g., "###", "..#")
-        } else if current_id.is_some() && !line.contains(':') && !line.contains('x') {
-            for (c, ch) in line.chars().enumerate() {
-                if ch == '#' {
-                    current_cells.push((current_row, c));
-                }
-            }
-            current_row += 1;
-        // Task lines (e.g., "4x4: ...") - marks end of shape definitions
-        } else if line.contains("x")
-            && let Some(id) = current_id
-        {
-            shapes.push(Shape::normalize(id, current_cells));
-            current_id = None;
-            current_cells = Vec::new();
-        }
-    }
-    // Handle last shape if file ends without tasks
-    if let Some(id) = current_id
-        && !current_cells.is_empty()
-    {
-        shapes.push(Shape::normalize(id, current_cells));
-    }
-    shapes
+fn parse_task(line: &str) -> Result<RegionTask, ParseError> {
+    let ((width, height), counts) = parse::key_value(
+        line,
+        |size| parse::pair(size, 'x', parse::int, parse::int),
+        |counts| parse::whitespace_list(counts, parse::int::<usize>),
+    )?;
+    let requirements = counts
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, count)| count > 0)
+        .collect();
+    Ok(RegionTask {
+        width,
+        height,
+        requirements,
+    })
 }
 
-fn parse_tasks(input: &str) -> Vec<RegionTask> {
+fn parse_tasks(input: &str) -> Result<Vec<RegionTask>, ParseError> {
     input
         .lines()
-        .filter_map(|line| {
-            let line = line.trim();
-            if !line.contains('x') {
-                return None;
-            }
-
-            let (size_str, req_str) = line.split_once(':')?;
-            let (w_str, h_str) = size_str.split_once('x')?;
-            let width = w_str.trim().parse().ok()?;
-            let height = h_str.trim().parse().ok()?;
-
-            let requirements = req_str
-                .split_whitespace()
-                .enumerate()
-                .filter_map(|(id, count_str)| {
-                    let count = count_str.parse::<usize>().ok()?;
-                    if count > 0 { Some((id, count)) } else { None }
-                })
-                .collect();
-
-            Some(RegionTask {
-                width,
-                height,
-                requirements,
-            })
-        })
+        .filter(|line| is_task_line(line))
+        .map(|line| parse_task(line).map_err(|err| err.within(input, line)))
         .collect()
 }
 
 // --- Solver Logic ---
 
-#[inline]
-fn can_place(grid: &[bool], w: usize, shape: &Shape, r: usize, c: usize) -> bool {
-    // 1. Boundary check
-    if r + shape.height > (grid.len() / w) || c + shape.width > w {
-        return false;
-    }
-    // 2. Overlap check
-    for &(dr, dc) in &shape.cells {
-        if grid[(r + dr) * w + (c + dc)] {
-            return false;
-        }
-    }
-    true
+/// Cap on the placements the exact-cover search may try for one region.
+const NODE_BUDGET: u64 = 5_000_000;
+
+/// One present in a packed region: `variant` indexes the shape's
+/// orientations, and `(row, col)` is the top-left of its bounding box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Placement {
+    pub shape: usize,
+    pub variant: usize,
+    pub row: usize,
+    pub col: usize,
 }
 
-#[inline]
-fn toggle_shape(grid: &mut [bool], w: usize, shape: &Shape, r: usize, c: usize, val: bool) {
-    for &(dr, dc) in &shape.cells {
-        grid[(r + dr) * w + (c + dc)] = val;
+/// Packs every required present into the region at once, or returns `None`
+/// if they don't fit.
+///
+/// Regions too small for the presents' total area fail outright, and regions
+/// with a free square box per present (the box fits every orientation) are
+/// laid out box by box. Anything in between is packed as an exact cover: one
+/// primary column per shape, covered once per present of that shape, and
+/// one secondary column per cell, which may stay empty.
+fn solve_region(
+    task: &RegionTask,
+    all_shapes: &[Shape],
+) -> Result<Option<Vec<Placement>>, BudgetExceeded> {
+    let total_cells = task.width * task.height;
+
+    let mut pieces = Vec::new();
+    let mut total_item_area = 0;
+    let mut total_count = 0;
+    let mut side = 0;
+    for &(id, count) in &task.requirements {
+        let Some(base) = all_shapes.iter().find(|s| s.id == id) else {
+            return Ok(None); // Shape missing
+        };
+        total_item_area += base.area() * count;
+        total_count += count;
+        side = side.max(base.side());
+        pieces.push((base, count));
     }
-}
 
-/// Recursive backtracking.
-/// `items` contains indices into `variations_pool` for each item that needs placement.
-fn backtrack(
-    grid: &mut [bool],
-    w: usize,
-    h: usize,
-    items: &[usize], // Flattened list of shapes to place
-    item_idx: usize, // Current item index
-    variations_pool: &[Vec<Shape>],
-    last_pos: usize, // For symmetry breaking
-) -> bool {
-    // Base Case: All items placed
-    if item_idx == items.len() {
-        return true;
+    if total_item_area > total_cells {
+        return Ok(None);
+    }
+    if side == 0 || (task.width / side) * (task.height / side) >= total_count {
+        return Ok(Some(box_layout(&pieces, task.width / side.max(1), side)));
     }
 
-    let pool_idx = items[item_idx];
-    let variants = &variations_pool[pool_idx];
-
-    // Symmetry Breaking:
-    // If this item is the same type as the previous one, strictly start searching
-    // from where the last one was placed. This avoids N! redundant permutations.
-    let start_pos = if item_idx > 0 && items[item_idx] == items[item_idx - 1] {
-        last_pos
-    } else {
-        0
-    };
-
-    let total_cells = w * h;
-
-    // Try to place the shape at every valid position in the grid
-    for pos in start_pos..total_cells {
-        let r = pos / w;
-        let c = pos % w;
-
-        for variant in variants {
-            if can_place(grid, w, variant, r, c) {
-                toggle_shape(grid, w, variant, r, c, true);
-
-                // Recurse
-                // Pass `pos` as the next `last_pos` for symmetry breaking
-                if backtrack(grid, w, h, items, item_idx + 1, variations_pool, pos) {
-                    return true;
+    let mut cover = ExactCover::new(pieces.len(), total_cells).with_node_budget(NODE_BUDGET);
+    for (piece, &(_, count)) in pieces.iter().enumerate() {
+        cover.set_multiplicity(piece, count);
+    }
+    let mut placements = Vec::new();
+    let mut columns = Vec::new();
+    for (piece, (shape, _)) in pieces.iter().enumerate() {
+        for (variant, orientation) in shape.orientations().iter().enumerate() {
+            if orientation.height() > task.height || orientation.width() > task.width {
+                continue;
+            }
+            for row in 0..=task.height - orientation.height() {
+                for col in 0..=task.width - orientation.width() {
+                    columns.clear();
+                    columns.push(piece);
+                    columns.extend(
+                        orientation
+                            .cells()
+                            .iter()
+                            .map(|p| pieces.len() + (row + p.row) * task.width + (col + p.col)),
+                    );
+                    cover.add_row(&columns);
+                    placements.push(Placement {
+                        shape: shape.id,
+                        variant,
+                        row,
+                        col,
+                    });
                 }
-
-                toggle_shape(grid, w, variant, r, c, false); // Backtrack
             }
         }
     }
 
-    false
+    Ok(cover.solve()?.map(|rows| {
+        let mut layout: Vec<Placement> = rows.into_iter().map(|r| placements[r]).collect();
+        layout.sort_unstable_by_key(|p| (p.row, p.col));
+        layout
+    }))
 }
 
-fn solve_region(task: &RegionTask, all_shapes: &[Shape]) -> bool {
-    let total_cells = task.width * task.height;
-    let mut grid = vec![false; total_cells];
+/// Puts each present, as drawn, in its own `side`-wide box, `per_row` boxes
+/// to a row.
+fn box_layout(pieces: &[(&Shape, usize)], per_row: usize, side: usize) -> Vec<Placement> {
+    pieces
+        .iter()
+        .flat_map(|&(shape, count)| {
+            let variant = shape
+                .orientations()
+                .iter()
+                .position(|o| o == shape.base())
+                .unwrap_or(0);
+            std::iter::repeat_n((shape.id, variant), count)
+        })
+        .enumerate()
+        .map(|(k, (shape, variant))| Placement {
+            shape,
+            variant,
+            row: k / per_row * side,
+            col: k % per_row * side,
+        })
+        .collect()
+}
 
-    // Data prep for recursion
-    // Tuples: (id, count, area, variations)
-    let mut temp_reqs = Vec::new();
-    let mut total_item_area = 0;
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LayoutError {
+    #[error("piece {piece} uses unknown shape {shape}")]
+    UnknownShape { piece: usize, shape: usize },
+    #[error("piece {piece} uses unknown variant {variant}")]
+    UnknownVariant { piece: usize, variant: usize },
+    #[error("piece {piece} runs off the region")]
+    OutOfBounds { piece: usize },
+    #[error("piece {piece} overlaps an earlier piece")]
+    Overlap { piece: usize },
+    #[error("shape {shape} is placed {found} times, expected {expected}")]
+    WrongCount {
+        shape: usize,
+        expected: usize,
+        found: usize,
+    },
+}
 
-    for &(id, count) in &task.requirements {
-        if let Some(base) = all_shapes.iter().find(|s| s.id == id) {
-            let area = base.cells.len();
-            let vars = base.generate_unique_variations();
-            temp_reqs.push((id, count, area, vars));
-            total_item_area += area * count;
-        } else {
-            return false; // Shape missing
+/// Re-checks a layout cell by cell on a `Grid<bool>`, independently of the
+/// exact-cover solver: every piece is a real orientation, lies inside the
+/// region and clear of the others, and each shape appears exactly as often
+/// as the region asks.
+fn validate(task: &RegionTask, shapes: &[Shape], layout: &[Placement]) -> Result<(), LayoutError> {
+    let mut filled = Grid::new(task.width, task.height, false);
+    for (piece, p) in layout.iter().enumerate() {
+        let shape = shapes
+            .iter()
+            .find(|s| s.id == p.shape)
+            .ok_or(LayoutError::UnknownShape {
+                piece,
+                shape: p.shape,
+            })?;
+        let orientation =
+            shape
+                .orientations()
+                .get(p.variant)
+                .ok_or(LayoutError::UnknownVariant {
+                    piece,
+                    variant: p.variant,
+                })?;
+        if p.row + orientation.height() > task.height || p.col + orientation.width() > task.width {
+            return Err(LayoutError::OutOfBounds { piece });
+        }
+        for cell in orientation.cells() {
+            let taken = filled
+                .get_mut(Pos::new(p.row + cell.row, p.col + cell.col))
+                .ok_or(LayoutError::OutOfBounds { piece })?;
+            if std::mem::replace(taken, true) {
+                return Err(LayoutError::Overlap { piece });
+            }
         }
     }
 
-    // Pruning: Area check
-    if total_item_area > total_cells {
-        return false;
+    let mut shape_ids: Vec<usize> = layout.iter().map(|p| p.shape).collect();
+    shape_ids.extend(task.requirements.iter().map(|&(id, _)| id));
+    shape_ids.sort_unstable();
+    shape_ids.dedup();
+    for shape in shape_ids {
+        let expected = task
+            .requirements
+            .iter()
+            .filter(|&&(id, _)| id == shape)
+            .map(|&(_, count)| count)
+            .sum();
+        let found = layout.iter().filter(|p| p.shape == shape).count();
+        if found != expected {
+            return Err(LayoutError::WrongCount {
+                shape,
+                expected,
+                found,
+            });
+        }
     }
+    Ok(())
+}
+
+const PIECE_LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// ANSI foreground colours, cycled through so neighbouring pieces differ.
+const PIECE_COLOURS: [u8; 12] = [31, 32, 33, 34, 35, 36, 91, 92, 93, 94, 95, 96];
 
-    // Optimization: Sort by size (Largest First)
-    temp_reqs.sort_by(|a, b| b.2.cmp(&a.2));
+/// Draws the region with one letter per piece and `.` for empty cells,
+/// colouring the letters with ANSI escapes if `colour` is set.
+fn render(task: &RegionTask, shapes: &[Shape], layout: &[Placement], colour: bool) -> String {
+    let mut cells: Vec<Option<usize>> = vec![None; task.width * task.height];
+    for (piece, p) in layout.iter().enumerate() {
+        let Some(orientation) = shapes
+            .iter()
+            .find(|s| s.id == p.shape)
+            .and_then(|s| s.orientations().get(p.variant))
+        else {
+            continue;
+        };
+        for cell in orientation.cells() {
+            let (row, col) = (p.row + cell.row, p.col + cell.col);
+            if row < task.height && col < task.width {
+                cells[row * task.width + col] = Some(piece);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for row in cells.chunks(task.width.max(1)) {
+        for &cell in row {
+            match cell {
+                None => out.push('.'),
+                Some(piece) => {
+                    let letter = char::from(PIECE_LETTERS[piece % PIECE_LETTERS.len()]);
+                    if colour {
+                        let code = PIECE_COLOURS[piece % PIECE_COLOURS.len()];
+                        out.push_str(&format!("\x1b[{code}m{letter}\x1b[0m"));
+                    } else {
+                        out.push(letter);
+                    }
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
 
-    // Flatten requirements into a linear list of jobs
-    let mut variations_pool = Vec::new();
-    let mut items_to_place = Vec::new(); // Stores indices pointing to variations_pool
+/// Solves every region and draws the packable ones, for the binary's
+/// `--show` flag.
+///
+/// # Errors
+///
+/// Fails on malformed input, on a region the search gives up on, and on a
+/// layout that does not pass [`validate`].
+pub fn show(input: &str, colour: bool) -> miette::Result<String> {
+    let shapes = parse_shapes(input)?;
+    let tasks = parse_tasks(input)?;
+    let mut out = String::new();
 
-    for (pool_idx, (_, count, _, vars)) in temp_reqs.into_iter().enumerate() {
-        variations_pool.push(vars);
-        for _ in 0..count {
-            items_to_place.push(pool_idx);
+    for task in tasks {
+        let header = format!("{}x{}", task.width, task.height);
+        match solve_region(&task, &shapes).into_diagnostic()? {
+            Some(layout) => {
+                validate(&task, &shapes, &layout).into_diagnostic()?;
+                out.push_str(&format!("{header}: {} presents\n", layout.len()));
+                out.push_str(&render(&task, &shapes, &layout, colour));
+            }
+            None => out.push_str(&format!("{header}: does not fit\n")),
         }
+        out.push('\n');
     }
 
-    backtrack(
-        &mut grid,
-        task.width,
-        task.height,
-        &items_to_place,
-        0,
-        &variations_pool,
-        0,
-    )
+    Ok(out)
 }
 
 #[instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let shapes = parse_shapes(input);
-    let tasks = parse_tasks(input);
+    let shapes = parse_shapes(input)?;
+    let tasks = parse_tasks(input)?;
     let mut success_count = 0;
 
     for task in tasks {
-        if solve_region(&task, &shapes) {
+        if let Some(layout) = solve_region(&task, &shapes).into_diagnostic()? {
+            validate(&task, &shapes, &layout).into_diagnostic()?;
             success_count += 1;
         }
     }
@@ -322,15 +350,15 @@ mod tests {
 ###
 ##.
 ##.";
-        let shapes = parse_shapes(input);
+        let shapes = parse_shapes(input).expect("valid shapes");
         assert_eq!(shapes.len(), 1);
-        let s = &shapes[0];
-        assert_eq!(s.id, 0);
-        assert_eq!(s.width, 3);
-        assert_eq!(s.height, 3);
-        assert!(s.cells.contains(&(0, 0)));
-        assert!(s.cells.contains(&(2, 1)));
-        assert!(!s.cells.contains(&(1, 2)));
+        let s = shapes[0].base();
+        assert_eq!(shapes[0].id, 0);
+        assert_eq!(s.width(), 3);
+        assert_eq!(s.height(), 3);
+        assert!(s.cells().contains(&Pos::new(0, 0)));
+        assert!(s.cells().contains(&Pos::new(2, 1)));
+        assert!(!s.cells().contains(&Pos::new(1, 2)));
     }
 
     #[test_log::test]
@@ -348,33 +376,20 @@ mod tests {
 2:
 #..
 ###
-###";
-        let shapes = parse_shapes(input);
+###
 
-        assert_eq!(shapes.len(), 3);
+4x4: 0 2 0";
+        let shapes = parse_shapes(input).expect("valid shapes");
 
-        // Shape 1 logic verification
-        let s1 = &shapes[1]; // Note: shapes vector index might not match ID if sorted, but here input order is preserved
-        assert_eq!(s1.id, 1);
-
-        // After normalize:
-        // Input:
-        // ..# (0,2) -> min_c=0, min_r=0
-        // .## (1,1), (1,2)
-        // ##. (2,0), (2,1)
-        // Normalized should maintain relative structure.
-
-        assert!(s1.cells.contains(&(0, 2)));
-        assert!(s1.cells.contains(&(1, 1)));
-        assert!(s1.cells.contains(&(1, 2)));
-        assert!(s1.cells.contains(&(2, 0)));
-        assert!(s1.cells.contains(&(2, 1)));
+        assert_eq!(shapes.len(), 3);
+        assert_eq!(shapes[1].id, 1);
+        assert_eq!(shapes[1].base().to_string(), "..#\n.##\n##.");
     }
 
     #[test_log::test]
     fn test_parse_single_task_line() {
         let input = "12x5: 1 0 1 0 2 2";
-        let tasks = parse_tasks(input);
+        let tasks = parse_tasks(input).expect("valid regions");
 
         assert_eq!(tasks.len(), 1);
         let t = &tasks[0];
@@ -394,7 +409,7 @@ mod tests {
     #[test_log::test]
     fn test_parse_multiple_task_lines() {
         let input = "4x4: 0 0 0 0 2 0\n10x10: 5 0";
-        let tasks = parse_tasks(input);
+        let tasks = parse_tasks(input).expect("valid regions");
 
         assert_eq!(tasks.len(), 2);
 
@@ -408,65 +423,135 @@ mod tests {
     }
 
     #[test_log::test]
-    fn test_rotate_shape() {
-        // ##
-        // .#
-        let shape = Shape::normalize(0, vec![(0, 0), (0, 1), (1, 1)]);
-        let rotated = shape.rotate();
-
-        // Original 2x2, rotated is still 2x2
-        assert_eq!(rotated.height, 2);
-        assert_eq!(rotated.width, 2);
-
-        // Rotated logic: (r,c) -> (c, h-1-r)
-        // (0,0) -> (0,1)
-        // (0,1) -> (1,1)
-        // (1,1) -> (1,0)
-        assert!(rotated.cells.contains(&(0, 1)));
-        assert!(rotated.cells.contains(&(1, 1)));
-        assert!(rotated.cells.contains(&(1, 0)));
+    fn test_parse_errors_point_into_the_input() {
+        // Shape-level errors are covered in common::polyomino; these check
+        // that the spans survive splitting the full input.
+        let err = parse_shapes("0:\n###\n\n1: #\n4x4: 1").expect_err("text after the header");
+        assert_eq!(err.line_col(), (4, 4));
+
+        let err = parse_tasks("0:\n###\n\n4x4: 0 2\n4x: 1").expect_err("no height");
+        assert_eq!(err.message(), "expected an integer");
+        assert_eq!(err.line_col(), (5, 3));
+
+        let err = parse_tasks("4x4: 0 -2").expect_err("negative count");
+        assert_eq!(err.line_col(), (1, 8));
     }
 
-    #[test_log::test]
-    fn test_deduplication_square() {
-        // A 2x2 square should only have 1 unique variation
-        let s = Shape::normalize(0, vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
-        let vars = s.generate_unique_variations();
-        assert_eq!(vars.len(), 1);
+    fn c_shape() -> Vec<Shape> {
+        parse_shapes("0:\n###\n#..\n###\n").expect("valid shapes")
+    }
+
+    fn region(width: usize, height: usize, count: usize) -> RegionTask {
+        RegionTask {
+            width,
+            height,
+            requirements: vec![(0, count)],
+        }
     }
 
     #[test_log::test]
-    fn test_horizontal_flip_l_shape() {
-        // #.  (0,0)
-        // #.  (1,0)
-        // ##  (2,0), (2,1)
-        let l_shape = Shape::normalize(0, vec![(0, 0), (1, 0), (2, 0), (2, 1)]);
-        let flipped = l_shape.horizontal_flip();
-
-        // .#  (0,1)
-        // .#  (1,1)
-        // ##  (2,0), (2,1)
-        assert!(flipped.cells.contains(&(0, 1)));
-        assert!(flipped.cells.contains(&(1, 1)));
-        assert!(flipped.cells.contains(&(2, 1)));
-        assert!(flipped.cells.contains(&(2, 0)));
+    fn test_solve_region_shortcuts() {
+        let shapes = c_shape();
+        let solve = |task: &RegionTask| {
+            let layout = solve_region(task, &shapes).expect("within budget");
+            if let Some(layout) = &layout {
+                assert_eq!(validate(task, &shapes, layout), Ok(()));
+            }
+            layout.is_some()
+        };
+
+        // Four 3x3 boxes fit in 6x7 without any search.
+        assert!(solve(&region(6, 7, 4)));
+        // Five presents need 35 cells.
+        assert!(!solve(&region(6, 5, 5)));
+        // Two C shapes interlock in 4x4, which the search has to find.
+        assert!(solve(&region(4, 4, 2)));
+        // Nothing 3 tall fits in 2 rows, however wide.
+        assert!(!solve(&region(8, 2, 2)));
     }
 
     #[test_log::test]
-    fn test_generate_variations_includes_flips() {
-        let l_shape = Shape::normalize(0, vec![(0, 0), (1, 0), (2, 0), (2, 1)]);
-        let vars = l_shape.generate_unique_variations();
+    fn test_render_interlocking_layout() {
+        let shapes = c_shape();
+        let task = region(4, 4, 2);
+        let layout = solve_region(&task, &shapes)
+            .expect("within budget")
+            .expect("fits");
+        let drawn = render(&task, &shapes, &layout, false);
+        assert_eq!(drawn.matches('A').count(), 7);
+        assert_eq!(drawn.matches('B').count(), 7);
+        assert_eq!(drawn.matches('.').count(), 2);
+        assert_eq!(drawn.lines().count(), 4);
+
+        let coloured = render(&task, &shapes, &layout, true);
+        assert!(coloured.contains("\x1b[31mA\x1b[0m"));
+        assert!(coloured.contains("\x1b[32mB\x1b[0m"));
+    }
 
-        let has_j_shape = vars
+    #[test_log::test]
+    fn test_validate_rejects_bad_layouts() {
+        let shapes = c_shape();
+        let task = region(6, 3, 2);
+        let at = |variant, row, col| Placement {
+            shape: 0,
+            variant,
+            row,
+            col,
+        };
+        let base = shapes[0]
+            .orientations()
             .iter()
-            .any(|s| s.cells.contains(&(0, 1)) && s.cells.contains(&(2, 0)));
+            .position(|o| o == shapes[0].base())
+            .expect("base is an orientation");
 
-        assert!(
-            has_j_shape,
-            "Variations should include the horizontally flipped version"
+        assert_eq!(
+            validate(&task, &shapes, &[at(base, 0, 0), at(base, 0, 3)]),
+            Ok(())
+        );
+        assert_eq!(
+            validate(&task, &shapes, &[at(base, 0, 0), at(base, 0, 2)]),
+            Err(LayoutError::Overlap { piece: 1 })
+        );
+        assert_eq!(
+            validate(&task, &shapes, &[at(base, 0, 0), at(base, 1, 3)]),
+            Err(LayoutError::OutOfBounds { piece: 1 })
         );
-        // Note: Unique variations for L-shape is usually 8
-        assert_eq!(vars.len(), 8);
+        assert_eq!(
+            validate(&task, &shapes, &[at(base, 0, 0)]),
+            Err(LayoutError::WrongCount {
+                shape: 0,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            validate(&task, &shapes, &[at(99, 0, 0)]),
+            Err(LayoutError::UnknownVariant {
+                piece: 0,
+                variant: 99
+            })
+        );
+        let stray = Placement {
+            shape: 7,
+            ..at(base, 0, 0)
+        };
+        assert_eq!(
+            validate(&task, &shapes, &[stray]),
+            Err(LayoutError::UnknownShape { piece: 0, shape: 7 })
+        );
+
+        // The cell grid has no width limit, so wide regions validate too.
+        let wide = region(200, 3, 1);
+        assert_eq!(validate(&wide, &shapes, &[at(base, 0, 197)]), Ok(()));
+    }
+
+    #[test_log::test]
+    fn test_show_example() -> miette::Result<()> {
+        let input = "0:\n###\n#..\n###\n\n4x4: 2\n8x2: 2\n";
+        let shown = show(input, false)?;
+        assert!(shown.starts_with("4x4: 2 presents\n"));
+        assert!(shown.contains("8x2: does not fit\n"));
+        Ok(())
     }
 
     #[test_log::test]